@@ -1,4 +1,7 @@
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
+use firm_core::schema::{BuiltinGroup, UnknownFieldPolicy};
+use firm_lang::workspace::WorkspaceOptions;
 use std::path::PathBuf;
 
 use super::query::CliDirection;
@@ -17,6 +20,22 @@ pub struct FirmCli {
     #[arg(short, long, global = true)]
     pub cached: bool,
 
+    /// Treat fields not declared in schemas as errors?
+    #[arg(short, long, global = true)]
+    pub strict: bool,
+
+    /// Optional group of built-in schemas to use (e.g. finance, can be repeated)
+    #[arg(long = "with-builtin", value_name = "GROUP", global = true)]
+    pub with_builtin: Vec<BuiltinGroup>,
+
+    /// Group of built-in schemas to leave out (e.g. customer_relations, can be repeated)
+    #[arg(long = "without-builtin", value_name = "GROUP", global = true)]
+    pub without_builtin: Vec<BuiltinGroup>,
+
+    /// Leave out all built-in schemas?
+    #[arg(long, global = true)]
+    pub no_builtin_schemas: bool,
+
     /// Enable verbose output?
     #[arg(short, long, global = true)]
     pub verbose: bool,
@@ -29,6 +48,30 @@ pub struct FirmCli {
     pub command: FirmCliCommand,
 }
 
+impl FirmCli {
+    /// Gets the options to build the workspace with, from the global flags.
+    pub fn workspace_options(&self) -> WorkspaceOptions {
+        let mut options = WorkspaceOptions::default();
+
+        // Strict mode rejects undeclared fields instead of warning about them
+        if self.strict {
+            options = options.with_unknown_fields(UnknownFieldPolicy::Error);
+        }
+
+        if self.no_builtin_schemas {
+            options = options.without_builtin_schemas();
+        }
+        for group in &self.with_builtin {
+            options = options.with_builtin_group(*group);
+        }
+        for group in &self.without_builtin {
+            options = options.without_builtin_group(*group);
+        }
+
+        options
+    }
+}
+
 /// Defines the available subcommands of the Firm CLI.
 #[derive(Subcommand, Debug, PartialEq)]
 pub enum FirmCliCommand {
@@ -45,6 +88,33 @@ pub enum FirmCliCommand {
     List {
         /// An entity type (e.g. "person") or "schema" to list schemas
         entity_type: String,
+        /// A field to sort the entities by (e.g. due_date)
+        #[arg(long)]
+        sort: Option<String>,
+        /// Sort in descending order?
+        #[arg(long, requires = "sort")]
+        descending: bool,
+        /// A currency field to total across the listed entities (e.g. value)
+        #[arg(long, requires = "currency")]
+        total: Option<String>,
+        /// Currency to total in, converting amounts with the workspace exchange rates (e.g. EUR)
+        #[arg(long, requires = "total")]
+        currency: Option<String>,
+        /// Date to pick exchange rates for (defaults to today)
+        #[arg(long, requires = "total")]
+        date: Option<NaiveDate>,
+    },
+    /// Searches the text of entity IDs and fields.
+    Search {
+        /// Words to search for (e.g. retainer)
+        #[arg(required = true)]
+        terms: Vec<String>,
+        /// Entity type to search (all types if not specified, can be repeated)
+        #[arg(long = "type", value_name = "TYPE")]
+        entity_types: Vec<String>,
+        /// Maximum number of results to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
     },
     /// Gets entities related to a given entity.
     Related {
@@ -56,9 +126,191 @@ pub enum FirmCliCommand {
         #[arg(short, long)]
         direction: Option<CliDirection>,
     },
+    /// Plans the tasks of a project, showing what can be started now and what blocks the deadline.
+    Plan {
+        /// Project ID (e.g. website_redesign)
+        project_id: String,
+        /// Date to plan from (defaults to today)
+        #[arg(long)]
+        start: Option<NaiveDate>,
+        /// Date the tasks must be finished by (defaults to the project's due date)
+        #[arg(long)]
+        deadline: Option<NaiveDate>,
+    },
+    /// Shows entity counts per type and how entities are connected.
+    Stats {
+        /// Entity type to show orphans and leaves for (all types if not specified, can be repeated)
+        #[arg(long = "type", value_name = "TYPE")]
+        entity_types: Vec<String>,
+        /// Number of most connected entities to show
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Extracts the entities around one or more entities, as DSL or JSON.
+    Subgraph {
+        /// IDs of the entities to start from (e.g. account.acme)
+        #[arg(required = true, value_name = "ENTITY_ID")]
+        seeds: Vec<String>,
+        /// Number of relationships to follow from the starting entities
+        #[arg(long, default_value_t = 1)]
+        depth: usize,
+        /// Direction of relationships to follow (incoming, outgoing, or both if not specified)
+        #[arg(short, long)]
+        direction: Option<CliDirection>,
+        /// Reference field to follow (all fields if not specified, can be repeated)
+        #[arg(long = "field", value_name = "FIELD")]
+        fields: Vec<String>,
+        /// Entity type to include (all types if not specified, can be repeated)
+        #[arg(long = "type", value_name = "TYPE")]
+        entity_types: Vec<String>,
+        /// File to write to (printed if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Interactively adds a new entity to a file in the workspace.
     Add {
         /// Target firm file.
         to_file: Option<PathBuf>,
     },
+    /// Moves an entity to a new state, if its schema allows it.
+    Transition {
+        /// Entity type (e.g. opportunity)
+        entity_type: String,
+        /// Entity ID (e.g. big_deal)
+        entity_id: String,
+        /// State to move to (e.g. won)
+        state: String,
+        /// Field to transition (the first field with states if not specified)
+        #[arg(long)]
+        field: Option<String>,
+    },
+    /// Works with the schemas in the workspace.
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
+    },
+    /// Migrates entities after a schema change, with the given operations or the migrations declared in schemas.
+    Migrate {
+        /// Entity type to migrate (all types with declared migrations if not specified)
+        entity_type: Option<String>,
+        /// Renames a field (e.g. title=name)
+        #[arg(long, value_name = "OLD=NEW")]
+        rename: Vec<String>,
+        /// Converts a field to a type, or the type in its schema if not specified (e.g. probability=float)
+        #[arg(long, value_name = "FIELD[=TYPE]")]
+        convert: Vec<String>,
+        /// Fills a field with its schema default on entities which don't have it (e.g. region)
+        #[arg(long, value_name = "FIELD")]
+        fill: Vec<String>,
+        /// Shows the changes as a diff without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Exports the workspace in other formats.
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+}
+
+/// Defines the subcommands for working with schemas.
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum SchemaCommand {
+    /// Exports schemas, for example as JSON Schema with --format jsonschema.
+    Export {
+        /// Schema to export (all schemas if not specified)
+        entity_type: Option<String>,
+        /// File to write to (printed if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Generates typed Rust structs for all schemas, with conversions to and from entities.
+    Generate {
+        /// File to write to (printed if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Defines the subcommands for exporting the workspace.
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ExportCommand {
+    /// Exports the entity graph for visualization with --format dot, mermaid or graphml.
+    Graph {
+        /// Field to label entities with (the entity ID if not specified or missing)
+        #[arg(long)]
+        label: Option<String>,
+        /// Entity type to include (all types if not specified, can be repeated)
+        #[arg(long = "type", value_name = "TYPE")]
+        entity_types: Vec<String>,
+        /// Only include entities related to this entity (e.g. person.john_doe)
+        #[arg(long, value_name = "ENTITY_ID")]
+        around: Option<String>,
+        /// Number of relationships to follow from the --around entity
+        #[arg(long, default_value_t = 1, requires = "around")]
+        depth: usize,
+        /// File to write to (printed if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_options_without_builtin_groups() {
+        let cli = FirmCli::try_parse_from([
+            "firm",
+            "--without-builtin",
+            "customer_relations",
+            "--without-builtin",
+            "resources",
+            "build",
+        ])
+        .unwrap();
+
+        let options = cli.workspace_options();
+
+        assert_eq!(
+            options.builtin_groups,
+            vec![BuiltinGroup::Core, BuiltinGroup::WorkManagement]
+        );
+        assert_eq!(options.unknown_fields, UnknownFieldPolicy::Warn);
+    }
+
+    #[test]
+    fn test_workspace_options_without_builtin_schemas() {
+        let cli =
+            FirmCli::try_parse_from(["firm", "build", "--no-builtin-schemas", "--strict"]).unwrap();
+
+        let options = cli.workspace_options();
+
+        assert!(options.builtin_groups.is_empty());
+        assert_eq!(options.unknown_fields, UnknownFieldPolicy::Error);
+    }
+
+    #[test]
+    fn test_workspace_options_with_builtin_group() {
+        let cli = FirmCli::try_parse_from([
+            "firm",
+            "--no-builtin-schemas",
+            "--with-builtin",
+            "finance",
+            "build",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.workspace_options().builtin_groups,
+            vec![BuiltinGroup::Finance]
+        );
+    }
+
+    #[test]
+    fn test_unknown_builtin_group() {
+        let result = FirmCli::try_parse_from(["firm", "--without-builtin", "crm", "build"]);
+        assert!(result.is_err());
+    }
 }
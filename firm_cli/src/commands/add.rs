@@ -2,7 +2,7 @@ use convert_case::{Case, Casing};
 use firm_core::graph::EntityGraph;
 use firm_core::{Entity, EntitySchema, compose_entity_id};
 use firm_lang::generate::generate_dsl;
-use firm_lang::workspace::Workspace;
+use firm_lang::workspace::{Workspace, WorkspaceOptions};
 use inquire::{Confirm, Select, Text};
 use std::fs::{self, File};
 use std::io::Write;
@@ -29,14 +29,15 @@ impl<'a> std::fmt::Display for InquireSchema<'a> {
 /// Interactively add a new entity and generate DSL for it.
 pub fn add_entity(
     workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
     to_file: Option<PathBuf>,
     output_format: OutputFormat,
 ) -> Result<(), CliError> {
     ui::header("Adding new entity");
-    let mut workspace = Workspace::new();
+    let mut workspace = Workspace::with_options(options.clone());
     load_workspace_files(&workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
-    let build = build_workspace(workspace).map_err(|_| CliError::BuildError)?;
-    let graph = build_graph(&build)?;
+    let build = build_workspace(&mut workspace).map_err(|_| CliError::BuildError)?;
+    let graph = build_graph(build.entities)?;
 
     // Let user choose entity type from built-in and custom schemas
     let mut sorted_schemas = build.schemas.clone();
@@ -83,6 +84,13 @@ pub fn add_entity(
         )?;
     }
 
+    // Write defaults that should be materialized for fields which weren't filled in
+    for (field_id, value) in chosen_schema.materialized_defaults() {
+        if entity.get_field(&field_id).is_none() {
+            entity = entity.with_field(field_id, value);
+        }
+    }
+
     // Generate and write the resulting DSL
     let generated_dsl = generate_dsl(&[entity.clone()]);
 
@@ -110,10 +118,12 @@ fn prompt_required_fields(
 
     required_fields.sort_by_key(|(field_id, _)| field_id.as_str());
     for (field_id, field) in required_fields {
+        let default = field.default.as_ref().map(|default| default.resolve());
         match prompt_for_field_value(
             field_id,
             field.expected_type(),
             field.is_required(),
+            default.as_ref(),
             Arc::clone(arc_graph),
             source_path,
             workspace_path,
@@ -144,10 +154,12 @@ fn prompt_optional_fields(
 
     optional_fields.sort_by_key(|(field_id, _)| field_id.as_str());
     for (field_id, field) in optional_fields {
+        let default = field.default.as_ref().map(|default| default.resolve());
         match prompt_for_field_value(
             field_id,
             field.expected_type(),
             field.is_required(),
+            default.as_ref(),
             Arc::clone(&graph),
             source_path,
             workspace_path,
@@ -232,7 +244,7 @@ fn write_dsl(
 
                 match output_format {
                     OutputFormat::Pretty => ui::pretty_output_entity_single(&entity),
-                    OutputFormat::Json => ui::json_output(&entity),
+                    _ => ui::json_output(&entity),
                 }
                 Ok(())
             }
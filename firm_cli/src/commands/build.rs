@@ -1,24 +1,34 @@
+use firm_core::Entity;
 use firm_core::graph::{EntityGraph, GraphError};
-use firm_lang::workspace::{Workspace, WorkspaceBuild, WorkspaceError};
+use firm_core::schema::ValidationErrorType;
+use firm_core::search::SearchIndex;
+use firm_lang::workspace::{Workspace, WorkspaceBuild, WorkspaceError, WorkspaceOptions};
 use std::path::PathBuf;
 
 use crate::errors::CliError;
-use crate::files::save_graph_with_backup;
+use crate::files::{save_graph_with_backup, save_search_index};
 use crate::ui::{self};
 
 /// Builds the selected workspace and saves the resulting entity graph.
-pub fn build_and_save_graph(workspace_path: &PathBuf) -> Result<(), CliError> {
+pub fn build_and_save_graph(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+) -> Result<(), CliError> {
     ui::header("Building graph");
 
     // First load and build the workspace from DSL
-    let mut workspace = Workspace::new();
+    let mut workspace = Workspace::with_options(options.clone());
     load_workspace_files(&workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
-    let build = build_workspace(workspace).map_err(|_| CliError::BuildError)?;
+    let build = build_workspace(&mut workspace).map_err(|_| CliError::BuildError)?;
 
     // Then build and save the entity graph
-    let graph = build_graph(&build).map_err(|_| CliError::BuildError)?;
+    let graph = build_graph(build.entities).map_err(|_| CliError::BuildError)?;
     save_graph_with_backup(&workspace_path, &graph).map_err(|_| CliError::BuildError)?;
 
+    // The search index is cached with the graph, so searches don't have to rebuild it
+    let index = SearchIndex::from_graph(&graph);
+    save_search_index(&workspace_path, &index).map_err(|_| CliError::BuildError)?;
+
     ui::success("Graph was built and saved");
 
     Ok(())
@@ -46,7 +56,7 @@ pub fn load_workspace_files(
 }
 
 /// Builds a workspace with progress indicator.
-pub fn build_workspace(mut workspace: Workspace) -> Result<WorkspaceBuild, WorkspaceError> {
+pub fn build_workspace(workspace: &mut Workspace) -> Result<WorkspaceBuild, WorkspaceError> {
     let progress = ui::progress_bar(workspace.num_files().try_into().unwrap());
 
     match workspace.build_with_progress(|total, curent, phase| {
@@ -56,6 +66,16 @@ pub fn build_workspace(mut workspace: Workspace) -> Result<WorkspaceBuild, Works
     }) {
         Ok(build) => {
             progress.finish_with_message("Workspace built successfully");
+            for warning in &build.warnings {
+                // Rule messages don't name the entity, unlike those of unknown fields
+                match (&warning.error_type, &warning.entity_id) {
+                    (ValidationErrorType::RuleViolation { .. }, Some(entity_id)) => {
+                        ui::warning(&format!("{}: {}", entity_id, warning.message))
+                    }
+                    _ => ui::warning(&warning.message),
+                }
+            }
+
             Ok(build)
         }
         Err(e) => {
@@ -66,12 +86,13 @@ pub fn build_workspace(mut workspace: Workspace) -> Result<WorkspaceBuild, Works
     }
 }
 
-/// Builds the entity graph from a workspace with progress indicator.
-pub fn build_graph(build: &WorkspaceBuild) -> Result<EntityGraph, CliError> {
+/// Builds the entity graph from the entities of a workspace with progress indicator.
+/// The graph takes ownership of the entities, so they aren't copied.
+pub fn build_graph(entities: Vec<Entity>) -> Result<EntityGraph, CliError> {
     let spinner = ui::spinner("Creating graph from workspace");
     let mut graph = EntityGraph::new();
 
-    let entity_result = graph.add_entities(build.entities.clone());
+    let entity_result = graph.add_entities(entities);
     if let Err(e) = entity_result {
         spinner.finish_and_clear();
 
@@ -0,0 +1,78 @@
+use firm_core::graph::{ExportOptions, GraphError, GraphFormat};
+use firm_core::{EntityId, EntityType, FieldId};
+use std::{fs, path::PathBuf};
+
+use crate::errors::CliError;
+use crate::files::load_current_graph;
+use crate::ui::{self, OutputFormat};
+
+/// The part of the entity graph to export, given on the command line.
+pub struct GraphExport {
+    pub label: Option<String>,
+    pub entity_types: Vec<String>,
+    pub around: Option<(String, usize)>,
+}
+
+/// Exports the current workspace entity graph for visualization, printing it or writing it to a file.
+///
+/// The pretty format exports Graphviz DOT, and JSON formats aren't supported.
+pub fn export_graph(
+    workspace_path: &PathBuf,
+    export: GraphExport,
+    output_path: Option<PathBuf>,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Exporting entity graph");
+
+    let format = match output_format {
+        OutputFormat::Pretty | OutputFormat::Dot => GraphFormat::Dot,
+        OutputFormat::Mermaid => GraphFormat::Mermaid,
+        OutputFormat::GraphMl => GraphFormat::GraphMl,
+        OutputFormat::Json | OutputFormat::JsonSchema => {
+            ui::error(&format!(
+                "The graph can't be exported as {}, use dot, mermaid or graphml",
+                output_format
+            ));
+            return Err(CliError::InputError);
+        }
+    };
+
+    let mut options = ExportOptions::default();
+    if let Some(label) = export.label {
+        options = options.with_label_field(FieldId::new(&label));
+    }
+    for entity_type in export.entity_types {
+        options = options.with_entity_type(EntityType::new(&entity_type));
+    }
+    if let Some((entity_id, depth)) = export.around {
+        options = options.around(EntityId::from(entity_id.as_str()), depth);
+    }
+
+    let graph = load_current_graph(workspace_path)?;
+    let exported = graph.export(format, &options).map_err(|e| {
+        match e {
+            GraphError::EntityNotFound(entity_id) => {
+                ui::error(&format!("Couldn't find entity with ID '{}'", entity_id))
+            }
+            e => ui::error_with_details("Couldn't export the graph", &format!("{:?}", e)),
+        }
+        CliError::QueryError
+    })?;
+
+    match output_path {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, &exported) {
+                ui::error_with_details("Couldn't write to file", &e.to_string());
+                return Err(CliError::FileError);
+            }
+
+            ui::success(&format!("Exported entity graph to {}", path.display()));
+        }
+        None => {
+            ui::success("Exported entity graph");
+            println!("{}", exported);
+        }
+    }
+
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use chrono::{FixedOffset, Local, NaiveTime, TimeZone, Timelike};
+use chrono::{FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Timelike};
 use console::style;
 use convert_case::{Case, Casing};
 use firm_core::{
-    FieldId, FieldType, FieldValue, ReferenceValue, compose_entity_id, graph::EntityGraph,
+    FieldId, FieldType, FieldValue, ReferenceValue, compose_entity_id,
+    graph::EntityGraph,
+    quantity::Quantity,
+    temporal::{Duration, Recurrence},
 };
 use inquire::{Confirm, CustomType, DateSelect, Select, Text, validator::Validation};
 use iso_currency::{Currency, IntoEnumIterator};
@@ -14,15 +17,17 @@ use std::{
     sync::Arc,
 };
 
-use crate::errors::CliError;
+use crate::{errors::CliError, ui};
 
 pub const SKIP_PROMPT_FRAGMENT: &str = " (esc to skip)";
 
 /// Interactive prompt for a field value, applying relevant prompt configurations depending on the field type.
+/// If the field has a default value, the prompt is pre-filled with it.
 pub fn prompt_for_field_value(
     field_id: &FieldId,
     field_type: &FieldType,
     is_required: bool,
+    default: Option<&FieldValue>,
     entity_graph: Arc<EntityGraph>,
     source_path: &PathBuf,
     workspace_dir: &PathBuf,
@@ -31,14 +36,17 @@ pub fn prompt_for_field_value(
     let field_id_prompt = field_id.as_str().to_case(Case::Sentence);
 
     match field_type {
-        FieldType::Boolean => bool_prompt(skippable, &field_id_prompt),
-        FieldType::String => string_prompt(skippable, &field_id_prompt),
-        FieldType::Integer => int_prompt(skippable, &field_id_prompt),
-        FieldType::Float => float_prompt(skippable, &field_id_prompt),
-        FieldType::Currency => currency_prompt(skippable, &field_id_prompt),
-        FieldType::Reference => {
-            reference_prompt(skippable, &field_id_prompt, Arc::clone(&entity_graph))
-        }
+        FieldType::Boolean => bool_prompt(skippable, &field_id_prompt, default),
+        FieldType::String => string_prompt(skippable, &field_id_prompt, default),
+        FieldType::Integer => int_prompt(skippable, &field_id_prompt, default),
+        FieldType::Float => float_prompt(skippable, &field_id_prompt, default),
+        FieldType::Currency => currency_prompt(skippable, &field_id_prompt, default),
+        FieldType::Reference => reference_prompt(
+            skippable,
+            &field_id_prompt,
+            default,
+            Arc::clone(&entity_graph),
+        ),
         FieldType::List => list_prompt(
             skippable,
             &field_id_prompt,
@@ -46,10 +54,23 @@ pub fn prompt_for_field_value(
             source_path,
             workspace_dir,
         ),
-        FieldType::DateTime => date_prompt(skippable, &field_id_prompt),
+        FieldType::Record => {
+            // The DSL has no syntax for record values, so they can't be written to the entity's file
+            ui::warning(&format!(
+                "Skipping {}: record fields can't be written in .firm files",
+                field_id_prompt
+            ));
+            Ok(None)
+        }
+        FieldType::DateTime => datetime_prompt(skippable, &field_id_prompt, default),
+        FieldType::Date => date_prompt(skippable, &field_id_prompt, default),
+        FieldType::Duration => duration_prompt(skippable, &field_id_prompt, default),
+        FieldType::Recurrence => recurrence_prompt(skippable, &field_id_prompt, default),
+        FieldType::Quantity => quantity_prompt(skippable, &field_id_prompt, default),
         FieldType::Path => path_prompt(
             skippable,
             &field_id_prompt,
+            default,
             source_path,
             workspace_dir.clone(),
         ),
@@ -58,19 +79,25 @@ pub fn prompt_for_field_value(
 
 /// Prompts for a boolean field.
 /// Value must be true or false.
-fn bool_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<FieldValue>, CliError> {
+fn bool_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
+    let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+
+    let mut value = Confirm::new(&prompt_text);
+    if let Some(FieldValue::Boolean(default)) = default {
+        value = value.with_default(*default);
+    }
 
     if skippable {
-        let value = Confirm::new(&format!("{}{}:", field_id_prompt, skip_message))
-            .prompt_skippable()
-            .map_err(|_| CliError::InputError)?;
-        Ok(value.map(FieldValue::Boolean))
+        let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
+        Ok(result.map(FieldValue::Boolean))
     } else {
-        let value = Confirm::new(&format!("{}{}:", field_id_prompt, skip_message))
-            .prompt()
-            .map_err(|_| CliError::InputError)?;
-        Ok(Some(FieldValue::Boolean(value)))
+        let result = value.prompt().map_err(|_| CliError::InputError)?;
+        Ok(Some(FieldValue::Boolean(result)))
     }
 }
 
@@ -79,21 +106,21 @@ fn bool_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<Field
 fn string_prompt(
     skippable: bool,
     field_id_prompt: &String,
+    default: Option<&FieldValue>,
 ) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
 
     loop {
+        let mut text = Text::new(&prompt_text);
+        if let Some(FieldValue::String(default)) = default {
+            text = text.with_default(default);
+        }
+
         let result = if skippable {
-            Text::new(&prompt_text)
-                .prompt_skippable()
-                .map_err(|_| CliError::InputError)?
+            text.prompt_skippable().map_err(|_| CliError::InputError)?
         } else {
-            Some(
-                Text::new(&prompt_text)
-                    .prompt()
-                    .map_err(|_| CliError::InputError)?,
-            )
+            Some(text.prompt().map_err(|_| CliError::InputError)?)
         };
 
         match result {
@@ -121,13 +148,20 @@ fn string_prompt(
 
 /// Prompts for an integer field.
 /// Value must not have a decimal place.
-fn int_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<FieldValue>, CliError> {
+fn int_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
 
-    let value = CustomType::<i64>::new(&prompt_text)
+    let mut value = CustomType::<i64>::new(&prompt_text)
         .with_error_message("Enter a valid integer")
         .with_help_message("Enter a whole number");
+    if let Some(FieldValue::Integer(default)) = default {
+        value = value.with_default(*default);
+    }
 
     if skippable {
         let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
@@ -140,13 +174,20 @@ fn int_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<FieldV
 
 /// Prompts for a float field.
 /// Value must have a decimal place.
-fn float_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<FieldValue>, CliError> {
+fn float_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
 
-    let value = CustomType::<f64>::new(&prompt_text)
+    let mut value = CustomType::<f64>::new(&prompt_text)
         .with_error_message("Enter a valid decimal number")
         .with_help_message("Enter a decimal number (e.g., 3.14)");
+    if let Some(FieldValue::Float(default)) = default {
+        value = value.with_default(*default);
+    }
 
     if skippable {
         let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
@@ -157,6 +198,84 @@ fn float_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<Fiel
     }
 }
 
+/// Prompts for a duration field.
+/// Value must be amounts with units, from weeks down to minutes.
+fn duration_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
+    let skip_message = get_skippable_prompt(skippable);
+    let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+
+    let mut value = CustomType::<Duration>::new(&prompt_text)
+        .with_error_message("Enter a valid duration")
+        .with_help_message("Enter amounts with units w, d, h or m (e.g., 3d or 2h30m)");
+    if let Some(FieldValue::Duration(default)) = default {
+        value = value.with_default(*default);
+    }
+
+    if skippable {
+        let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
+        Ok(result.map(FieldValue::Duration))
+    } else {
+        let result = value.prompt().map_err(|_| CliError::InputError)?;
+        Ok(Some(FieldValue::Duration(result)))
+    }
+}
+
+/// Prompts for a quantity field.
+/// Value must be a number followed by a unit.
+fn quantity_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
+    let skip_message = get_skippable_prompt(skippable);
+    let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+
+    let mut value = CustomType::<Quantity>::new(&prompt_text)
+        .with_error_message("Enter a number followed by a unit")
+        .with_help_message("Enter a number and a unit (e.g., 120 h or 5 TB)");
+    if let Some(FieldValue::Quantity(default)) = default {
+        value = value.with_default(default.clone());
+    }
+
+    if skippable {
+        let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
+        Ok(result.map(FieldValue::Quantity))
+    } else {
+        let result = value.prompt().map_err(|_| CliError::InputError)?;
+        Ok(Some(FieldValue::Quantity(result)))
+    }
+}
+
+/// Prompts for a recurrence field.
+/// Value must be "every" followed by an optional interval and a unit.
+fn recurrence_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
+    let skip_message = get_skippable_prompt(skippable);
+    let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+
+    let mut value = CustomType::<Recurrence>::new(&prompt_text)
+        .with_error_message("Enter a valid recurrence")
+        .with_help_message("Enter a day, week, month or year interval (e.g., every 2 weeks)");
+    if let Some(FieldValue::Recurrence(default)) = default {
+        value = value.with_default(*default);
+    }
+
+    if skippable {
+        let result = value.prompt_skippable().map_err(|_| CliError::InputError)?;
+        Ok(result.map(FieldValue::Recurrence))
+    } else {
+        let result = value.prompt().map_err(|_| CliError::InputError)?;
+        Ok(Some(FieldValue::Recurrence(result)))
+    }
+}
+
 /// Wraps currency for use in Inquire custom prompt.
 struct CurrencyOption {
     currency: Currency,
@@ -173,15 +292,23 @@ impl std::fmt::Display for CurrencyOption {
 fn currency_prompt(
     skippable: bool,
     field_id_prompt: &String,
+    default: Option<&FieldValue>,
 ) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let amount_prompt = format!("Amount for {}{}:", field_id_prompt, skip_message);
+    let (default_amount, default_currency) = match default {
+        Some(FieldValue::Currency { amount, currency }) => (Some(*amount), Some(*currency)),
+        _ => (None, None),
+    };
 
     // Get the amount
-    let amount = CustomType::<Decimal>::new(&amount_prompt)
+    let mut amount = CustomType::<Decimal>::new(&amount_prompt)
         .with_error_message("Enter a valid decimal amount (e.g., 123.45)")
         .with_help_message("Enter the monetary amount as a decimal number")
         .with_parser(&|input| Decimal::from_str_exact(input).map_err(|_| ()));
+    if let Some(default_amount) = default_amount {
+        amount = amount.with_default(default_amount);
+    }
 
     let amount_value = if skippable {
         let result = amount
@@ -201,9 +328,14 @@ fn currency_prompt(
         .map(|currency| CurrencyOption { currency })
         .collect();
 
+    let starting_cursor = default_currency
+        .and_then(|default| currencies.iter().position(|c| c.currency == default))
+        .unwrap_or(0);
+
     let currency_prompt = format!("Currency for {}:", field_id_prompt);
     let selected_option = Select::new(&currency_prompt, currencies)
         .with_help_message("Select the currency")
+        .with_starting_cursor(starting_cursor)
         .prompt()
         .map_err(|_| CliError::InputError)?;
 
@@ -219,19 +351,27 @@ fn currency_prompt(
 fn reference_prompt(
     skippable: bool,
     field_id_prompt: &String,
+    default: Option<&FieldValue>,
     entity_graph: Arc<EntityGraph>,
 ) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+    let default_text = match default {
+        Some(FieldValue::Reference(reference)) => Some(reference.to_string()),
+        _ => None,
+    };
 
     let graph_for_validator = Arc::clone(&entity_graph);
     let validator = move |input: &str| parse_reference(input, &graph_for_validator);
     let graph_for_autocomplete = Arc::clone(&entity_graph);
     let autocomplete = move |input: &str| get_reference_suggestions(input, &graph_for_autocomplete);
-    let reference_value_prompt = Text::new(&prompt_text)
+    let mut reference_value_prompt = Text::new(&prompt_text)
         .with_help_message("Start typing the reference for autocompletion")
         .with_validator(validator)
         .with_autocomplete(autocomplete);
+    if let Some(default_text) = &default_text {
+        reference_value_prompt = reference_value_prompt.with_default(default_text);
+    }
 
     let result_str = if skippable {
         let result = reference_value_prompt
@@ -255,7 +395,7 @@ fn reference_prompt(
         )))),
         3 => Ok(Some(FieldValue::Reference(ReferenceValue::Field(
             compose_entity_id(&parts[0], &parts[1]),
-            FieldId(parts[2].into()),
+            FieldId::from(parts[2]),
         )))),
         _ => unreachable!("Parser should have prevented this format."),
     }
@@ -374,6 +514,9 @@ fn list_prompt(
         FieldType::Float,
         FieldType::Boolean,
         FieldType::DateTime,
+        FieldType::Date,
+        FieldType::Duration,
+        FieldType::Quantity,
         FieldType::Currency,
     ];
 
@@ -409,6 +552,7 @@ fn list_prompt(
             &item_field_id,
             &item_type,
             false,
+            None,
             Arc::clone(&entity_graph),
             source_path,
             workspace_dir,
@@ -427,26 +571,73 @@ fn list_prompt(
     Ok(Some(FieldValue::List(items)))
 }
 
-/// Prompts for a date field.
-/// We do in 3 steps, first a calendar, then time, then UTC offset.
-fn date_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<FieldValue>, CliError> {
+/// Prompts for a date field using a calendar.
+fn date_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
+    let default_date = match default {
+        Some(FieldValue::Date(date)) => Some(*date),
+        Some(FieldValue::DateTime(datetime)) => Some(datetime.date_naive()),
+        _ => None,
+    };
+
+    Ok(calendar_prompt(skippable, field_id_prompt, default_date)?.map(FieldValue::Date))
+}
+
+/// Shows a calendar to pick a date from.
+fn calendar_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default_date: Option<NaiveDate>,
+) -> Result<Option<NaiveDate>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
+    let date_prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+    let mut date_select = DateSelect::new(&date_prompt_text)
+        .with_help_message("Use arrow keys to navigate, Enter to select");
+    if let Some(default_date) = default_date {
+        date_select = date_select.with_default(default_date);
+    }
 
-    // Get the date
-    let date = if skippable {
-        match DateSelect::new(&format!("{}{}:", field_id_prompt, skip_message))
-            .with_help_message("Use arrow keys to navigate, Enter to select")
+    if skippable {
+        date_select
             .prompt_skippable()
-            .map_err(|_| CliError::InputError)?
-        {
-            Some(d) => d,
-            None => return Ok(None),
-        }
+            .map_err(|_| CliError::InputError)
     } else {
-        DateSelect::new(&format!("{}{}:", field_id_prompt, skip_message))
-            .with_help_message("Use arrow keys to navigate, Enter to select")
+        date_select
             .prompt()
-            .map_err(|_| CliError::InputError)?
+            .map(Some)
+            .map_err(|_| CliError::InputError)
+    }
+}
+
+/// Prompts for a datetime field.
+/// We do in 3 steps, first a calendar, then time, then UTC offset.
+fn datetime_prompt(
+    skippable: bool,
+    field_id_prompt: &String,
+    default: Option<&FieldValue>,
+) -> Result<Option<FieldValue>, CliError> {
+    let default_datetime = match default {
+        Some(FieldValue::DateTime(datetime)) => Some(*datetime),
+        _ => None,
+    };
+    let default_time = default_datetime
+        .map(|datetime| {
+            datetime
+                .time()
+                .with_second(0)
+                .unwrap()
+                .with_nanosecond(0)
+                .unwrap()
+        })
+        .unwrap_or(NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+
+    // Get the date
+    let default_date = default_datetime.map(|datetime| datetime.date_naive());
+    let Some(date) = calendar_prompt(skippable, field_id_prompt, default_date)? else {
+        return Ok(None);
     };
 
     // Get the time (HH:MM only)
@@ -459,7 +650,7 @@ fn date_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<Field
                     .map(|t| t.with_second(0).unwrap())
                     .map_err(|_| ())
             })
-            .with_default(NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+            .with_default(default_time)
             .prompt_skippable()
             .map_err(|_| CliError::InputError)?
         {
@@ -475,15 +666,17 @@ fn date_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<Field
                     .map(|t| t.with_second(0).unwrap())
                     .map_err(|_| ())
             })
-            .with_default(NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+            .with_default(default_time)
             .prompt()
             .map_err(|_| CliError::InputError)?
     };
 
     let naive_datetime = date.and_time(time_input);
 
-    // Get the local timezone offset in hours
-    let local_offset_seconds = Local::now().offset().local_minus_utc();
+    // Get the default timezone offset in hours, falling back to the local timezone
+    let local_offset_seconds = default_datetime
+        .map(|datetime| datetime.offset().local_minus_utc())
+        .unwrap_or_else(|| Local::now().offset().local_minus_utc());
     let local_offset_hours = local_offset_seconds / 3600;
 
     // Get timezone offset as integer hours
@@ -522,18 +715,26 @@ fn date_prompt(skippable: bool, field_id_prompt: &String) -> Result<Option<Field
 fn path_prompt(
     skippable: bool,
     field_id_prompt: &String,
+    default: Option<&FieldValue>,
     source_path: &PathBuf,
     workspace_dir: PathBuf,
 ) -> Result<Option<FieldValue>, CliError> {
     let skip_message = get_skippable_prompt(skippable);
     let prompt_text = format!("{}{}:", field_id_prompt, skip_message);
+    let default_text = match default {
+        Some(FieldValue::Path(path)) => Some(path.display().to_string()),
+        _ => None,
+    };
 
     let autocomplete_workspace = workspace_dir.clone();
     let autocomplete =
         move |input: &str| get_path_suggestions(input, autocomplete_workspace.clone());
-    let reference_value_prompt = Text::new(&prompt_text)
+    let mut reference_value_prompt = Text::new(&prompt_text)
         .with_help_message("Start typing the path for autocompletion")
         .with_autocomplete(autocomplete);
+    if let Some(default_text) = &default_text {
+        reference_value_prompt = reference_value_prompt.with_default(default_text);
+    }
 
     let result_str = if skippable {
         let result = reference_value_prompt
@@ -1,6 +1,13 @@
-use firm_core::compose_entity_id;
-use firm_lang::workspace::Workspace;
-use std::path::PathBuf;
+use chrono::{Local, NaiveDate};
+use firm_core::compare::{SortOrder, sort_by_field};
+use firm_core::field::Currency;
+use firm_core::graph::EntityGraph;
+use firm_core::{EntityId, EntityType, FieldId, compose_entity_id, decompose_entity_id};
+use firm_lang::workspace::{Workspace, WorkspaceOptions};
+use inquire::Select;
+use serde_json::{Value, json};
+use std::io::{self, IsTerminal};
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
 
 use super::{build_workspace, load_workspace_files};
 use crate::errors::CliError;
@@ -8,6 +15,16 @@ use crate::files::load_current_graph;
 use crate::query::CliDirection;
 use crate::ui::{self, OutputFormat};
 
+/// A currency field to total when listing entities, given on the command line.
+pub struct ListTotal {
+    pub field: String,
+    pub currency: String,
+    pub date: Option<NaiveDate>,
+}
+
+/// The most suggestions to offer for a misspelled entity ID or type.
+const SUGGESTION_LIMIT: usize = 5;
+
 /// Gets an entity by ID from the current workspace entity graph.
 pub fn get_entity_by_id(
     workspace_path: &PathBuf,
@@ -18,27 +35,24 @@ pub fn get_entity_by_id(
     ui::header("Getting entity by ID");
     let graph = load_current_graph(&workspace_path)?;
 
-    let id = compose_entity_id(&entity_type, &entity_id);
-    match graph.get_entity(&id) {
-        Some(entity) => {
-            ui::success(&format!(
-                "Found '{}' entity with ID '{}'",
-                entity_type, entity_id
-            ));
+    let id = find_entity_id(
+        &graph,
+        compose_entity_id(&entity_type, &entity_id),
+        &output_format,
+    )?;
+    let Some(entity) = graph.get_entity(&id) else {
+        return Err(CliError::QueryError);
+    };
 
-            match output_format {
-                ui::OutputFormat::Pretty => ui::pretty_output_entity_single(entity),
-                ui::OutputFormat::Json => ui::json_output(entity),
-            }
-        }
-        None => {
-            ui::error(&format!(
-                "Couldn't find '{}' entity with ID '{}'",
-                entity_type, entity_id
-            ));
+    let (entity_type, entity_id) = decompose_entity_id(id.as_str());
+    ui::success(&format!(
+        "Found '{}' entity with ID '{}'",
+        entity_type, entity_id
+    ));
 
-            return Err(CliError::QueryError);
-        }
+    match output_format {
+        ui::OutputFormat::Pretty => ui::pretty_output_entity_single(entity),
+        _ => ui::json_output(entity),
     }
 
     Ok(())
@@ -55,7 +69,13 @@ pub fn get_related_entities(
     ui::header("Getting related entities");
     let graph = load_current_graph(&workspace_path)?;
 
-    let id = compose_entity_id(&entity_type, &entity_id);
+    let id = find_entity_id(
+        &graph,
+        compose_entity_id(&entity_type, &entity_id),
+        &output_format,
+    )?;
+    let (entity_type, entity_id) = decompose_entity_id(id.as_str());
+
     match graph.get_related(&id, direction.clone().map(|d| d.into())) {
         Some(entities) => {
             let direction_text = match direction {
@@ -74,14 +94,14 @@ pub fn get_related_entities(
 
             match output_format {
                 OutputFormat::Pretty => ui::pretty_output_entity_list(&entities),
-                OutputFormat::Json => ui::json_output(&entities),
+                _ => ui::json_output(&entities),
             }
 
             Ok(())
         }
         None => {
             ui::error(&format!(
-                "Couldn't find '{}' entity with ID '{}'",
+                "Couldn't find '{}' entity with ID '{}'",
                 entity_type, entity_id
             ));
 
@@ -94,33 +114,183 @@ pub fn get_related_entities(
 pub fn list_entities_by_type(
     workspace_path: &PathBuf,
     entity_type: String,
+    sort: Option<(String, SortOrder)>,
+    total: Option<ListTotal>,
     output_format: OutputFormat,
 ) -> Result<(), CliError> {
     ui::header("Listing entities by type");
     let graph = load_current_graph(&workspace_path)?;
 
-    let entities = graph.list_by_type(&entity_type.as_str().into());
+    let entity_type = find_entity_type(&graph, entity_type, &output_format);
+    let mut entities = graph.list_by_type(&entity_type.as_str().into());
+    if let Some((field, order)) = sort {
+        sort_by_field(&mut entities, &FieldId::new(&field), order).map_err(|e| {
+            ui::error_with_details(
+                &format!("Couldn't sort by field '{}'", field),
+                &e.to_string(),
+            );
+            CliError::QueryError
+        })?;
+    }
+
     ui::success(&format!(
         "Found {} entities with type '{}'",
         entities.len(),
         entity_type,
     ));
 
+    let Some(total) = total else {
+        match output_format {
+            OutputFormat::Pretty => ui::pretty_output_entity_list(&entities),
+            _ => ui::json_output(&entities),
+        }
+
+        return Ok(());
+    };
+
+    let Some(currency) = Currency::from_code(&total.currency.to_uppercase()) else {
+        ui::error(&format!("Unknown currency '{}'", total.currency));
+        return Err(CliError::InputError);
+    };
+
+    let date = total.date.unwrap_or_else(|| Local::now().date_naive());
+    let sum =
+        match graph.total_currency_field(&entities, &FieldId::new(&total.field), currency, date) {
+            Ok(sum) => sum.rounded(),
+            Err(e) => {
+                ui::error_with_details(
+                    &format!("Couldn't total field '{}'", total.field),
+                    &e.to_string(),
+                );
+                return Err(CliError::QueryError);
+            }
+        };
+
     match output_format {
-        OutputFormat::Pretty => ui::pretty_output_entity_list(&entities),
-        OutputFormat::Json => ui::json_output(&entities),
+        OutputFormat::Pretty => {
+            ui::pretty_output_entity_list(&entities);
+            ui::success(&format!("Total '{}' on {} is {}", total.field, date, sum));
+        }
+        _ => ui::json_output(&json!({
+            "entities": entities,
+            "total": {
+                "field": total.field,
+                "amount": sum.amount.to_string(),
+                "currency": sum.currency.code(),
+                "date": date.to_string(),
+            },
+        })),
     }
 
     Ok(())
 }
 
+/// Finds an entity ID in the graph, offering the closest IDs if it's not there.
+///
+/// With pretty output in a terminal, one of the suggestions can be picked to use instead.
+/// Otherwise the suggestions are shown with the error, and included in JSON output.
+fn find_entity_id(
+    graph: &EntityGraph,
+    id: EntityId,
+    output_format: &OutputFormat,
+) -> Result<EntityId, CliError> {
+    if graph.get_entity(&id).is_some() {
+        return Ok(id);
+    }
+
+    let (entity_type, entity_id) = decompose_entity_id(id.as_str());
+    let message = format!(
+        "Couldn't find '{}' entity with ID '{}'",
+        entity_type, entity_id
+    );
+    let suggestions = graph.suggest_entity_ids(&id, SUGGESTION_LIMIT);
+
+    if !suggestions.is_empty() && *output_format == OutputFormat::Pretty && can_prompt() {
+        ui::warning(&message);
+        if let Ok(picked) = Select::new("Did you mean:", suggestions.clone()).prompt() {
+            return Ok(picked);
+        }
+    }
+
+    match suggestions.is_empty() {
+        true => ui::error(&message),
+        false => ui::error_with_details(&message, &did_you_mean(&suggestions)),
+    }
+
+    if *output_format != OutputFormat::Pretty {
+        ui::json_output(&json!({
+            "error": message,
+            "suggestions": suggestions,
+        }));
+    }
+
+    Err(CliError::QueryError)
+}
+
+/// Finds an entity type in the graph, offering the closest types if there are no entities of it.
+///
+/// A type without entities isn't an error, since it may just not be used yet, so this only warns.
+/// With pretty output in a terminal, one of the suggestions can be picked to use instead.
+fn find_entity_type(
+    graph: &EntityGraph,
+    entity_type: String,
+    output_format: &OutputFormat,
+) -> String {
+    let unknown_type = EntityType::new(&entity_type);
+    if graph.get_all_entity_types().contains(&unknown_type) {
+        return entity_type;
+    }
+
+    let suggestions = graph.suggest_entity_types(&unknown_type, SUGGESTION_LIMIT);
+    if suggestions.is_empty() {
+        return entity_type;
+    }
+
+    let message = format!("There are no entities with type '{}'", entity_type);
+    if *output_format == OutputFormat::Pretty && can_prompt() {
+        ui::warning(&message);
+        let help = format!("Press esc to list '{}' anyway", entity_type);
+        let picked = Select::new("Did you mean:", suggestions)
+            .with_help_message(&help)
+            .prompt();
+
+        return match picked {
+            Ok(picked) => picked.to_string(),
+            Err(_) => entity_type,
+        };
+    }
+
+    ui::warning(&format!("{}. {}", message, did_you_mean(&suggestions)));
+    entity_type
+}
+
+/// Whether the user can be prompted, which needs both input and the prompt to be on a terminal.
+fn can_prompt() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Formats suggestions as a question, like "Did you mean 'a', 'b' or 'c'?".
+fn did_you_mean<T: Display>(suggestions: &[T]) -> String {
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+
+    match quoted.split_last() {
+        Some((last, [])) => format!("Did you mean {}?", last),
+        Some((last, rest)) => format!("Did you mean {} or {}?", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
 /// Lists schemas in the workspace.
 /// This is a special case for the CLI list action where a type of "schema" is provided.
-pub fn list_schemas(workspace_path: &PathBuf, output_format: OutputFormat) -> Result<(), CliError> {
+pub fn list_schemas(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
     ui::header("Listing schemas");
-    let mut workspace = Workspace::new();
+    let mut workspace = Workspace::with_options(options.clone());
     load_workspace_files(&workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
-    let build = build_workspace(workspace).map_err(|_| CliError::BuildError)?;
+    let build = build_workspace(&mut workspace).map_err(|_| CliError::BuildError)?;
 
     ui::success(&format!(
         "Found {} schemas for this workspace",
@@ -129,7 +299,15 @@ pub fn list_schemas(workspace_path: &PathBuf, output_format: OutputFormat) -> Re
 
     match output_format {
         OutputFormat::Pretty => ui::pretty_output_schema_list(&build.schemas.iter().collect()),
-        OutputFormat::Json => ui::json_output(&build.schemas),
+        OutputFormat::JsonSchema => {
+            let documents: BTreeMap<&str, Value> = build
+                .schemas
+                .iter()
+                .map(|schema| (schema.entity_type.as_str(), schema.to_json_schema()))
+                .collect();
+            ui::json_output(&documents)
+        }
+        _ => ui::json_output(&build.schemas),
     }
     Ok(())
 }
@@ -0,0 +1,191 @@
+use firm_core::{EntityId, EntityType, FieldId, migration::Migration};
+use firm_lang::convert::convert_field_type;
+use firm_lang::workspace::{MigratedFile, Workspace, WorkspaceOptions};
+use pathdiff::diff_paths;
+use serde::Serialize;
+use similar::TextDiff;
+use std::{fs, path::PathBuf};
+
+use super::load_workspace_files;
+use crate::errors::CliError;
+use crate::ui::{self, OutputFormat};
+
+/// Migration operations given on the command line.
+pub struct MigrationOperations {
+    pub renames: Vec<String>,
+    pub conversions: Vec<String>,
+    pub fills: Vec<String>,
+}
+
+impl MigrationOperations {
+    fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.conversions.is_empty() && self.fills.is_empty()
+    }
+}
+
+/// The changes a migration makes to a file, as output in JSON.
+#[derive(Serialize)]
+struct MigratedFileOutput {
+    path: PathBuf,
+    entities: Vec<EntityId>,
+    diff: String,
+}
+
+/// Migrates entities in the workspace and rewrites their source files, or shows the changes for a dry run.
+///
+/// Operations given on the command line are applied to one entity type, renames first, then conversions and fills.
+/// Without operations, the migrations declared in workspace schemas are applied.
+pub fn migrate_entities(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+    entity_type: Option<String>,
+    operations: MigrationOperations,
+    dry_run: bool,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Migrating entities");
+    let mut workspace = Workspace::with_options(options.clone());
+    load_workspace_files(workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
+
+    let migrations = match (entity_type, !operations.is_empty()) {
+        (Some(entity_type), true) => vec![migration_from_operations(entity_type, operations)?],
+        (None, true) => {
+            ui::error("Migration operations need an entity type to apply to");
+            return Err(CliError::InputError);
+        }
+        (entity_type, false) => {
+            let declared = workspace.declared_migrations().map_err(|e| {
+                ui::error_with_details("Couldn't read migrations from schemas", &e.to_string());
+                CliError::BuildError
+            })?;
+
+            declared
+                .into_iter()
+                .filter(|migration| {
+                    entity_type
+                        .as_ref()
+                        .is_none_or(|entity_type| migration.entity_type.as_str() == entity_type)
+                })
+                .collect()
+        }
+    };
+
+    if migrations.is_empty() {
+        ui::info("No migrations to apply");
+        return Ok(());
+    }
+
+    let migrated_files = workspace.migrate(&migrations).map_err(|e| {
+        ui::error_with_details("Couldn't migrate workspace", &e.to_string());
+        CliError::BuildError
+    })?;
+
+    let num_entities: usize = migrated_files.iter().map(|file| file.entities.len()).sum();
+
+    match output_format {
+        OutputFormat::Pretty => {
+            if dry_run {
+                for file in &migrated_files {
+                    ui::diff_output(&file_diff(workspace_path, file));
+                }
+            }
+        }
+        _ => {
+            let output: Vec<MigratedFileOutput> = migrated_files
+                .iter()
+                .map(|file| MigratedFileOutput {
+                    path: file.path.clone(),
+                    entities: file.entities.clone(),
+                    diff: file_diff(workspace_path, file),
+                })
+                .collect();
+
+            ui::json_output(&output);
+        }
+    }
+
+    if dry_run {
+        ui::info(&format!(
+            "Would migrate {} entities in {} files",
+            num_entities,
+            migrated_files.len()
+        ));
+
+        return Ok(());
+    }
+
+    for file in &migrated_files {
+        if let Err(e) = fs::write(&file.path, &file.migrated) {
+            ui::error_with_details(
+                &format!("Couldn't write to file '{}'", file.path.display()),
+                &e.to_string(),
+            );
+            return Err(CliError::FileError);
+        }
+    }
+
+    ui::success(&format!(
+        "Migrated {} entities in {} files",
+        num_entities,
+        migrated_files.len()
+    ));
+
+    Ok(())
+}
+
+/// Builds a migration for an entity type from the operations given on the command line.
+fn migration_from_operations(
+    entity_type: String,
+    operations: MigrationOperations,
+) -> Result<Migration, CliError> {
+    let mut migration = Migration::new(EntityType::new(entity_type));
+
+    for rename in operations.renames {
+        let Some((from, to)) = rename.split_once('=') else {
+            ui::error(&format!(
+                "Expected a rename like 'old_name=new_name' but got '{}'",
+                rename
+            ));
+            return Err(CliError::InputError);
+        };
+
+        migration = migration.with_rename(FieldId::new(from.trim()), FieldId::new(to.trim()));
+    }
+
+    for conversion in operations.conversions {
+        let (field, to) = match conversion.split_once('=') {
+            Some((field, type_str)) => match convert_field_type(type_str.trim()) {
+                Ok(field_type) => (field, Some(field_type)),
+                Err(e) => {
+                    ui::error(&e.to_string());
+                    return Err(CliError::InputError);
+                }
+            },
+            None => (conversion.as_str(), None),
+        };
+
+        migration = migration.with_conversion(FieldId::new(field.trim()), to);
+    }
+
+    for fill in operations.fills {
+        migration = migration.with_fill(FieldId::new(fill.trim()), None);
+    }
+
+    Ok(migration)
+}
+
+/// Gets a unified diff of a migrated file, with paths relative to the workspace.
+fn file_diff(workspace_path: &PathBuf, file: &MigratedFile) -> String {
+    let relative_path = diff_paths(&file.path, workspace_path)
+        .unwrap_or_else(|| file.path.clone())
+        .display()
+        .to_string();
+
+    TextDiff::from_lines(&file.original, &file.migrated)
+        .unified_diff()
+        .header(
+            &format!("a/{}", relative_path),
+            &format!("b/{}", relative_path),
+        )
+        .to_string()
+}
@@ -1,8 +1,26 @@
 mod add;
 mod build;
+mod export;
 mod field_prompt;
 mod get;
+mod migrate;
+mod plan;
+mod schema;
+mod search;
+mod stats;
+mod subgraph;
+mod transition;
 
 pub use add::add_entity;
 pub use build::{build_and_save_graph, build_graph, build_workspace, load_workspace_files};
-pub use get::{get_entity_by_id, get_related_entities, list_entities_by_type, list_schemas};
+pub use export::{GraphExport, export_graph};
+pub use get::{
+    ListTotal, get_entity_by_id, get_related_entities, list_entities_by_type, list_schemas,
+};
+pub use migrate::{MigrationOperations, migrate_entities};
+pub use plan::plan_project;
+pub use schema::{export_schemas, generate_schema_code};
+pub use search::search_entities;
+pub use stats::show_stats;
+pub use subgraph::{SubgraphSelection, extract_subgraph};
+pub use transition::transition_entity;
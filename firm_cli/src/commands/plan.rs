@@ -0,0 +1,58 @@
+use chrono::{Local, NaiveDate};
+use firm_core::{FieldId, FieldValue, compose_entity_id};
+use std::path::PathBuf;
+
+use crate::errors::CliError;
+use crate::files::load_current_graph;
+use crate::ui::{self, OutputFormat};
+
+/// Plans the incomplete tasks of a project in the current workspace entity graph.
+///
+/// The deadline defaults to the project's due date, and the plan starts today unless a start date is given.
+pub fn plan_project(
+    workspace_path: &PathBuf,
+    project_id: String,
+    start: Option<NaiveDate>,
+    deadline: Option<NaiveDate>,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Planning project tasks");
+    let graph = load_current_graph(workspace_path)?;
+
+    let id = compose_entity_id("project", &project_id);
+    let Some(project) = graph.get_entity(&id) else {
+        ui::error(&format!(
+            "Couldn't find 'project' entity with ID '{}'",
+            project_id
+        ));
+        return Err(CliError::QueryError);
+    };
+
+    let deadline = deadline.or(match project.get_field(&FieldId::new("due_date")) {
+        Some(FieldValue::Date(due_date)) => Some(*due_date),
+        _ => None,
+    });
+    let start = start.unwrap_or_else(|| Local::now().date_naive());
+
+    let tasks = graph.project_tasks(&id).map_err(|_| CliError::QueryError)?;
+    let schedule = graph.schedule(&tasks, start, deadline).map_err(|e| {
+        ui::error_with_details("Couldn't plan the project", &e.to_string());
+        CliError::QueryError
+    })?;
+
+    ui::success(&format!(
+        "Planned {} open tasks for project '{}'",
+        schedule.tasks.len(),
+        project_id
+    ));
+    if schedule.is_late() {
+        ui::warning("The tasks can't be finished by the deadline");
+    }
+
+    match output_format {
+        OutputFormat::Pretty => println!("\n{}", schedule),
+        _ => ui::json_output(&schedule),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use firm_core::EntitySchema;
+use firm_lang::codegen::generate_rust;
+use firm_lang::workspace::{Workspace, WorkspaceOptions};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use super::{build_workspace, load_workspace_files};
+use crate::errors::CliError;
+use crate::ui::{self, OutputFormat};
+
+/// Exports one or all schemas in the workspace, printing them or writing them to a file.
+///
+/// With the JSON Schema format, each schema becomes a document describing the JSON of its entities.
+/// All schemas are exported as an object keyed by entity type.
+pub fn export_schemas(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+    entity_type: Option<String>,
+    output_path: Option<PathBuf>,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Exporting schemas");
+    let mut workspace = Workspace::with_options(options.clone());
+    load_workspace_files(workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
+    let build = build_workspace(&mut workspace).map_err(|_| CliError::BuildError)?;
+
+    let mut schemas: Vec<&EntitySchema> = match &entity_type {
+        Some(entity_type) => build
+            .schemas
+            .iter()
+            .filter(|schema| schema.entity_type.as_str() == entity_type)
+            .collect(),
+        None => build.schemas.iter().collect(),
+    };
+    schemas.sort_by(|a, b| a.entity_type.cmp(&b.entity_type));
+
+    if schemas.is_empty() {
+        ui::error(&format!(
+            "Couldn't find schema '{}'",
+            entity_type.unwrap_or_default()
+        ));
+        return Err(CliError::QueryError);
+    }
+
+    let single = entity_type.is_some();
+    let exported = match output_format {
+        OutputFormat::Pretty => schemas
+            .iter()
+            .map(|schema| schema.to_string())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        OutputFormat::JsonSchema => to_json_string(&schemas, single, EntitySchema::to_json_schema),
+        _ => to_json_string(&schemas, single, |schema| {
+            serde_json::to_value(schema).unwrap_or_default()
+        }),
+    };
+
+    write_output(&exported, schemas.len(), output_path)
+}
+
+/// Generates typed Rust structs for the schemas in the workspace, printing them or writing them to a file.
+pub fn generate_schema_code(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+    output_path: Option<PathBuf>,
+) -> Result<(), CliError> {
+    ui::header("Generating Rust structs");
+    let mut workspace = Workspace::with_options(options.clone());
+    load_workspace_files(workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
+
+    // Only schemas are needed, so entities that don't match them yet don't stop generation
+    let schemas = workspace.schemas().map_err(|e| {
+        ui::error_with_details("Failed to build schemas", &e.to_string());
+        CliError::BuildError
+    })?;
+
+    write_output(&generate_rust(&schemas), schemas.len(), output_path)
+}
+
+/// Writes exported schemas to a file, or prints them if no file is given.
+fn write_output(
+    exported: &str,
+    num_schemas: usize,
+    output_path: Option<PathBuf>,
+) -> Result<(), CliError> {
+    match output_path {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, exported) {
+                ui::error_with_details("Couldn't write to file", &e.to_string());
+                return Err(CliError::FileError);
+            }
+
+            ui::success(&format!(
+                "Exported {} schemas to {}",
+                num_schemas,
+                path.display()
+            ));
+        }
+        None => {
+            ui::success(&format!("Exported {} schemas", num_schemas));
+            println!("{}", exported);
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts schemas to pretty-printed JSON: a single document, or an object keyed by entity type.
+fn to_json_string(
+    schemas: &[&EntitySchema],
+    single: bool,
+    convert: impl Fn(&EntitySchema) -> Value,
+) -> String {
+    let value = match schemas {
+        [schema] if single => convert(schema),
+        _ => {
+            let documents: BTreeMap<&str, Value> = schemas
+                .iter()
+                .map(|schema| (schema.entity_type.as_str(), convert(schema)))
+                .collect();
+            serde_json::to_value(documents).unwrap_or_default()
+        }
+    };
+
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
@@ -0,0 +1,63 @@
+use firm_core::EntityType;
+use firm_core::search::{SearchHit, SearchOptions, Snippet, snippets};
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::errors::CliError;
+use crate::files::{load_current_graph, load_search_index};
+use crate::ui::{self, OutputFormat};
+
+/// A search result as output in JSON.
+#[derive(Serialize)]
+struct SearchResultOutput<'a> {
+    #[serde(flatten)]
+    hit: &'a SearchHit,
+    snippets: Vec<Snippet>,
+}
+
+/// Searches the entities in the current workspace entity graph for words, showing matching snippets.
+pub fn search_entities(
+    workspace_path: &PathBuf,
+    query: String,
+    entity_types: Vec<String>,
+    limit: usize,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Searching entities");
+    let graph = load_current_graph(workspace_path)?;
+    let index = load_search_index(workspace_path, &graph);
+
+    let mut options = SearchOptions::default().with_limit(limit);
+    for entity_type in entity_types {
+        options = options.with_entity_type(EntityType::new(&entity_type));
+    }
+
+    let hits = index.search(&query, &options);
+    ui::success(&format!(
+        "Found {} entities matching '{}'",
+        hits.len(),
+        query
+    ));
+
+    let results: Vec<SearchResultOutput> = hits
+        .iter()
+        .map(|hit| SearchResultOutput {
+            hit,
+            snippets: graph
+                .get_entity(&hit.entity_id)
+                .map(|entity| snippets(entity, &hit.fields, &query))
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    match output_format {
+        OutputFormat::Pretty => {
+            for result in &results {
+                ui::pretty_output_search_hit(result.hit, &result.snippets);
+            }
+        }
+        _ => ui::json_output(&results),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use crate::errors::CliError;
+use crate::files::load_current_graph;
+use crate::ui::{self, OutputFormat};
+
+/// Shows entity counts per type and connectivity metrics for the current workspace entity graph.
+pub fn show_stats(
+    workspace_path: &PathBuf,
+    entity_types: Vec<String>,
+    top: usize,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Analyzing entity graph");
+    let graph = load_current_graph(workspace_path)?;
+
+    let mut stats = graph.stats(top);
+    if !entity_types.is_empty() {
+        stats
+            .types
+            .retain(|type_stats| entity_types.contains(&type_stats.entity_type.to_string()));
+
+        if stats.types.is_empty() {
+            ui::error(&format!(
+                "Couldn't find entities with type '{}'",
+                entity_types.join("', '")
+            ));
+            return Err(CliError::QueryError);
+        }
+    }
+
+    ui::success(&format!(
+        "Analyzed {} entities with {} relationships",
+        stats.entities, stats.relationships
+    ));
+
+    match output_format {
+        OutputFormat::Pretty => println!("\n{}", stats),
+        _ => ui::json_output(&stats),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use firm_core::graph::{GraphError, SubgraphOptions};
+use firm_core::{Entity, EntityId, EntityType, FieldId};
+use firm_lang::generate::generate_dsl;
+use std::{fs, path::PathBuf};
+
+use crate::errors::CliError;
+use crate::files::load_current_graph;
+use crate::query::CliDirection;
+use crate::ui::{self, OutputFormat};
+
+/// The relationships to follow when extracting a subgraph, given on the command line.
+pub struct SubgraphSelection {
+    pub depth: usize,
+    pub direction: Option<CliDirection>,
+    pub fields: Vec<String>,
+    pub entity_types: Vec<String>,
+}
+
+/// Extracts the entities around one or more entities in the current workspace entity graph,
+/// printing them or writing them to a file as DSL, or as JSON with the JSON format.
+pub fn extract_subgraph(
+    workspace_path: &PathBuf,
+    seeds: Vec<String>,
+    selection: SubgraphSelection,
+    output_path: Option<PathBuf>,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Extracting subgraph");
+    let graph = load_current_graph(workspace_path)?;
+
+    let mut options = SubgraphOptions::default().with_depth(selection.depth);
+    if let Some(direction) = selection.direction {
+        options = options.with_direction(direction.into());
+    }
+    for field in selection.fields {
+        options = options.with_field(FieldId::new(&field));
+    }
+    for entity_type in selection.entity_types {
+        options = options.with_entity_type(EntityType::new(&entity_type));
+    }
+
+    let seeds: Vec<EntityId> = seeds
+        .iter()
+        .map(|entity_id| EntityId::from(entity_id.as_str()))
+        .collect();
+    let subgraph = graph.subgraph(&seeds, &options).map_err(|e| {
+        match e {
+            GraphError::EntityNotFound(entity_id) => {
+                ui::error(&format!("Couldn't find entity with ID '{}'", entity_id))
+            }
+            e => ui::error_with_details("Couldn't extract the subgraph", &format!("{:?}", e)),
+        }
+        CliError::QueryError
+    })?;
+
+    // References leaving the subgraph are kept, so the entities still match their schemas
+    for dangling in subgraph.dangling_references() {
+        ui::warning(&format!(
+            "'{}' refers to '{}' in '{}', which is outside the subgraph",
+            dangling.entity_id, dangling.target_id, dangling.field_id
+        ));
+    }
+
+    let entities: Vec<Entity> = subgraph.entities().into_iter().cloned().collect();
+    let extracted = match output_format {
+        OutputFormat::Pretty => generate_dsl(&entities),
+        _ => serde_json::to_string_pretty(&entities).unwrap_or_default(),
+    };
+
+    match output_path {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, &extracted) {
+                ui::error_with_details("Couldn't write to file", &e.to_string());
+                return Err(CliError::FileError);
+            }
+
+            ui::success(&format!(
+                "Extracted {} entities to {}",
+                entities.len(),
+                path.display()
+            ));
+        }
+        None => {
+            ui::success(&format!("Extracted {} entities", entities.len()));
+            println!("{}", extracted);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use firm_core::{FieldId, compose_entity_id};
+use firm_lang::edit::set_entity_fields;
+use firm_lang::workspace::{Workspace, WorkspaceOptions};
+use std::{fs, path::PathBuf};
+
+use super::{build_workspace, load_workspace_files};
+use crate::errors::CliError;
+use crate::ui::{self, OutputFormat};
+
+/// Moves an entity to a new state and rewrites its source block.
+pub fn transition_entity(
+    workspace_path: &PathBuf,
+    options: &WorkspaceOptions,
+    entity_type: String,
+    entity_id: String,
+    state: String,
+    field: Option<String>,
+    output_format: OutputFormat,
+) -> Result<(), CliError> {
+    ui::header("Transitioning entity");
+    let mut workspace = Workspace::with_options(options.clone());
+    load_workspace_files(&workspace_path, &mut workspace).map_err(|_| CliError::BuildError)?;
+    let build = build_workspace(&mut workspace).map_err(|_| CliError::BuildError)?;
+
+    let id = compose_entity_id(&entity_type, &entity_id);
+    let Some(entity) = build.entities.iter().find(|entity| entity.id == id) else {
+        ui::error(&format!(
+            "Couldn't find '{}' entity with ID '{}'",
+            entity_type, entity_id
+        ));
+
+        return Err(CliError::QueryError);
+    };
+
+    let Some(schema) = build
+        .schemas
+        .iter()
+        .find(|schema| schema.entity_type == entity.entity_type)
+    else {
+        ui::error(&format!("Couldn't find schema for '{}'", entity_type));
+        return Err(CliError::QueryError);
+    };
+
+    // Use the given field, or the first field of the schema which declares states
+    let Some(field_id) = field.map(FieldId::new).or(schema.state_field().cloned()) else {
+        ui::error(&format!(
+            "Schema '{}' doesn't declare states for any field",
+            schema.entity_type
+        ));
+
+        return Err(CliError::InputError);
+    };
+
+    let (transitioned, changes) = match schema.transition_entity(entity, &field_id, &state) {
+        Ok(transitioned) => transitioned,
+        Err(e) => {
+            ui::error_with_details("Couldn't transition entity", &e.to_string());
+            return Err(CliError::InputError);
+        }
+    };
+
+    // Rewrite the entity in its source file, leaving the rest of the file as it was
+    let Some((source_path, edited_source)) = workspace
+        .find_entity_source(&id)
+        .and_then(|(path, parsed)| Some((path, set_entity_fields(parsed, &id, &changes)?)))
+    else {
+        ui::error(&format!("Couldn't find source for entity '{}'", id));
+        return Err(CliError::FileError);
+    };
+
+    if let Err(e) = fs::write(source_path, edited_source) {
+        ui::error_with_details("Couldn't write to file", &e.to_string());
+        return Err(CliError::FileError);
+    }
+
+    ui::success(&format!(
+        "Moved '{}' to '{}' in {}",
+        id,
+        state,
+        source_path.display()
+    ));
+
+    match output_format {
+        OutputFormat::Pretty => ui::pretty_output_entity_single(&transitioned),
+        _ => ui::json_output(&transitioned),
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use firm_core::graph::EntityGraph;
+use firm_core::search::SearchIndex;
 use std::{env, fs, path::PathBuf};
 
 use super::errors::CliError;
@@ -6,6 +7,7 @@ use super::ui::{self};
 
 pub const CURRENT_GRAPH_NAME: &str = "current.firm.graph";
 pub const BACKUP_GRAPH_NAME: &str = "backup.firm.graph";
+pub const SEARCH_INDEX_NAME: &str = "current.firm.index";
 
 /// Gets the Firm workspace path.
 /// If it was provided from CLI args, use that, otherwise use current working directory.
@@ -91,3 +93,51 @@ pub fn load_current_graph(workspace_path: &PathBuf) -> Result<EntityGraph, CliEr
 
     Ok(graph)
 }
+
+/// Saves the search index for an entity graph to the workspace root, next to the graph.
+pub fn save_search_index(workspace_path: &PathBuf, index: &SearchIndex) -> Result<(), CliError> {
+    let index_path = workspace_path.join(SEARCH_INDEX_NAME);
+
+    ui::debug("Saving search index");
+    let serialized_index = serde_json::to_string(&index).map_err(|e| {
+        ui::error_with_details("Failed to serialize search index", &e.to_string());
+        CliError::FileError
+    })?;
+
+    if let Err(e) = fs::write(&index_path, serialized_index) {
+        ui::error_with_details("Failed to write search index file", &e.to_string());
+        return Err(CliError::FileError);
+    }
+
+    ui::info(&format!("Search index saved to {}", index_path.display()));
+    Ok(())
+}
+
+/// Loads the search index from the workspace root.
+/// If there's no usable index, or it was built from a different graph, it's rebuilt from the graph instead.
+pub fn load_search_index(workspace_path: &PathBuf, graph: &EntityGraph) -> SearchIndex {
+    let index_path = workspace_path.join(SEARCH_INDEX_NAME);
+
+    ui::debug("Loading search index");
+    let loaded = fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<SearchIndex>(&content).ok());
+
+    match loaded {
+        Some(index) if index.is_current(graph) => {
+            ui::info(&format!(
+                "Search index loaded from {}",
+                index_path.display()
+            ));
+            index
+        }
+        Some(_) => {
+            ui::debug("Search index is out of date, rebuilding it from the graph");
+            SearchIndex::from_graph(graph)
+        }
+        None => {
+            ui::debug("Search index couldn't be loaded, rebuilding it from the graph");
+            SearchIndex::from_graph(graph)
+        }
+    }
+}
@@ -13,11 +13,13 @@ mod query;
 mod ui;
 
 use clap::Parser;
+use firm_core::compare::SortOrder;
 use std::process::ExitCode;
 
-use cli::{FirmCli, FirmCliCommand};
+use cli::{ExportCommand, FirmCli, FirmCliCommand, SchemaCommand};
 use commands::build_and_save_graph;
 use files::get_workspace_path;
+use ui::OutputFormat;
 
 fn main() -> ExitCode {
     let cli = FirmCli::parse();
@@ -34,9 +36,42 @@ fn main() -> ExitCode {
         Err(_) => return ExitCode::FAILURE,
     };
 
-    // Pre-build the graph unless we're using cache or doing a build command
-    if !cli.cached && cli.command != FirmCliCommand::Build {
-        match build_and_save_graph(&workspace_path) {
+    // JSON Schema output only describes schemas, not entities
+    let outputs_schemas = match &cli.command {
+        FirmCliCommand::Schema { .. } => true,
+        FirmCliCommand::List { entity_type, .. } => entity_type == "schema",
+        _ => false,
+    };
+    if cli.format == OutputFormat::JsonSchema && !outputs_schemas {
+        ui::error("The jsonschema format only applies to schema commands");
+        return ExitCode::FAILURE;
+    }
+
+    // Graph formats only describe the entity graph as a whole
+    let exports_graph = matches!(
+        cli.command,
+        FirmCliCommand::Export {
+            command: ExportCommand::Graph { .. }
+        }
+    );
+    if cli.format.is_graph() && !exports_graph {
+        ui::error(&format!(
+            "The {} format only applies to graph export",
+            cli.format
+        ));
+        return ExitCode::FAILURE;
+    }
+
+    let workspace_options = cli.workspace_options();
+
+    // Pre-build the graph unless we're using cache or doing a build command.
+    // Migrations also skip it, as entities may not match their schema until they are migrated.
+    let skip_prebuild = matches!(
+        cli.command,
+        FirmCliCommand::Build | FirmCliCommand::Migrate { .. }
+    );
+    if !cli.cached && !skip_prebuild {
+        match build_and_save_graph(&workspace_path, &workspace_options) {
             Ok(_) => (),
             Err(_) => return ExitCode::FAILURE,
         }
@@ -44,18 +79,54 @@ fn main() -> ExitCode {
 
     // Handle CLI subcommands
     let result = match cli.command {
-        FirmCliCommand::Build => build_and_save_graph(&workspace_path),
+        FirmCliCommand::Build => build_and_save_graph(&workspace_path, &workspace_options),
         FirmCliCommand::Get {
             entity_type,
             entity_id,
         } => commands::get_entity_by_id(&workspace_path, entity_type, entity_id, cli.format),
-        FirmCliCommand::List { entity_type } => {
+        FirmCliCommand::List {
+            entity_type,
+            sort,
+            descending,
+            total,
+            currency,
+            date,
+        } => {
             if entity_type == "schema" {
-                commands::list_schemas(&workspace_path, cli.format)
+                commands::list_schemas(&workspace_path, &workspace_options, cli.format)
             } else {
-                commands::list_entities_by_type(&workspace_path, entity_type, cli.format)
+                let total = total
+                    .zip(currency)
+                    .map(|(field, currency)| commands::ListTotal {
+                        field,
+                        currency,
+                        date,
+                    });
+                let order = if descending {
+                    SortOrder::Descending
+                } else {
+                    SortOrder::Ascending
+                };
+                commands::list_entities_by_type(
+                    &workspace_path,
+                    entity_type,
+                    sort.map(|field| (field, order)),
+                    total,
+                    cli.format,
+                )
             }
         }
+        FirmCliCommand::Search {
+            terms,
+            entity_types,
+            limit,
+        } => commands::search_entities(
+            &workspace_path,
+            terms.join(" "),
+            entity_types,
+            limit,
+            cli.format,
+        ),
         FirmCliCommand::Related {
             entity_type,
             entity_id,
@@ -67,9 +138,103 @@ fn main() -> ExitCode {
             direction,
             cli.format,
         ),
+        FirmCliCommand::Plan {
+            project_id,
+            start,
+            deadline,
+        } => commands::plan_project(&workspace_path, project_id, start, deadline, cli.format),
+        FirmCliCommand::Stats { entity_types, top } => {
+            commands::show_stats(&workspace_path, entity_types, top, cli.format)
+        }
+        FirmCliCommand::Subgraph {
+            seeds,
+            depth,
+            direction,
+            fields,
+            entity_types,
+            output,
+        } => commands::extract_subgraph(
+            &workspace_path,
+            seeds,
+            commands::SubgraphSelection {
+                depth,
+                direction,
+                fields,
+                entity_types,
+            },
+            output,
+            cli.format,
+        ),
         FirmCliCommand::Add { to_file } => {
-            commands::add_entity(&workspace_path, to_file, cli.format)
+            commands::add_entity(&workspace_path, &workspace_options, to_file, cli.format)
         }
+        FirmCliCommand::Transition {
+            entity_type,
+            entity_id,
+            state,
+            field,
+        } => commands::transition_entity(
+            &workspace_path,
+            &workspace_options,
+            entity_type,
+            entity_id,
+            state,
+            field,
+            cli.format,
+        ),
+        FirmCliCommand::Schema {
+            command:
+                SchemaCommand::Export {
+                    entity_type,
+                    output,
+                },
+        } => commands::export_schemas(
+            &workspace_path,
+            &workspace_options,
+            entity_type,
+            output,
+            cli.format,
+        ),
+        FirmCliCommand::Schema {
+            command: SchemaCommand::Generate { output },
+        } => commands::generate_schema_code(&workspace_path, &workspace_options, output),
+        FirmCliCommand::Migrate {
+            entity_type,
+            rename,
+            convert,
+            fill,
+            dry_run,
+        } => commands::migrate_entities(
+            &workspace_path,
+            &workspace_options,
+            entity_type,
+            commands::MigrationOperations {
+                renames: rename,
+                conversions: convert,
+                fills: fill,
+            },
+            dry_run,
+            cli.format,
+        ),
+        FirmCliCommand::Export {
+            command:
+                ExportCommand::Graph {
+                    label,
+                    entity_types,
+                    around,
+                    depth,
+                    output,
+                },
+        } => commands::export_graph(
+            &workspace_path,
+            commands::GraphExport {
+                label,
+                entity_types,
+                around: around.map(|entity_id| (entity_id, depth)),
+            },
+            output,
+            cli.format,
+        ),
     };
 
     result.map_or(ExitCode::FAILURE, |_| ExitCode::SUCCESS)
@@ -1,5 +1,7 @@
 use clap::ValueEnum;
 use console::Style;
+use convert_case::{Case, Casing};
+use firm_core::search::{SearchHit, Snippet};
 use firm_core::{Entity, EntitySchema};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::{fmt, time::Duration};
@@ -82,6 +84,16 @@ pub fn error_with_details(main_msg: &str, details: &str) {
 pub enum OutputFormat {
     Pretty,
     Json,
+    /// JSON Schema documents, which only apply to schemas.
+    #[value(name = "jsonschema")]
+    JsonSchema,
+    /// Graphviz DOT, which only applies to graph export.
+    Dot,
+    /// A Mermaid flowchart, which only applies to graph export.
+    Mermaid,
+    /// GraphML, which only applies to graph export.
+    #[value(name = "graphml")]
+    GraphMl,
 }
 
 impl fmt::Display for OutputFormat {
@@ -89,10 +101,24 @@ impl fmt::Display for OutputFormat {
         match self {
             OutputFormat::Pretty => write!(f, "pretty"),
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::JsonSchema => write!(f, "jsonschema"),
+            OutputFormat::Dot => write!(f, "dot"),
+            OutputFormat::Mermaid => write!(f, "mermaid"),
+            OutputFormat::GraphMl => write!(f, "graphml"),
         }
     }
 }
 
+impl OutputFormat {
+    /// Checks if this is one of the formats for exporting the entity graph.
+    pub fn is_graph(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Dot | OutputFormat::Mermaid | OutputFormat::GraphMl
+        )
+    }
+}
+
 impl Default for OutputFormat {
     fn default() -> Self {
         OutputFormat::Pretty
@@ -133,6 +159,38 @@ pub fn pretty_output_schema_list(schemas: &Vec<&EntitySchema>) {
     }
 }
 
+/// Outputs a search result in pretty format, with the matching words in its snippets highlighted.
+pub fn pretty_output_search_hit(hit: &SearchHit, snippets: &[Snippet]) {
+    println!(
+        "\n{} {}",
+        UiStyle::highlight().apply_to(&hit.entity_id),
+        UiStyle::dim().apply_to(format!("({:.2})", hit.score))
+    );
+
+    for snippet in snippets {
+        let mut line = String::new();
+        let mut position = 0;
+        for range in &snippet.highlights {
+            line.push_str(&snippet.text[position..range.start]);
+            line.push_str(
+                &Style::new()
+                    .yellow()
+                    .bold()
+                    .apply_to(&snippet.text[range.clone()])
+                    .to_string(),
+            );
+            position = range.end;
+        }
+        line.push_str(&snippet.text[position..]);
+
+        println!(
+            "{}: {}",
+            snippet.field_id.as_str().to_case(Case::Sentence),
+            line
+        );
+    }
+}
+
 /// Outputs a serde-serializable object in json format.
 pub fn json_output<T: serde::Serialize>(data: &T) {
     if let Ok(json) = serde_json::to_string_pretty(data) {
@@ -140,6 +198,23 @@ pub fn json_output<T: serde::Serialize>(data: &T) {
     }
 }
 
+/// Outputs a unified diff, coloring added and removed lines.
+pub fn diff_output(diff: &str) {
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", UiStyle::highlight().apply_to(line));
+        } else if line.starts_with('+') {
+            println!("{}", Style::new().green().apply_to(line));
+        } else if line.starts_with('-') {
+            println!("{}", Style::new().red().apply_to(line));
+        } else if line.starts_with("@@") {
+            println!("{}", UiStyle::dim().apply_to(line));
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
 /// Creates a spinner progress indicator.
 pub fn spinner(msg: &str) -> ProgressBar {
     let pb = ProgressBar::new_spinner();
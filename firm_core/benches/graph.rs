@@ -0,0 +1,128 @@
+//! Benchmarks for building and querying large entity graphs.
+//!
+//! Run with `cargo bench -p firm_core`.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use firm_core::graph::EntityGraph;
+use firm_core::{Entity, EntityId, EntityType, FieldId, FieldValue, ReferenceValue};
+
+const SIZES: [usize; 2] = [10_000, 100_000];
+
+/// Generates a workspace-like set of people, organizations and tasks which reference each other.
+fn entities(count: usize) -> Vec<Entity> {
+    let organizations = (count / 100).max(1);
+    let people = (count / 10).max(1);
+
+    (0..count)
+        .map(|index| {
+            if index < organizations {
+                Entity::new(
+                    EntityId::new(format!("organization.org_{}", index)),
+                    EntityType::new("organization"),
+                )
+                .with_field(FieldId::new("name"), format!("Organization {}", index))
+                .with_field(FieldId::new("industry"), "Software")
+            } else if index < organizations + people {
+                Entity::new(
+                    EntityId::new(format!("person.person_{}", index)),
+                    EntityType::new("person"),
+                )
+                .with_field(FieldId::new("name"), format!("Person {}", index))
+                .with_field(
+                    FieldId::new("email"),
+                    format!("person{}@example.com", index),
+                )
+                .with_field(
+                    FieldId::new("organization_ref"),
+                    FieldValue::Reference(ReferenceValue::Entity(EntityId::new(format!(
+                        "organization.org_{}",
+                        index % organizations
+                    )))),
+                )
+            } else {
+                Entity::new(
+                    EntityId::new(format!("task.task_{}", index)),
+                    EntityType::new("task"),
+                )
+                .with_field(FieldId::new("name"), format!("Task {}", index))
+                .with_field(FieldId::new("description"), "Something to do")
+                .with_field(FieldId::new("is_completed"), index % 3 == 0)
+                .with_field(FieldId::new("priority"), (index % 5) as i64)
+                .with_field(
+                    FieldId::new("assignee_ref"),
+                    FieldValue::Reference(ReferenceValue::Entity(EntityId::new(format!(
+                        "person.person_{}",
+                        organizations + index % people
+                    )))),
+                )
+            }
+        })
+        .collect()
+}
+
+fn graph(count: usize) -> EntityGraph {
+    let mut graph = EntityGraph::new();
+    graph.add_entities(entities(count)).unwrap();
+    graph.build();
+    graph
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(
+                || entities(size),
+                |entities| {
+                    let mut graph = EntityGraph::new();
+                    graph.add_entities(entities).unwrap();
+                    graph.build();
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+
+    for size in SIZES {
+        let graph = graph(size);
+        let id = EntityId::new(format!("task.task_{}", size - 1));
+        let field = FieldId::new("assignee_ref");
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                graph
+                    .get_entity(black_box(&id))
+                    .and_then(|entity| entity.get_field(black_box(&field)))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_list_by_type(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_by_type");
+
+    for size in SIZES {
+        let graph = graph(size);
+        let entity_type = EntityType::new("person");
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| graph.list_by_type(black_box(&entity_type)).len())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_build, bench_lookup, bench_list_by_type);
+criterion_main!(benches);
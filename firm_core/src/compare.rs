@@ -0,0 +1,485 @@
+//! Ordering of field values, and sorting and filtering of entities by their fields.
+//!
+//! Values compare by their meaning rather than their representation: integers and floats
+//! numerically, dates and datetimes chronologically (a date sorting at midnight UTC of its day),
+//! currencies only within the same currency, quantities after converting between units, and
+//! strings lexically.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::{DateTime, NaiveTime, Utc};
+
+use crate::field::{Currency, NaiveDate};
+use crate::quantity::Dimension;
+use crate::schema::Comparison;
+use crate::{Entity, FieldId, FieldType, FieldValue};
+
+/// The errors that can occur when comparing two field values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareError {
+    /// The value types have no ordering between them.
+    IncomparableTypes(FieldType, FieldType),
+    /// Currency amounts can only be compared in the same currency.
+    MixedCurrencies(Currency, Currency),
+    /// Quantities can only be compared in units of the same dimension.
+    IncompatibleUnits(String, String),
+    /// A float value is not a number.
+    NotANumber,
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareError::IncomparableTypes(left, right) => {
+                write!(f, "Cannot compare {} with {}", left, right)
+            }
+            CompareError::MixedCurrencies(left, right) => write!(
+                f,
+                "Cannot compare amounts in {} with amounts in {}",
+                left.code(),
+                right.code()
+            ),
+            CompareError::IncompatibleUnits(left, right) => write!(
+                f,
+                "Cannot compare quantities in '{}' with quantities in '{}'",
+                left, right
+            ),
+            CompareError::NotANumber => write!(f, "Cannot compare a float which is not a number"),
+        }
+    }
+}
+
+impl std::error::Error for CompareError {}
+
+/// The direction to sort entities in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl FieldValue {
+    /// Orders this value against another value.
+    ///
+    /// Returns an error if the values are of types which can't be ordered against each other.
+    pub fn compare(&self, other: &FieldValue) -> Result<Ordering, CompareError> {
+        match (self, other) {
+            (FieldValue::Integer(a), FieldValue::Integer(b)) => Ok(a.cmp(b)),
+            (FieldValue::Integer(a), FieldValue::Float(b)) => compare_floats(*a as f64, *b),
+            (FieldValue::Float(a), FieldValue::Integer(b)) => compare_floats(*a, *b as f64),
+            (FieldValue::Float(a), FieldValue::Float(b)) => compare_floats(*a, *b),
+            (
+                FieldValue::Currency {
+                    amount: a,
+                    currency: currency_a,
+                },
+                FieldValue::Currency {
+                    amount: b,
+                    currency: currency_b,
+                },
+            ) => {
+                if currency_a == currency_b {
+                    Ok(a.cmp(b))
+                } else {
+                    Err(CompareError::MixedCurrencies(*currency_a, *currency_b))
+                }
+            }
+            (FieldValue::DateTime(a), FieldValue::DateTime(b)) => Ok(a.cmp(b)),
+            (FieldValue::Date(a), FieldValue::Date(b)) => Ok(a.cmp(b)),
+            // A date sorts as midnight UTC of that day, before any datetime at that same instant,
+            // so mixed dates and datetimes still have a total order
+            (FieldValue::Date(a), FieldValue::DateTime(b)) => {
+                Ok(date_instant(a).cmp(&b.to_utc()).then(Ordering::Less))
+            }
+            (FieldValue::DateTime(a), FieldValue::Date(b)) => {
+                Ok(a.to_utc().cmp(&date_instant(b)).then(Ordering::Greater))
+            }
+            (FieldValue::Duration(a), FieldValue::Duration(b)) => Ok(a.cmp(b)),
+            // Quantities in compatible units are compared in the unit of the left side
+            (FieldValue::Quantity(a), FieldValue::Quantity(b)) => match b.convert_to(&a.unit) {
+                Some(converted) => compare_floats(a.value, converted.value),
+                None => Err(CompareError::IncompatibleUnits(
+                    a.unit.clone(),
+                    b.unit.clone(),
+                )),
+            },
+            (FieldValue::String(a), FieldValue::String(b)) => Ok(a.cmp(b)),
+            (FieldValue::Boolean(a), FieldValue::Boolean(b)) => Ok(a.cmp(b)),
+            (FieldValue::Path(a), FieldValue::Path(b)) => Ok(a.cmp(b)),
+            _ => Err(CompareError::IncomparableTypes(
+                self.get_type(),
+                other.get_type(),
+            )),
+        }
+    }
+
+    /// Orders this value against another when checking whether it matches, like in rules and filters.
+    ///
+    /// Unlike [`FieldValue::compare`], a date covers its whole day here, so it's equal to any
+    /// datetime on that day in the datetime's own offset. That isn't a total order, so it's
+    /// not used for sorting.
+    pub fn compare_for_matching(&self, other: &FieldValue) -> Result<Ordering, CompareError> {
+        match (self, other) {
+            (FieldValue::Date(a), FieldValue::DateTime(b)) => Ok(a.cmp(&b.date_naive())),
+            (FieldValue::DateTime(a), FieldValue::Date(b)) => Ok(a.date_naive().cmp(b)),
+            _ => self.compare(other),
+        }
+    }
+}
+
+impl Entity {
+    /// Orders this entity against another by the value of a field.
+    /// Entities without the field are ordered after those with it.
+    pub fn compare_by_field(
+        &self,
+        other: &Entity,
+        field_id: &FieldId,
+    ) -> Result<Ordering, CompareError> {
+        match (self.get_field(field_id), other.get_field(field_id)) {
+            (Some(a), Some(b)) => a.compare(b),
+            (Some(_), None) => Ok(Ordering::Less),
+            (None, Some(_)) => Ok(Ordering::Greater),
+            (None, None) => Ok(Ordering::Equal),
+        }
+    }
+}
+
+/// A group of values which can be ordered against each other, and against no values outside it.
+#[derive(Debug, PartialEq)]
+enum ComparabilityClass<'a> {
+    Number,
+    Currency(Currency),
+    /// Dates and datetimes, which order chronologically.
+    Time,
+    Duration,
+    /// Quantities in known units of a dimension.
+    Dimension(Dimension),
+    /// Quantities in a unit Firm doesn't know, which only compare in that same unit.
+    Unit(&'a str),
+    Other(FieldType),
+}
+
+impl<'a> ComparabilityClass<'a> {
+    fn of(value: &'a FieldValue) -> Self {
+        match value {
+            FieldValue::Integer(_) | FieldValue::Float(_) => ComparabilityClass::Number,
+            FieldValue::Currency { currency, .. } => ComparabilityClass::Currency(*currency),
+            FieldValue::Date(_) | FieldValue::DateTime(_) => ComparabilityClass::Time,
+            FieldValue::Duration(_) => ComparabilityClass::Duration,
+            FieldValue::Quantity(quantity) => match quantity.dimension() {
+                Some(dimension) => ComparabilityClass::Dimension(dimension),
+                None => ComparabilityClass::Unit(&quantity.unit),
+            },
+            _ => ComparabilityClass::Other(value.get_type()),
+        }
+    }
+}
+
+/// Sorts entities by the value of a field, keeping the order of entities with equal values.
+///
+/// Entities without the field are placed last in either order. Returns an error, leaving the
+/// entities unsorted, if any two values of the field can't be compared.
+pub fn sort_by_field(
+    entities: &mut [&Entity],
+    field_id: &FieldId,
+    order: SortOrder,
+) -> Result<(), CompareError> {
+    // Check up front that the values form a single class and each compares with the first
+    // of its class, so the sort itself can't fail
+    let mut classes: Vec<(ComparabilityClass, &FieldValue)> = Vec::new();
+    for value in entities
+        .iter()
+        .filter_map(|entity| entity.get_field(field_id))
+    {
+        let class = ComparabilityClass::of(value);
+        match classes.iter().find(|(existing, _)| *existing == class) {
+            Some((_, first)) => {
+                first.compare(value)?;
+            }
+            None => classes.push((class, value)),
+        }
+    }
+
+    // Values in different classes never compare, so this reports why the first two don't
+    if let [(_, first), (_, other), ..] = classes.as_slice() {
+        return Err(first.compare(other).err().unwrap_or_else(|| {
+            CompareError::IncomparableTypes(first.get_type(), other.get_type())
+        }));
+    }
+
+    entities.sort_by(
+        |a, b| match (a.get_field(field_id), b.get_field(field_id)) {
+            (Some(a), Some(b)) => {
+                let ordering = a.compare(b).unwrap_or(Ordering::Equal);
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            }
+            _ => a.compare_by_field(b, field_id).unwrap_or(Ordering::Equal),
+        },
+    );
+
+    Ok(())
+}
+
+/// Gets the entities whose field compares to a value in a given way.
+///
+/// Entities without the field don't match. Returns an error if a field value can't be compared
+/// with the value.
+pub fn filter_by_field<'a>(
+    entities: &[&'a Entity],
+    field_id: &FieldId,
+    comparison: Comparison,
+    value: &FieldValue,
+) -> Result<Vec<&'a Entity>, CompareError> {
+    let mut matching = Vec::new();
+    for entity in entities {
+        match entity.get_field(field_id) {
+            Some(field_value) if comparison.holds_for(field_value.compare_for_matching(value)?) => {
+                matching.push(*entity)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(matching)
+}
+
+fn compare_floats(a: f64, b: f64) -> Result<Ordering, CompareError> {
+    a.partial_cmp(&b).ok_or(CompareError::NotANumber)
+}
+
+/// The instant a date sorts at among datetimes, which is midnight UTC of that day.
+fn date_instant(date: &NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Decimal, FixedOffset};
+    use crate::quantity::Quantity;
+    use crate::{EntityId, EntityType};
+    use chrono::TimeZone;
+
+    fn task(id: &str, priority: Option<i64>) -> Entity {
+        let entity = Entity::new(EntityId::new(id), EntityType::new("task"));
+        match priority {
+            Some(priority) => entity.with_field(FieldId::new("priority"), priority),
+            None => entity,
+        }
+    }
+
+    #[test]
+    fn test_compare_numbers() {
+        assert_eq!(
+            FieldValue::Integer(2).compare(&FieldValue::Float(2.5)),
+            Ok(Ordering::Less)
+        );
+        assert_eq!(
+            FieldValue::Float(3.0).compare(&FieldValue::Integer(3)),
+            Ok(Ordering::Equal)
+        );
+        assert_eq!(
+            FieldValue::Float(f64::NAN).compare(&FieldValue::Float(1.0)),
+            Err(CompareError::NotANumber)
+        );
+    }
+
+    #[test]
+    fn test_compare_dates() {
+        let earlier = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        let later = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        assert_eq!(earlier.compare(&later), Ok(Ordering::Less));
+        assert_eq!(later.compare(&earlier), Ok(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_sort_mixed_dates_and_datetimes_on_the_same_day() {
+        let at = |hour| {
+            FieldValue::DateTime(
+                FixedOffset::east_opt(0)
+                    .unwrap()
+                    .with_ymd_and_hms(2025, 3, 1, hour, 0, 0)
+                    .unwrap(),
+            )
+        };
+        let day = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+
+        // The date sorts at midnight, before a datetime at the same instant
+        assert_eq!(day.compare(&at(0)), Ok(Ordering::Less));
+        assert_eq!(at(0).compare(&day), Ok(Ordering::Greater));
+        assert_eq!(day.compare(&at(9)), Ok(Ordering::Less));
+        assert_eq!(at(9).compare(&at(17)), Ok(Ordering::Less));
+
+        let entities: Vec<Entity> = [("late", at(17)), ("day", day), ("early", at(9))]
+            .into_iter()
+            .map(|(id, due)| {
+                Entity::new(EntityId::new(id), EntityType::new("task"))
+                    .with_field(FieldId::new("due"), due)
+            })
+            .collect();
+        let mut sorted: Vec<&Entity> = entities.iter().collect();
+
+        sort_by_field(&mut sorted, &FieldId::new("due"), SortOrder::Ascending).unwrap();
+        let ids: Vec<&str> = sorted.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["day", "early", "late"]);
+    }
+
+    #[test]
+    fn test_compare_currencies() {
+        let small = FieldValue::Currency {
+            amount: Decimal::new(100, 0),
+            currency: Currency::EUR,
+        };
+        let large = FieldValue::Currency {
+            amount: Decimal::new(250, 0),
+            currency: Currency::EUR,
+        };
+        let other = FieldValue::Currency {
+            amount: Decimal::new(250, 0),
+            currency: Currency::USD,
+        };
+
+        assert_eq!(small.compare(&large), Ok(Ordering::Less));
+        assert_eq!(
+            small.compare(&other),
+            Err(CompareError::MixedCurrencies(Currency::EUR, Currency::USD))
+        );
+    }
+
+    #[test]
+    fn test_compare_quantities() {
+        let hours = FieldValue::Quantity(Quantity::new(48.0, "h"));
+        let days = FieldValue::Quantity(Quantity::new(1.0, "d"));
+        let storage = FieldValue::Quantity(Quantity::new(1.0, "TB"));
+
+        assert_eq!(hours.compare(&days), Ok(Ordering::Greater));
+        assert_eq!(
+            hours.compare(&storage),
+            Err(CompareError::IncompatibleUnits(
+                "h".to_string(),
+                "TB".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_compare_incomparable_types() {
+        assert_eq!(
+            FieldValue::String("high".to_string()).compare(&FieldValue::Integer(1)),
+            Err(CompareError::IncomparableTypes(
+                FieldType::String,
+                FieldType::Integer
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sort_by_field() {
+        let (low, high, unset, middle) = (
+            task("low", Some(1)),
+            task("high", Some(3)),
+            task("unset", None),
+            task("middle", Some(2)),
+        );
+        let mut entities = vec![&low, &high, &unset, &middle];
+
+        sort_by_field(
+            &mut entities,
+            &FieldId::new("priority"),
+            SortOrder::Ascending,
+        )
+        .unwrap();
+        let ids: Vec<&str> = entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["low", "middle", "high", "unset"]);
+
+        sort_by_field(
+            &mut entities,
+            &FieldId::new("priority"),
+            SortOrder::Descending,
+        )
+        .unwrap();
+        let ids: Vec<&str> = entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["high", "middle", "low", "unset"]);
+    }
+
+    #[test]
+    fn test_sort_by_field_with_incomparable_values() {
+        let numbered = task("numbered", Some(1));
+        let named = Entity::new(EntityId::new("named"), EntityType::new("task"))
+            .with_field(FieldId::new("priority"), "high");
+        let mut entities = vec![&numbered, &named];
+
+        let result = sort_by_field(
+            &mut entities,
+            &FieldId::new("priority"),
+            SortOrder::Ascending,
+        );
+        assert!(matches!(result, Err(CompareError::IncomparableTypes(..))));
+    }
+
+    #[test]
+    fn test_sort_by_field_with_mixed_classes() {
+        let amount = |id: &str, amount: i64, currency: Currency| {
+            Entity::new(EntityId::new(id), EntityType::new("deal")).with_field(
+                FieldId::new("value"),
+                FieldValue::Currency {
+                    amount: Decimal::new(amount, 0),
+                    currency,
+                },
+            )
+        };
+        let (first, second, third) = (
+            amount("first", 100, Currency::EUR),
+            amount("second", 200, Currency::EUR),
+            amount("third", 300, Currency::USD),
+        );
+        let mut entities = vec![&first, &second, &third];
+
+        assert_eq!(
+            sort_by_field(&mut entities, &FieldId::new("value"), SortOrder::Ascending),
+            Err(CompareError::MixedCurrencies(Currency::EUR, Currency::USD))
+        );
+
+        let measured = |id: &str, quantity: Quantity| {
+            Entity::new(EntityId::new(id), EntityType::new("deal"))
+                .with_field(FieldId::new("size"), FieldValue::Quantity(quantity))
+        };
+        let (hours, days, storage) = (
+            measured("hours", Quantity::new(48.0, "h")),
+            measured("days", Quantity::new(1.0, "d")),
+            measured("storage", Quantity::new(1.0, "TB")),
+        );
+        let mut entities = vec![&hours, &days, &storage];
+
+        assert!(matches!(
+            sort_by_field(&mut entities, &FieldId::new("size"), SortOrder::Ascending),
+            Err(CompareError::IncompatibleUnits(..))
+        ));
+        let ids: Vec<&str> = entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["hours", "days", "storage"]);
+    }
+
+    #[test]
+    fn test_filter_by_field() {
+        let (low, high, unset) = (
+            task("low", Some(1)),
+            task("high", Some(3)),
+            task("unset", None),
+        );
+        let entities = vec![&low, &high, &unset];
+
+        let matching = filter_by_field(
+            &entities,
+            &FieldId::new("priority"),
+            Comparison::GreaterOrEqual,
+            &FieldValue::Float(1.5),
+        )
+        .unwrap();
+        assert_eq!(matching, vec![&high]);
+    }
+}
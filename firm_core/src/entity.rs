@@ -1,5 +1,6 @@
 use convert_case::{Case, Casing};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fmt;
 
 use super::{EntityId, EntityType, FieldId, FieldValue};
@@ -9,7 +10,7 @@ use super::{EntityId, EntityType, FieldId, FieldValue};
 pub struct Entity {
     pub id: EntityId,
     pub entity_type: EntityType,
-    pub fields: Vec<(FieldId, FieldValue)>,
+    pub fields: Fields,
 }
 
 impl Entity {
@@ -18,7 +19,7 @@ impl Entity {
         Self {
             id: id,
             entity_type: entity_type,
-            fields: Vec::new(),
+            fields: Fields::new(),
         }
     }
 
@@ -33,10 +34,7 @@ impl Entity {
 
     /// Try to get a entity field value for a given field ID.
     pub fn get_field(&self, id: &FieldId) -> Option<&FieldValue> {
-        self.fields
-            .iter()
-            .find(|(field_id, _)| field_id == id)
-            .map(|(_, field_value)| field_value)
+        self.fields.get(id)
     }
 }
 
@@ -56,6 +54,183 @@ impl fmt::Display for Entity {
     }
 }
 
+/// The fields of an entity, in the order they were written.
+///
+/// Fields are kept in a list with an index by field ID, so lookups don't scan the entity.
+/// If a field ID occurs more than once, lookups find the first one.
+#[derive(Debug, Clone, Default)]
+pub struct Fields {
+    entries: Vec<(FieldId, FieldValue)>,
+    index: HashMap<FieldId, usize>,
+}
+
+impl Fields {
+    /// Creates an empty set of fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a field after the existing fields.
+    pub fn push(&mut self, (id, value): (FieldId, FieldValue)) {
+        self.index.entry(id.clone()).or_insert(self.entries.len());
+        self.entries.push((id, value));
+    }
+
+    /// Sets the value of a field, replacing it in place if it exists or appending it otherwise.
+    /// Returns the value it replaced.
+    pub fn insert(&mut self, id: FieldId, value: FieldValue) -> Option<FieldValue> {
+        match self.get_mut(&id) {
+            Some(existing) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.push((id, value));
+                None
+            }
+        }
+    }
+
+    /// Gets the value of a field.
+    pub fn get(&self, id: &FieldId) -> Option<&FieldValue> {
+        self.index
+            .get(id)
+            .map(|&position| &self.entries[position].1)
+    }
+
+    /// Gets a mutable reference to the value of a field.
+    pub fn get_mut(&mut self, id: &FieldId) -> Option<&mut FieldValue> {
+        self.index
+            .get(id)
+            .map(|&position| &mut self.entries[position].1)
+    }
+
+    /// Checks if a field is set.
+    pub fn contains(&self, id: &FieldId) -> bool {
+        self.index.contains_key(id)
+    }
+
+    /// Renames every field with an ID, keeping its position.
+    pub fn rename(&mut self, from: &FieldId, to: &FieldId) {
+        for (id, _) in self.entries.iter_mut() {
+            if id == from {
+                *id = to.clone();
+            }
+        }
+        self.reindex();
+    }
+
+    /// Keeps only the fields the predicate returns true for, in their order.
+    pub fn retain(&mut self, mut keep: impl FnMut(&FieldId, &FieldValue) -> bool) {
+        self.entries.retain(|(id, value)| keep(id, value));
+        self.reindex();
+    }
+
+    /// Gets the number of fields.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if there are no fields.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the fields in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, (FieldId, FieldValue)> {
+        self.entries.iter()
+    }
+
+    /// Iterates over the fields in order, with mutable values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&FieldId, &mut FieldValue)> {
+        self.entries.iter_mut().map(|(id, value)| (&*id, value))
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (position, (id, _)) in self.entries.iter().enumerate() {
+            self.index.entry(id.clone()).or_insert(position);
+        }
+    }
+}
+
+impl PartialEq for Fields {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl PartialEq<Vec<(FieldId, FieldValue)>> for Fields {
+    fn eq(&self, other: &Vec<(FieldId, FieldValue)>) -> bool {
+        &self.entries == other
+    }
+}
+
+impl From<Vec<(FieldId, FieldValue)>> for Fields {
+    fn from(entries: Vec<(FieldId, FieldValue)>) -> Self {
+        let mut fields = Self {
+            entries,
+            index: HashMap::new(),
+        };
+        fields.reindex();
+        fields
+    }
+}
+
+impl<const N: usize> From<[(FieldId, FieldValue); N]> for Fields {
+    fn from(entries: [(FieldId, FieldValue); N]) -> Self {
+        Self::from(Vec::from(entries))
+    }
+}
+
+impl From<Fields> for Vec<(FieldId, FieldValue)> {
+    fn from(fields: Fields) -> Self {
+        fields.entries
+    }
+}
+
+impl FromIterator<(FieldId, FieldValue)> for Fields {
+    fn from_iter<I: IntoIterator<Item = (FieldId, FieldValue)>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl Extend<(FieldId, FieldValue)> for Fields {
+    fn extend<I: IntoIterator<Item = (FieldId, FieldValue)>>(&mut self, iter: I) {
+        for field in iter {
+            self.push(field);
+        }
+    }
+}
+
+impl IntoIterator for Fields {
+    type Item = (FieldId, FieldValue);
+    type IntoIter = std::vec::IntoIter<(FieldId, FieldValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Fields {
+    type Item = &'a (FieldId, FieldValue);
+    type IntoIter = std::slice::Iter<'a, (FieldId, FieldValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+// Fields serialize as their list of pairs, like they were stored before they were indexed
+impl Serialize for Fields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<(FieldId, FieldValue)>::deserialize(deserializer).map(Self::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,4 +269,57 @@ mod tests {
         assert_eq!(person.entity_type, EntityType::new("person"));
         assert_eq!(organization.entity_type, EntityType::new("organization"));
     }
+
+    #[test]
+    fn test_fields_keep_order_and_first_value() {
+        let mut fields = Fields::new();
+        fields.push((FieldId::new("name"), FieldValue::from("John Doe")));
+        fields.push((FieldId::new("email"), FieldValue::from("john@example.com")));
+        fields.push((FieldId::new("name"), FieldValue::from("Johnny")));
+
+        let ids: Vec<&str> = fields.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["name", "email", "name"]);
+        assert_eq!(
+            fields.get(&FieldId::new("name")),
+            Some(&FieldValue::from("John Doe"))
+        );
+    }
+
+    #[test]
+    fn test_fields_insert_and_retain() {
+        let mut fields: Fields = vec![
+            (FieldId::new("name"), FieldValue::from("John Doe")),
+            (FieldId::new("email"), FieldValue::from("john@example.com")),
+            (FieldId::new("phone"), FieldValue::from("555-0100")),
+        ]
+        .into();
+
+        let replaced = fields.insert(FieldId::new("email"), FieldValue::from("jd@example.com"));
+        assert_eq!(replaced, Some(FieldValue::from("john@example.com")));
+        assert_eq!(
+            fields.insert(FieldId::new("age"), FieldValue::Integer(42)),
+            None
+        );
+
+        fields.retain(|id, _| id.as_str() != "name");
+        let ids: Vec<&str> = fields.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["email", "phone", "age"]);
+        assert_eq!(
+            fields.get(&FieldId::new("phone")),
+            Some(&FieldValue::from("555-0100"))
+        );
+        assert!(!fields.contains(&FieldId::new("name")));
+    }
+
+    #[test]
+    fn test_fields_serialize_as_pairs() {
+        let person = Entity::new(EntityId::new("john_doe"), EntityType::new("person"))
+            .with_field(FieldId::new("name"), "John Doe");
+
+        let json = serde_json::to_value(&person.fields).unwrap();
+        assert_eq!(json, serde_json::json!([["name", {"String": "John Doe"}]]));
+
+        let fields: Fields = serde_json::from_value(json).unwrap();
+        assert_eq!(fields, person.fields);
+    }
 }
@@ -1,12 +1,18 @@
 use std::fmt;
 
-use chrono::{DateTime, FixedOffset};
-use iso_currency::Currency;
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use crate::{EntityId, FieldId};
+use crate::{
+    EntityId, FieldId,
+    quantity::Quantity,
+    temporal::{Duration, Recurrence},
+};
+
+// Re-exported so code using field values doesn't need its own dependencies
+pub use chrono::{DateTime, FixedOffset, NaiveDate};
+pub use iso_currency::Currency;
+pub use rust_decimal::Decimal;
 
 /// The supported types of an entity field.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -19,6 +25,11 @@ pub enum FieldType {
     Reference,
     List,
     DateTime,
+    Date,
+    Duration,
+    Recurrence,
+    Quantity,
+    Record,
     Path,
 }
 
@@ -33,6 +44,11 @@ impl fmt::Display for FieldType {
             FieldType::Reference => write!(f, "Reference"),
             FieldType::List => write!(f, "List"),
             FieldType::DateTime => write!(f, "DateTime"),
+            FieldType::Date => write!(f, "Date"),
+            FieldType::Duration => write!(f, "Duration"),
+            FieldType::Recurrence => write!(f, "Recurrence"),
+            FieldType::Quantity => write!(f, "Quantity"),
+            FieldType::Record => write!(f, "Record"),
             FieldType::Path => write!(f, "Path"),
         }
     }
@@ -61,10 +77,20 @@ pub enum FieldValue {
     String(String),
     Integer(i64),
     Float(f64),
-    Currency { amount: Decimal, currency: Currency },
+    Currency {
+        amount: Decimal,
+        currency: Currency,
+    },
     Reference(ReferenceValue),
     List(Vec<FieldValue>),
     DateTime(DateTime<FixedOffset>),
+    Date(NaiveDate),
+    Duration(Duration),
+    Recurrence(Recurrence),
+    Quantity(Quantity),
+    /// Named fields nested inside a field, like the parts of an address.
+    #[serde(with = "record_fields")]
+    Record(Vec<(FieldId, FieldValue)>),
     Path(PathBuf),
 }
 
@@ -88,6 +114,21 @@ impl fmt::Display for FieldValue {
                 )
             }
             FieldValue::DateTime(val) => write!(f, "{}", val),
+            FieldValue::Date(val) => write!(f, "{}", val.format("%Y-%m-%d")),
+            FieldValue::Duration(val) => write!(f, "{}", val),
+            FieldValue::Recurrence(val) => write!(f, "{}", val),
+            FieldValue::Quantity(val) => write!(f, "{}", val),
+            FieldValue::Record(fields) => {
+                write!(
+                    f,
+                    "{{{}}}",
+                    fields
+                        .iter()
+                        .map(|(id, value)| format!("{} = {}", id, value))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
             FieldValue::Path(val) => write!(f, "{}", val.display()),
         }
     }
@@ -109,13 +150,67 @@ impl FieldValue {
             FieldValue::Reference(ReferenceValue::Field(_, _)) => FieldType::Reference,
             FieldValue::List(_) => FieldType::List,
             FieldValue::DateTime(_) => FieldType::DateTime,
+            FieldValue::Date(_) => FieldType::Date,
+            FieldValue::Duration(_) => FieldType::Duration,
+            FieldValue::Recurrence(_) => FieldType::Recurrence,
+            FieldValue::Quantity(_) => FieldType::Quantity,
+            FieldValue::Record(_) => FieldType::Record,
             FieldValue::Path(_) => FieldType::Path,
         }
     }
 
     /// Checks if the field value has the expected type.
+    /// Datetimes also count as dates, since they can be read as the day they fall on.
     pub fn is_type(&self, expected: &FieldType) -> bool {
-        &self.get_type() == expected
+        match (self, expected) {
+            (FieldValue::DateTime(_), FieldType::Date) => true,
+            _ => &self.get_type() == expected,
+        }
+    }
+
+    /// Reads the value as the expected type, if it is written in a form of that type.
+    /// Datetimes are read as the date they fall on in their own offset.
+    /// Strings like `"2h30m"`, `"every 2 weeks"` or `"120 h"` are read as durations, recurrences or quantities.
+    /// Returns `None` if the value isn't a form of the expected type.
+    pub fn coerce_to(&self, expected: &FieldType) -> Option<FieldValue> {
+        match (self, expected) {
+            (FieldValue::DateTime(datetime), FieldType::Date) => {
+                Some(FieldValue::Date(datetime.date_naive()))
+            }
+            (FieldValue::String(raw), FieldType::Duration) => {
+                raw.parse().ok().map(FieldValue::Duration)
+            }
+            (FieldValue::String(raw), FieldType::Recurrence) => {
+                raw.parse().ok().map(FieldValue::Recurrence)
+            }
+            (FieldValue::String(raw), FieldType::Quantity) => {
+                raw.parse().ok().map(FieldValue::Quantity)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets a field nested in a record value.
+    pub fn get_record_field(&self, id: &FieldId) -> Option<&FieldValue> {
+        match self {
+            FieldValue::Record(fields) => fields
+                .iter()
+                .find(|(field_id, _)| field_id == id)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Adds a duration to a date or datetime value, like a start date plus an estimate.
+    /// Dates only move by the whole days of the duration.
+    pub fn add_duration(&self, duration: &Duration) -> Option<FieldValue> {
+        match self {
+            FieldValue::DateTime(datetime) => duration
+                .add_to_datetime(*datetime)
+                .map(FieldValue::DateTime),
+            FieldValue::Date(date) => duration.add_to_date(*date).map(FieldValue::Date),
+            _ => None,
+        }
     }
 }
 
@@ -161,6 +256,41 @@ impl From<DateTime<FixedOffset>> for FieldValue {
     }
 }
 
+/// Convert from NaiveDate to FieldValue.
+impl From<NaiveDate> for FieldValue {
+    fn from(value: NaiveDate) -> Self {
+        FieldValue::Date(value)
+    }
+}
+
+/// Convert from Duration to FieldValue.
+impl From<Duration> for FieldValue {
+    fn from(value: Duration) -> Self {
+        FieldValue::Duration(value)
+    }
+}
+
+/// Convert from Quantity to FieldValue.
+impl From<Quantity> for FieldValue {
+    fn from(value: Quantity) -> Self {
+        FieldValue::Quantity(value)
+    }
+}
+
+/// Convert from Recurrence to FieldValue.
+impl From<Recurrence> for FieldValue {
+    fn from(value: Recurrence) -> Self {
+        FieldValue::Recurrence(value)
+    }
+}
+
+/// Convert from a list of named fields to a record FieldValue.
+impl From<Vec<(FieldId, FieldValue)>> for FieldValue {
+    fn from(value: Vec<(FieldId, FieldValue)>) -> Self {
+        FieldValue::Record(value)
+    }
+}
+
 /// Convert from Vec<FieldValue> to FieldValue.
 impl From<Vec<FieldValue>> for FieldValue {
     fn from(value: Vec<FieldValue>) -> Self {
@@ -175,6 +305,54 @@ impl From<PathBuf> for FieldValue {
     }
 }
 
+/// Serializes record fields as a map from field ID to value, keeping their order.
+mod record_fields {
+    use serde::{
+        Deserializer, Serializer,
+        de::{MapAccess, Visitor},
+        ser::SerializeMap,
+    };
+    use std::fmt;
+
+    use super::FieldValue;
+    use crate::FieldId;
+
+    pub fn serialize<S: Serializer>(
+        fields: &[(FieldId, FieldValue)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for (field_id, value) in fields {
+            map.serialize_entry(field_id, value)?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(FieldId, FieldValue)>, D::Error> {
+        deserializer.deserialize_map(RecordVisitor)
+    }
+
+    struct RecordVisitor;
+
+    impl<'de> Visitor<'de> for RecordVisitor {
+        type Value = Vec<(FieldId, FieldValue)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a map of record fields")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut fields = Vec::new();
+            while let Some(entry) = map.next_entry()? {
+                fields.push(entry);
+            }
+            Ok(fields)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +370,45 @@ mod tests {
         assert!(string_value.is_type(&FieldType::String));
     }
 
+    #[test]
+    fn test_coerce_strings_to_temporal_values_and_quantities() {
+        assert_eq!(
+            FieldValue::from("2h30m").coerce_to(&FieldType::Duration),
+            Some(FieldValue::Duration(Duration::from_minutes(150)))
+        );
+        assert_eq!(
+            FieldValue::from("every 2 weeks").coerce_to(&FieldType::Recurrence),
+            Some(FieldValue::Recurrence(Recurrence::new(
+                2,
+                crate::temporal::RecurrenceUnit::Week
+            )))
+        );
+        assert_eq!(
+            FieldValue::from("5 TB").coerce_to(&FieldType::Quantity),
+            Some(FieldValue::Quantity(Quantity::new(5.0, "TB")))
+        );
+        assert_eq!(
+            FieldValue::from("soon").coerce_to(&FieldType::Duration),
+            None
+        );
+        assert_eq!(
+            FieldValue::from("2h30m").coerce_to(&FieldType::String),
+            None
+        );
+    }
+
+    #[test]
+    fn test_datetime_is_type_date() {
+        let datetime = FieldValue::DateTime(
+            chrono::DateTime::parse_from_rfc3339("2025-03-01T09:00:00+01:00").unwrap(),
+        );
+        assert!(datetime.is_type(&FieldType::DateTime));
+        assert!(datetime.is_type(&FieldType::Date));
+
+        let date = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        assert!(!date.is_type(&FieldType::DateTime));
+    }
+
     #[test]
     fn test_field_from_bool() {
         let field: FieldValue = true.into();
@@ -231,6 +448,40 @@ mod tests {
         assert_eq!(field, FieldValue::DateTime(dt));
     }
 
+    #[test]
+    fn test_field_from_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let field: FieldValue = date.into();
+        assert_eq!(field, FieldValue::Date(date));
+        assert_eq!(field.get_type(), FieldType::Date);
+        assert_eq!(field.to_string(), "2025-03-01");
+    }
+
+    #[test]
+    fn test_add_duration_to_dates() {
+        use chrono::{FixedOffset, TimeZone};
+
+        let duration = Duration::from_hours(36);
+        let date = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        assert_eq!(
+            date.add_duration(&duration),
+            Some(FieldValue::Date(
+                NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+            ))
+        );
+
+        let offset = FixedOffset::east_opt(0).unwrap();
+        let datetime = FieldValue::DateTime(offset.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(
+            datetime.add_duration(&duration),
+            Some(FieldValue::DateTime(
+                offset.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap()
+            ))
+        );
+
+        assert_eq!(FieldValue::Integer(1).add_duration(&duration), None);
+    }
+
     #[test]
     fn test_field_from_vec() {
         let values = vec![
@@ -365,6 +616,65 @@ mod tests {
         assert_eq!(deserialized, field);
     }
 
+    #[test]
+    fn test_duration_and_recurrence_serialization() {
+        use crate::temporal::RecurrenceUnit;
+
+        let field = FieldValue::Duration(Duration::from_minutes(150));
+        let serialized = serde_json::to_string(&field).unwrap();
+        assert_eq!(serialized, r#"{"Duration":"2h30m"}"#);
+        let deserialized: FieldValue = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, field);
+
+        let field = FieldValue::Recurrence(Recurrence::new(2, RecurrenceUnit::Week));
+        let serialized = serde_json::to_string(&field).unwrap();
+        assert_eq!(serialized, r#"{"Recurrence":"every 2 weeks"}"#);
+        let deserialized: FieldValue = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, field);
+    }
+
+    #[test]
+    fn test_quantity_serialization() {
+        let field = FieldValue::Quantity(Quantity::new(120.0, "h"));
+        let serialized = serde_json::to_string(&field).unwrap();
+        assert_eq!(serialized, r#"{"Quantity":"120 h"}"#);
+        let deserialized: FieldValue = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, field);
+        assert_eq!(field.get_type(), FieldType::Quantity);
+    }
+
+    #[test]
+    fn test_record_field_value() {
+        let record = FieldValue::Record(vec![
+            (FieldId::new("street"), FieldValue::from("Main Street 1")),
+            (FieldId::new("city"), FieldValue::from("Oslo")),
+        ]);
+
+        assert_eq!(record.get_type(), FieldType::Record);
+        assert_eq!(
+            record.get_record_field(&FieldId::new("city")),
+            Some(&FieldValue::from("Oslo"))
+        );
+        assert_eq!(record.to_string(), "{street = Main Street 1, city = Oslo}");
+
+        let serialized = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"Record":{"street":{"String":"Main Street 1"},"city":{"String":"Oslo"}}}"#
+        );
+        let deserialized: FieldValue = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, record);
+    }
+
+    #[test]
+    fn test_date_serialization() {
+        let field = FieldValue::Date(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        let serialized = serde_json::to_string(&field).unwrap();
+        assert_eq!(serialized, r#"{"Date":"2025-03-01"}"#);
+        let deserialized: FieldValue = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, field);
+    }
+
     #[test]
     fn test_string_list_serialization() {
         let field = FieldValue::List(vec![
@@ -0,0 +1,433 @@
+use petgraph::Direction;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fmt;
+
+use super::EntityGraph;
+use crate::{Entity, EntityId, EntityType};
+
+/// The number of relationships to and from an entity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Degree {
+    pub entity_id: EntityId,
+    /// References from other entities to this one.
+    pub incoming: usize,
+    /// References from this entity to others.
+    pub outgoing: usize,
+    /// The share of other entities this one is directly related to, from 0 to 1.
+    pub centrality: f64,
+}
+
+impl Degree {
+    /// Gets the number of relationships in either direction.
+    pub fn total(&self) -> usize {
+        self.incoming + self.outgoing
+    }
+}
+
+/// Counts and connectivity of the entities of one type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeStats {
+    pub entity_type: EntityType,
+    pub entities: usize,
+    /// Entities which no other entity references.
+    pub orphans: Vec<EntityId>,
+    /// Entities which reference no other entity.
+    pub leaves: Vec<EntityId>,
+}
+
+/// A summary of the entities in a graph and how they're connected.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphStats {
+    pub entities: usize,
+    pub relationships: usize,
+    /// Statistics for each entity type, in alphabetical order.
+    pub types: Vec<TypeStats>,
+    /// The sizes of the weakly connected components, largest first.
+    pub components: Vec<usize>,
+    /// The entities with the most relationships, most connected first.
+    pub most_connected: Vec<Degree>,
+    /// The entities in each reference cycle.
+    pub cycles: Vec<Vec<EntityId>>,
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Entities: {}", self.entities)?;
+        writeln!(f, "Relationships: {}", self.relationships)?;
+
+        let isolated = self.components.iter().filter(|&&size| size == 1).count();
+        writeln!(
+            f,
+            "Components: {} ({} isolated entities)",
+            self.components.len(),
+            isolated
+        )?;
+
+        for type_stats in &self.types {
+            writeln!(f, "\n{}", type_stats.entity_type)?;
+            writeln!(f, "- Entities: {}", type_stats.entities)?;
+            writeln!(f, "- Orphans: {}", id_list(&type_stats.orphans))?;
+            writeln!(f, "- Leaves: {}", id_list(&type_stats.leaves))?;
+        }
+
+        if !self.most_connected.is_empty() {
+            writeln!(f, "\nMost connected")?;
+            for degree in &self.most_connected {
+                writeln!(
+                    f,
+                    "- {}: {} in, {} out ({:.2} centrality)",
+                    degree.entity_id, degree.incoming, degree.outgoing, degree.centrality
+                )?;
+            }
+        }
+
+        if !self.cycles.is_empty() {
+            writeln!(f, "\nReference cycles")?;
+            for cycle in &self.cycles {
+                writeln!(f, "- {}", join_ids(cycle))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a count of entities followed by their IDs.
+fn id_list(ids: &[EntityId]) -> String {
+    match ids {
+        [] => "0".to_string(),
+        _ => format!("{} ({})", ids.len(), join_ids(ids)),
+    }
+}
+
+fn join_ids(ids: &[EntityId]) -> String {
+    ids.iter()
+        .map(|id| id.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+impl EntityGraph {
+    /// Gets the number of relationships to and from an entity, and its degree centrality.
+    ///
+    /// Each reference counts as a relationship, while centrality counts each related entity once.
+    pub fn degree(&self, id: &EntityId) -> Option<Degree> {
+        self.entity_map
+            .get(id)
+            .map(|&index| self.node_degree(index))
+    }
+
+    /// Gets the entities with the most relationships, most connected first.
+    pub fn most_connected(&self, limit: usize) -> Vec<Degree> {
+        let mut degrees: Vec<Degree> = self
+            .graph
+            .node_indices()
+            .map(|index| self.node_degree(index))
+            .filter(|degree| degree.total() > 0)
+            .collect();
+
+        degrees.sort_by(|a, b| {
+            b.total()
+                .cmp(&a.total())
+                .then_with(|| a.entity_id.cmp(&b.entity_id))
+        });
+        degrees.truncate(limit);
+        degrees
+    }
+
+    /// Groups entities which are connected by relationships in either direction.
+    ///
+    /// The largest groups come first, and entities without relationships are in groups of their own.
+    pub fn components(&self) -> Vec<Vec<&Entity>> {
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
+
+        for start in self.graph.node_indices() {
+            if !visited.insert(start) {
+                continue;
+            }
+
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(index) = queue.pop_front() {
+                for neighbour in self.graph.neighbors_undirected(index) {
+                    if visited.insert(neighbour) {
+                        component.push(neighbour);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            component.sort();
+            components.push(component);
+        }
+
+        // The sort is stable, so components of the same size keep the order of the graph
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+        components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|index| &self.graph[index])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Gets the entities of a type which no other entity references.
+    pub fn orphans(&self, entity_type: &EntityType) -> Vec<&Entity> {
+        self.entities_without_edges(entity_type, Direction::Incoming)
+    }
+
+    /// Gets the entities of a type which reference no other entity.
+    pub fn leaves(&self, entity_type: &EntityType) -> Vec<&Entity> {
+        self.entities_without_edges(entity_type, Direction::Outgoing)
+    }
+
+    /// Finds groups of entities which reference each other in a cycle, including entities
+    /// which reference themselves.
+    pub fn reference_cycles(&self) -> Vec<Vec<&Entity>> {
+        let mut cycles: Vec<Vec<NodeIndex>> = tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|nodes| match nodes.as_slice() {
+                [index] => self.graph.contains_edge(*index, *index),
+                _ => true,
+            })
+            .collect();
+
+        for cycle in &mut cycles {
+            cycle.sort();
+        }
+        cycles.sort();
+
+        cycles
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(|index| &self.graph[index]).collect())
+            .collect()
+    }
+
+    /// Summarizes the entities in the graph and how they're connected.
+    pub fn stats(&self, most_connected: usize) -> GraphStats {
+        let entity_types: BTreeSet<&EntityType> = self.entity_type_map.keys().collect();
+        let ids = |entities: Vec<&Entity>| -> Vec<EntityId> {
+            entities.iter().map(|entity| entity.id.clone()).collect()
+        };
+
+        GraphStats {
+            entities: self.graph.node_count(),
+            relationships: self.graph.edge_count(),
+            types: entity_types
+                .into_iter()
+                .map(|entity_type| TypeStats {
+                    entity_type: entity_type.clone(),
+                    entities: self.entity_type_map[entity_type].len(),
+                    orphans: ids(self.orphans(entity_type)),
+                    leaves: ids(self.leaves(entity_type)),
+                })
+                .collect(),
+            components: self
+                .components()
+                .iter()
+                .map(|component| component.len())
+                .collect(),
+            most_connected: self.most_connected(most_connected),
+            cycles: self.reference_cycles().into_iter().map(ids).collect(),
+        }
+    }
+
+    fn node_degree(&self, index: NodeIndex) -> Degree {
+        let neighbours: HashSet<NodeIndex> = self
+            .graph
+            .neighbors_undirected(index)
+            .filter(|&neighbour| neighbour != index)
+            .collect();
+        let others = self.graph.node_count().saturating_sub(1);
+
+        Degree {
+            entity_id: self.graph[index].id.clone(),
+            incoming: self
+                .graph
+                .edges_directed(index, Direction::Incoming)
+                .count(),
+            outgoing: self
+                .graph
+                .edges_directed(index, Direction::Outgoing)
+                .count(),
+            centrality: if others == 0 {
+                0.0
+            } else {
+                neighbours.len() as f64 / others as f64
+            },
+        }
+    }
+
+    fn entities_without_edges(
+        &self,
+        entity_type: &EntityType,
+        direction: Direction,
+    ) -> Vec<&Entity> {
+        match self.entity_type_map.get(entity_type) {
+            Some(nodes) => nodes
+                .iter()
+                .filter(|&&index| {
+                    // References an entity makes to itself don't connect it to anything
+                    self.graph
+                        .edges_directed(index, direction)
+                        .all(|edge| edge.source() == edge.target())
+                })
+                .map(|&index| &self.graph[index])
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FieldId, FieldValue, ReferenceValue};
+
+    fn reference(id: &str) -> FieldValue {
+        FieldValue::Reference(ReferenceValue::Entity(EntityId::new(id)))
+    }
+
+    fn ids(entities: &[&Entity]) -> Vec<String> {
+        entities
+            .iter()
+            .map(|entity| entity.id.to_string())
+            .collect()
+    }
+
+    /// Two people at one organization, an organization without contacts and a pair of
+    /// tasks which block each other.
+    fn sample_graph() -> EntityGraph {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entities(vec![
+                Entity::new(
+                    EntityId::new("organization.acme"),
+                    EntityType::new("organization"),
+                ),
+                Entity::new(
+                    EntityId::new("organization.globex"),
+                    EntityType::new("organization"),
+                ),
+                Entity::new(EntityId::new("person.jane"), EntityType::new("person"))
+                    .with_field(FieldId::new("employer_ref"), reference("organization.acme")),
+                Entity::new(EntityId::new("person.john"), EntityType::new("person"))
+                    .with_field(FieldId::new("employer_ref"), reference("organization.acme"))
+                    .with_field(FieldId::new("manager_ref"), reference("person.jane")),
+                Entity::new(EntityId::new("task.design"), EntityType::new("task"))
+                    .with_field(FieldId::new("blocked_by_ref"), reference("task.build")),
+                Entity::new(EntityId::new("task.build"), EntityType::new("task"))
+                    .with_field(FieldId::new("blocked_by_ref"), reference("task.design")),
+            ])
+            .unwrap();
+        graph.build();
+        graph
+    }
+
+    #[test]
+    fn test_degree() {
+        let graph = sample_graph();
+
+        let degree = graph.degree(&EntityId::new("person.jane")).unwrap();
+        assert_eq!(degree.incoming, 1);
+        assert_eq!(degree.outgoing, 1);
+        assert_eq!(degree.centrality, 2.0 / 5.0);
+
+        assert_eq!(graph.degree(&EntityId::new("person.missing")), None);
+    }
+
+    #[test]
+    fn test_most_connected() {
+        let graph = sample_graph();
+        let most_connected = graph.most_connected(2);
+
+        // Entities with as many relationships are ordered by ID
+        assert_eq!(most_connected.len(), 2);
+        assert_eq!(
+            most_connected[0].entity_id,
+            EntityId::new("organization.acme")
+        );
+        assert_eq!(most_connected[0].total(), 2);
+        assert_eq!(most_connected[1].entity_id, EntityId::new("person.jane"));
+    }
+
+    #[test]
+    fn test_components() {
+        let graph = sample_graph();
+        let components = graph.components();
+
+        assert_eq!(components.len(), 3);
+        assert_eq!(
+            ids(&components[0]),
+            vec!["organization.acme", "person.jane", "person.john"]
+        );
+        assert_eq!(ids(&components[1]), vec!["task.design", "task.build"]);
+        assert_eq!(ids(&components[2]), vec!["organization.globex"]);
+    }
+
+    #[test]
+    fn test_orphans_and_leaves() {
+        let graph = sample_graph();
+
+        assert_eq!(
+            ids(&graph.orphans(&EntityType::new("organization"))),
+            vec!["organization.globex"]
+        );
+        assert_eq!(
+            ids(&graph.orphans(&EntityType::new("person"))),
+            vec!["person.john"]
+        );
+        assert_eq!(
+            ids(&graph.leaves(&EntityType::new("organization"))),
+            vec!["organization.acme", "organization.globex"]
+        );
+        assert!(graph.leaves(&EntityType::new("task")).is_empty());
+    }
+
+    #[test]
+    fn test_reference_cycles() {
+        let graph = sample_graph();
+        let cycles = graph.reference_cycles();
+
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(ids(&cycles[0]), vec!["task.design", "task.build"]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = sample_graph().stats(3);
+
+        assert_eq!(stats.entities, 6);
+        assert_eq!(stats.relationships, 5);
+        assert_eq!(stats.components, vec![3, 2, 1]);
+        assert_eq!(stats.most_connected.len(), 3);
+        assert_eq!(
+            stats.cycles,
+            vec![vec![
+                EntityId::new("task.design"),
+                EntityId::new("task.build")
+            ]]
+        );
+
+        let types: Vec<(&str, usize)> = stats
+            .types
+            .iter()
+            .map(|type_stats| (type_stats.entity_type.as_str(), type_stats.entities))
+            .collect();
+        assert_eq!(types, vec![("organization", 2), ("person", 2), ("task", 2)]);
+
+        let output = stats.to_string();
+        assert!(output.contains("Components: 3 (1 isolated entities)"));
+        assert!(output.contains("- Orphans: 1 (organization.globex)"));
+        assert!(output.contains("- Leaves: 0"));
+        assert!(output.contains("- task.design, task.build"));
+    }
+}
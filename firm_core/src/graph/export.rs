@@ -0,0 +1,460 @@
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+use super::{EntityGraph, GraphError, SubgraphOptions};
+use crate::{Entity, EntityId, EntityType, FieldId};
+
+/// Fill colours for entity types, assigned to the types in the export in alphabetical order.
+const TYPE_COLORS: [&str; 10] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+    "#d9d9d9", "#bc80bd",
+];
+
+/// The formats an entity graph can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT, for `dot -Tsvg`.
+    Dot,
+    /// A Mermaid flowchart, for Markdown documents.
+    Mermaid,
+    /// GraphML, for tools like Gephi and yEd.
+    GraphMl,
+}
+
+/// Options for exporting an entity graph.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// The field to label entities with, which falls back to the entity ID.
+    pub label_field: Option<FieldId>,
+    /// The entity types to include, or all types if empty.
+    pub entity_types: Vec<EntityType>,
+    /// Only includes entities within a number of relationships of an entity.
+    pub around: Option<(EntityId, usize)>,
+}
+
+impl ExportOptions {
+    /// Builder method to label entities with a field, like `name`.
+    pub fn with_label_field(mut self, field_id: FieldId) -> Self {
+        self.label_field = Some(field_id);
+        self
+    }
+
+    /// Builder method to include an entity type. Without any, all types are included.
+    pub fn with_entity_type(mut self, entity_type: EntityType) -> Self {
+        self.entity_types.push(entity_type);
+        self
+    }
+
+    /// Builder method to only include entities within a number of relationships of an entity.
+    pub fn around(mut self, entity_id: EntityId, depth: usize) -> Self {
+        self.around = Some((entity_id, depth));
+        self
+    }
+}
+
+/// An entity and the colour of its type, ready to be written.
+struct ExportNode<'a> {
+    index: NodeIndex,
+    entity: &'a Entity,
+    label: String,
+    color: &'static str,
+}
+
+/// A relationship between two exported entities.
+struct ExportEdge<'a> {
+    source: NodeIndex,
+    target: NodeIndex,
+    source_id: &'a EntityId,
+    target_id: &'a EntityId,
+    field: &'a FieldId,
+}
+
+impl EntityGraph {
+    /// Exports the graph, or the part of it selected by the options, in a format for visualization.
+    ///
+    /// Entities are labelled with the chosen field and coloured by type, and relationships are
+    /// labelled with the field they come from.
+    pub fn export(
+        &self,
+        format: GraphFormat,
+        options: &ExportOptions,
+    ) -> Result<String, GraphError> {
+        let (nodes, edges) = self.export_selection(options)?;
+
+        Ok(match format {
+            GraphFormat::Dot => write_dot(&nodes, &edges),
+            GraphFormat::Mermaid => write_mermaid(&nodes, &edges),
+            GraphFormat::GraphMl => write_graphml(&nodes, &edges),
+        })
+    }
+
+    /// Selects the entities and relationships to export.
+    fn export_selection(
+        &self,
+        options: &ExportOptions,
+    ) -> Result<(Vec<ExportNode<'_>>, Vec<ExportEdge<'_>>), GraphError> {
+        let nearby = match &options.around {
+            Some((entity_id, depth)) => {
+                let &seed = self
+                    .entity_map
+                    .get(entity_id)
+                    .ok_or_else(|| GraphError::EntityNotFound(entity_id.clone()))?;
+                let options = SubgraphOptions::default().with_depth(*depth);
+                Some(self.neighbourhood(&[seed], &options))
+            }
+            None => None,
+        };
+
+        let selected: Vec<NodeIndex> = self
+            .graph
+            .node_indices()
+            .filter(|index| nearby.as_ref().is_none_or(|nearby| nearby.contains(index)))
+            .filter(|&index| {
+                options.entity_types.is_empty()
+                    || options
+                        .entity_types
+                        .contains(&self.graph[index].entity_type)
+            })
+            .collect();
+
+        let types: BTreeSet<&str> = selected
+            .iter()
+            .map(|&index| self.graph[index].entity_type.as_str())
+            .collect();
+        let color_of = |entity_type: &EntityType| {
+            let position = types
+                .iter()
+                .position(|t| *t == entity_type.as_str())
+                .unwrap_or_default();
+            TYPE_COLORS[position % TYPE_COLORS.len()]
+        };
+
+        let nodes: Vec<ExportNode> = selected
+            .iter()
+            .map(|&index| {
+                let entity = &self.graph[index];
+                let label = options
+                    .label_field
+                    .as_ref()
+                    .and_then(|field_id| entity.get_field(field_id))
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| entity.id.to_string());
+
+                ExportNode {
+                    index,
+                    entity,
+                    label,
+                    color: color_of(&entity.entity_type),
+                }
+            })
+            .collect();
+
+        let included: HashSet<NodeIndex> = selected.into_iter().collect();
+        let edges = self
+            .graph
+            .edge_references()
+            .filter(|edge| included.contains(&edge.source()) && included.contains(&edge.target()))
+            .map(|edge| ExportEdge {
+                source: edge.source(),
+                target: edge.target(),
+                source_id: &self.graph[edge.source()].id,
+                target_id: &self.graph[edge.target()].id,
+                field: edge.weight().from_field(),
+            })
+            .collect();
+
+        Ok((nodes, edges))
+    }
+}
+
+fn write_dot(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut dot = String::from("digraph firm {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box, style=\"rounded,filled\"];\n\n");
+
+    for node in nodes {
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [label=\"{}\", fillcolor=\"{}\", tooltip=\"{}\"];",
+            escape_dot(node.entity.id.as_str()),
+            escape_dot(&node.label),
+            node.color,
+            node.entity.entity_type
+        );
+    }
+
+    if !edges.is_empty() {
+        dot.push('\n');
+    }
+    for edge in edges {
+        let _ = writeln!(
+            dot,
+            "    \"{}\" -> \"{}\" [label=\"{}\"];",
+            escape_dot(edge.source_id.as_str()),
+            escape_dot(edge.target_id.as_str()),
+            escape_dot(edge.field.as_str())
+        );
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn write_mermaid(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    // Mermaid node IDs can't contain periods, so nodes are numbered by their index in the graph
+    let mut mermaid = String::from("flowchart LR\n");
+    for node in nodes {
+        let _ = writeln!(
+            mermaid,
+            "    n{}[\"{}\"]",
+            node.index.index(),
+            escape_mermaid(&node.label)
+        );
+    }
+
+    for edge in edges {
+        let _ = writeln!(
+            mermaid,
+            "    n{} -->|{}| n{}",
+            edge.source.index(),
+            escape_mermaid(edge.field.as_str()),
+            edge.target.index()
+        );
+    }
+
+    // Class names are prefixed so types like `default` don't clash with Mermaid's own classes,
+    // and numbered when two types only differ in characters which aren't allowed in them
+    let mut classes: Vec<(&EntityType, &str, String)> = Vec::new();
+    for node in nodes {
+        let entity_type = &node.entity.entity_type;
+        if !classes.iter().any(|(t, _, _)| *t == entity_type) {
+            let mut class_name = format!("type_{}", sanitize_mermaid_class(entity_type.as_str()));
+            if classes.iter().any(|(_, _, name)| *name == class_name) {
+                class_name = format!("{}_{}", class_name, classes.len());
+            }
+            classes.push((entity_type, node.color, class_name));
+        }
+    }
+    for (entity_type, color, class_name) in classes {
+        let members: Vec<String> = nodes
+            .iter()
+            .filter(|node| &node.entity.entity_type == entity_type)
+            .map(|node| format!("n{}", node.index.index()))
+            .collect();
+
+        let _ = writeln!(mermaid, "    classDef {} fill:{}", class_name, color);
+        let _ = writeln!(mermaid, "    class {} {}", members.join(","), class_name);
+    }
+
+    mermaid
+}
+
+fn write_graphml(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut graphml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    graphml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    graphml
+        .push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+    graphml.push_str("  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n");
+    graphml
+        .push_str("  <key id=\"color\" for=\"node\" attr.name=\"color\" attr.type=\"string\"/>\n");
+    graphml
+        .push_str("  <key id=\"field\" for=\"edge\" attr.name=\"field\" attr.type=\"string\"/>\n");
+    graphml.push_str("  <graph id=\"firm\" edgedefault=\"directed\">\n");
+
+    for node in nodes {
+        let _ = writeln!(
+            graphml,
+            "    <node id=\"{}\">",
+            escape_xml(node.entity.id.as_str())
+        );
+        let _ = writeln!(
+            graphml,
+            "      <data key=\"label\">{}</data>",
+            escape_xml(&node.label)
+        );
+        let _ = writeln!(
+            graphml,
+            "      <data key=\"type\">{}</data>",
+            escape_xml(node.entity.entity_type.as_str())
+        );
+        let _ = writeln!(graphml, "      <data key=\"color\">{}</data>", node.color);
+        graphml.push_str("    </node>\n");
+    }
+
+    for edge in edges {
+        let _ = writeln!(
+            graphml,
+            "    <edge source=\"{}\" target=\"{}\">",
+            escape_xml(edge.source_id.as_str()),
+            escape_xml(edge.target_id.as_str())
+        );
+        let _ = writeln!(
+            graphml,
+            "      <data key=\"field\">{}</data>",
+            escape_xml(edge.field.as_str())
+        );
+        graphml.push_str("    </edge>\n");
+    }
+
+    graphml.push_str("  </graph>\n</graphml>\n");
+    graphml
+}
+
+fn escape_dot(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value
+        .replace('"', "#quot;")
+        .replace('|', "#124;")
+        .replace('\n', " ")
+}
+
+/// Replaces the characters which aren't letters, digits or underscores, so the name is a valid class.
+fn sanitize_mermaid_class(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FieldValue, ReferenceValue};
+
+    fn reference(id: &str) -> FieldValue {
+        FieldValue::Reference(ReferenceValue::Entity(EntityId::new(id)))
+    }
+
+    fn sample_graph() -> EntityGraph {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entities(vec![
+                Entity::new(
+                    EntityId::new("organization.acme"),
+                    EntityType::new("organization"),
+                )
+                .with_field(FieldId::new("name"), "Acme \"Corp\""),
+                Entity::new(EntityId::new("person.jane"), EntityType::new("person"))
+                    .with_field(FieldId::new("name"), "Jane")
+                    .with_field(FieldId::new("employer_ref"), reference("organization.acme")),
+                Entity::new(EntityId::new("task.launch"), EntityType::new("task"))
+                    .with_field(FieldId::new("name"), "Launch")
+                    .with_field(FieldId::new("assignee_ref"), reference("person.jane")),
+            ])
+            .unwrap();
+        graph.build();
+        graph
+    }
+
+    #[test]
+    fn test_export_dot() {
+        let options = ExportOptions::default().with_label_field(FieldId::new("name"));
+        let dot = sample_graph().export(GraphFormat::Dot, &options).unwrap();
+
+        assert!(dot.starts_with("digraph firm {"));
+        assert!(dot.contains(
+            "\"organization.acme\" [label=\"Acme \\\"Corp\\\"\", fillcolor=\"#8dd3c7\", tooltip=\"organization\"];"
+        ));
+        assert!(dot.contains("\"person.jane\" -> \"organization.acme\" [label=\"employer_ref\"];"));
+        assert!(dot.contains("\"task.launch\" -> \"person.jane\" [label=\"assignee_ref\"];"));
+    }
+
+    #[test]
+    fn test_export_mermaid() {
+        let options = ExportOptions::default().with_label_field(FieldId::new("name"));
+        let mermaid = sample_graph()
+            .export(GraphFormat::Mermaid, &options)
+            .unwrap();
+
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("    n0[\"Acme #quot;Corp#quot;\"]"));
+        assert!(mermaid.contains("    n1 -->|employer_ref| n0"));
+        assert!(mermaid.contains("    classDef type_person fill:#ffffb3"));
+        assert!(mermaid.contains("    class n2 type_task"));
+    }
+
+    #[test]
+    fn test_export_mermaid_class_names() {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entities(vec![
+                Entity::new(EntityId::new("default.one"), EntityType::new("default")),
+                // Types built from raw strings keep characters which aren't allowed in class names
+                Entity::new(
+                    EntityId::from("sales-lead.two"),
+                    EntityType::from("sales-lead"),
+                ),
+                Entity::new(
+                    EntityId::from("sales lead.three"),
+                    EntityType::from("sales lead"),
+                ),
+            ])
+            .unwrap();
+        graph.build();
+        let mermaid = graph
+            .export(GraphFormat::Mermaid, &ExportOptions::default())
+            .unwrap();
+
+        assert!(mermaid.contains("    class n0 type_default\n"));
+        assert!(mermaid.contains("    class n1 type_sales_lead\n"));
+        assert!(mermaid.contains("    class n2 type_sales_lead_2\n"));
+        assert!(!mermaid.contains("classDef default"));
+    }
+
+    #[test]
+    fn test_export_graphml() {
+        let graphml = sample_graph()
+            .export(GraphFormat::GraphMl, &ExportOptions::default())
+            .unwrap();
+
+        assert!(graphml.contains("<node id=\"person.jane\">"));
+        assert!(graphml.contains("<data key=\"label\">person.jane</data>"));
+        assert!(graphml.contains("<edge source=\"task.launch\" target=\"person.jane\">"));
+        assert!(graphml.ends_with("</graphml>\n"));
+    }
+
+    #[test]
+    fn test_export_filters_by_type() {
+        let options = ExportOptions::default()
+            .with_entity_type(EntityType::new("person"))
+            .with_entity_type(EntityType::new("task"));
+        let dot = sample_graph().export(GraphFormat::Dot, &options).unwrap();
+
+        assert!(!dot.contains("organization.acme"));
+        assert!(dot.contains("\"task.launch\" -> \"person.jane\""));
+    }
+
+    #[test]
+    fn test_export_around_entity() {
+        let graph = sample_graph();
+
+        let options = ExportOptions::default().around(EntityId::new("organization.acme"), 1);
+        let dot = graph.export(GraphFormat::Dot, &options).unwrap();
+        assert!(dot.contains("\"person.jane\" -> \"organization.acme\""));
+        assert!(!dot.contains("task.launch"));
+
+        let options = ExportOptions::default().around(EntityId::new("organization.missing"), 1);
+        assert_eq!(
+            graph.export(GraphFormat::Dot, &options),
+            Err(GraphError::EntityNotFound(EntityId::new(
+                "organization.missing"
+            )))
+        );
+    }
+}
@@ -6,11 +6,19 @@ use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+mod analytics;
+mod export;
 mod graph_errors;
 mod query;
+mod schedule;
+mod subgraph;
 
+pub use analytics::{Degree, GraphStats, TypeStats};
+pub use export::{ExportOptions, GraphFormat};
 pub use graph_errors::GraphError;
 pub use petgraph::Direction;
+pub use schedule::{DEPENDS_ON_FIELD, ESTIMATE_FIELD, Schedule, ScheduleError, ScheduledTask};
+pub use subgraph::{DanglingReference, SubgraphOptions};
 
 use crate::{Entity, EntityId, EntityType, FieldId, FieldValue, ReferenceValue};
 
@@ -26,6 +34,16 @@ pub enum Relationship {
     },
 }
 
+impl Relationship {
+    /// Gets the field on the source entity which the relationship comes from.
+    pub fn from_field(&self) -> &FieldId {
+        match self {
+            Relationship::EntityReference { from_field } => from_field,
+            Relationship::FieldReference { from_field, .. } => from_field,
+        }
+    }
+}
+
 /// The entity graph tracks all Firm entities and their relationships.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityGraph {
@@ -56,7 +74,7 @@ impl EntityGraph {
         self.entity_type_map.clear();
     }
 
-    /// Adds a new entity to the graph.
+    /// Adds a new entity to the graph, which takes ownership of it.
     /// Note: After an entity is added, the graph should be re-built.
     pub fn add_entity(&mut self, entity: Entity) -> Result<(), GraphError> {
         debug!("Adding new entity '{}' to graph", entity.id);
@@ -66,11 +84,14 @@ impl EntityGraph {
             return Err(GraphError::EntityAlreadyExists(entity.id));
         }
 
-        let node_index = self.graph.add_node(entity.clone());
-        self.entity_map.insert(entity.id.clone(), node_index);
+        let id = entity.id.clone();
+        let entity_type = entity.entity_type.clone();
+
+        let node_index = self.graph.add_node(entity);
+        self.entity_map.insert(id, node_index);
 
         self.entity_type_map
-            .entry(entity.entity_type)
+            .entry(entity_type)
             .or_insert_with(Vec::new)
             .push(node_index);
 
@@ -79,6 +100,9 @@ impl EntityGraph {
 
     /// Adds a collection of entities to the graph.
     pub fn add_entities(&mut self, entities: Vec<Entity>) -> Result<(), GraphError> {
+        self.graph.reserve_nodes(entities.len());
+        self.entity_map.reserve(entities.len());
+
         for entity in entities {
             self.add_entity(entity)?;
         }
@@ -160,6 +184,17 @@ impl EntityGraph {
                     );
                 }
             }
+            // References inside records come from the nested field, like "address.owner_ref"
+            FieldValue::Record(fields) => {
+                for (nested_name, nested_value) in fields {
+                    self.collect_relationships_from_field(
+                        from_node,
+                        &FieldId::from(format!("{}.{}", field_name, nested_name)),
+                        nested_value,
+                        edges_to_add,
+                    );
+                }
+            }
             _ => {}
         }
     }
@@ -340,6 +375,34 @@ mod tests {
         assert_eq!(graph.graph.node_count(), 3);
     }
 
+    #[test]
+    fn test_record_field_references() {
+        let mut graph = EntityGraph::new();
+
+        let organization = create_organization("acme", "Acme").with_field(
+            FieldId::new("billing"),
+            FieldValue::Record(vec![
+                (FieldId::new("street"), FieldValue::from("Main Street 1")),
+                (
+                    FieldId::new("contact_ref"),
+                    FieldValue::Reference(ReferenceValue::Entity(EntityId::new("john"))),
+                ),
+            ]),
+        );
+
+        graph
+            .add_entities(vec![create_person("john", "John Doe"), organization])
+            .unwrap();
+        graph.build();
+
+        let edges: Vec<&Relationship> = graph.graph.edge_weights().collect();
+        assert_eq!(edges.len(), 1);
+        assert!(matches!(
+            edges[0],
+            Relationship::EntityReference { from_field } if from_field.as_str() == "billing.contact_ref"
+        ));
+    }
+
     fn test_serialization_roundtrip(graph: &EntityGraph) -> EntityGraph {
         let serialized = serde_json::to_string(graph).unwrap();
         serde_json::from_str(&serialized).unwrap()
@@ -2,7 +2,11 @@ use log::debug;
 use petgraph::{Direction, visit::EdgeRef};
 
 use super::{EntityGraph, GraphError, Relationship};
+use crate::field::Currency;
+use crate::money::{EXCHANGE_RATE_TYPE, ExchangeRates, Money, MoneyError};
+use crate::suggest::ranked_matches;
 use crate::{Entity, EntityId, EntityType, FieldId, FieldValue, ReferenceValue};
+use chrono::NaiveDate;
 
 use std::collections::HashSet;
 
@@ -46,11 +50,42 @@ impl EntityGraph {
         }
     }
 
+    /// Gets all entities in the graph, in the order they were added.
+    pub fn entities(&self) -> Vec<&Entity> {
+        self.graph.node_weights().collect()
+    }
+
     /// Gets a collection of all entity types present.
     pub fn get_all_entity_types(&self) -> Vec<EntityType> {
         self.entity_type_map.keys().cloned().collect()
     }
 
+    /// Suggests IDs of entities in the graph which are close to an ID that couldn't be found, closest first.
+    pub fn suggest_entity_ids(&self, id: &EntityId, limit: usize) -> Vec<EntityId> {
+        let mut candidates: Vec<&str> = self.entity_map.keys().map(|id| id.as_str()).collect();
+        candidates.sort();
+
+        ranked_matches(id.as_str(), candidates, limit)
+            .into_iter()
+            .map(EntityId::from)
+            .collect()
+    }
+
+    /// Suggests entity types present in the graph which are close to a type that couldn't be found, closest first.
+    pub fn suggest_entity_types(&self, entity_type: &EntityType, limit: usize) -> Vec<EntityType> {
+        let mut candidates: Vec<&str> = self
+            .entity_type_map
+            .keys()
+            .map(|entity_type| entity_type.as_str())
+            .collect();
+        candidates.sort();
+
+        ranked_matches(entity_type.as_str(), candidates, limit)
+            .into_iter()
+            .map(EntityType::from)
+            .collect()
+    }
+
     /// Gets all entities of a specific type.
     pub fn list_by_type(&self, entity_type: &EntityType) -> Vec<&Entity> {
         match self.entity_type_map.get(entity_type) {
@@ -62,6 +97,31 @@ impl EntityGraph {
         }
     }
 
+    /// Gets the exchange rates defined by `exchange_rate` entities in the graph.
+    pub fn exchange_rates(&self) -> ExchangeRates {
+        ExchangeRates::from_entities(self.list_by_type(&EntityType::new(EXCHANGE_RATE_TYPE)))
+    }
+
+    /// Totals a currency field across entities in one currency.
+    ///
+    /// Amounts are converted with the exchange rates in the graph which are valid on the date.
+    /// Entities without the field are skipped.
+    pub fn total_currency_field(
+        &self,
+        entities: &[&Entity],
+        field_id: &FieldId,
+        currency: Currency,
+        date: NaiveDate,
+    ) -> Result<Money, MoneyError> {
+        let amounts = entities
+            .iter()
+            .filter_map(|entity| entity.get_field(field_id))
+            .map(Money::try_from)
+            .collect::<Result<Vec<Money>, MoneyError>>()?;
+
+        Money::total(&amounts, currency, &self.exchange_rates(), date)
+    }
+
     /// Gets all entities that references an entity ID.
     ///
     /// Edges in the graph are directed, and here we can choose if we want only
@@ -663,4 +723,111 @@ mod tests {
         let non_existing = graph.get_related(&EntityId::new("non_existing"), None);
         assert!(non_existing.is_none());
     }
+
+    #[test]
+    fn test_total_currency_field() {
+        use crate::field::Decimal;
+
+        let mut graph = EntityGraph::new();
+
+        let exchange_rate = Entity::new(
+            EntityId::new("eur_dkk"),
+            EntityType::new(EXCHANGE_RATE_TYPE),
+        )
+        .with_field(FieldId::new("currency"), "EUR")
+        .with_field(
+            FieldId::new("rate"),
+            FieldValue::Currency {
+                amount: Decimal::new(745, 2),
+                currency: Currency::DKK,
+            },
+        )
+        .with_field(
+            FieldId::new("valid_from"),
+            FieldValue::Date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+        );
+
+        let deal1 = Entity::new(EntityId::new("deal1"), EntityType::new("opportunity")).with_field(
+            FieldId::new("value"),
+            FieldValue::Currency {
+                amount: Decimal::new(1000, 0),
+                currency: Currency::EUR,
+            },
+        );
+
+        let deal2 = Entity::new(EntityId::new("deal2"), EntityType::new("opportunity")).with_field(
+            FieldId::new("value"),
+            FieldValue::Currency {
+                amount: Decimal::new(500, 0),
+                currency: Currency::DKK,
+            },
+        );
+
+        let deal3 = Entity::new(EntityId::new("deal3"), EntityType::new("opportunity"));
+
+        graph
+            .add_entities(vec![exchange_rate, deal1, deal2, deal3])
+            .unwrap();
+
+        assert!(!graph.exchange_rates().is_empty());
+
+        let deals = graph.list_by_type(&EntityType::new("opportunity"));
+        let total = graph
+            .total_currency_field(
+                &deals,
+                &FieldId::new("value"),
+                Currency::DKK,
+                NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(total.amount, Decimal::new(7950, 0));
+        assert_eq!(total.currency, Currency::DKK);
+
+        // No rate is valid before it was set
+        let result = graph.total_currency_field(
+            &deals,
+            &FieldId::new("value"),
+            Currency::DKK,
+            NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        );
+        assert!(matches!(
+            result,
+            Err(MoneyError::MissingExchangeRate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_suggestions_for_unknown_ids_and_types() {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entities(vec![
+                Entity::new(EntityId::from("person.john_doe"), EntityType::new("person")),
+                Entity::new(EntityId::from("person.jane_doe"), EntityType::new("person")),
+                Entity::new(EntityId::from("account.acme"), EntityType::new("account")),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            graph.suggest_entity_ids(&EntityId::from("person.jon_doe"), 5),
+            vec![
+                EntityId::from("person.john_doe"),
+                EntityId::from("person.jane_doe")
+            ]
+        );
+        assert!(
+            graph
+                .suggest_entity_ids(&EntityId::from("project.website"), 5)
+                .is_empty()
+        );
+
+        assert_eq!(
+            graph.suggest_entity_types(&EntityType::new("acount"), 5),
+            vec![EntityType::new("account")]
+        );
+        assert!(
+            graph
+                .suggest_entity_types(&EntityType::new("invoice"), 5)
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,654 @@
+use chrono::{NaiveDate, TimeDelta};
+use petgraph::Direction;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use super::{EntityGraph, GraphError};
+use crate::temporal::Duration;
+use crate::{Entity, EntityId, EntityType, FieldId, FieldValue};
+
+/// The task field listing the tasks which must be completed first.
+pub const DEPENDS_ON_FIELD: &str = "depends_on_refs";
+
+/// The task field with the expected length of the work, as a duration or a time quantity.
+pub const ESTIMATE_FIELD: &str = "estimate";
+
+/// The number of days a task without an estimate is planned to take.
+const DEFAULT_TASK_DAYS: i64 = 1;
+
+/// The errors that can occur when scheduling tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleError {
+    /// Tasks depend on each other in a cycle, so none of them can start.
+    DependencyCycle(Vec<EntityId>),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::DependencyCycle(cycle) => {
+                let ids: Vec<&str> = cycle.iter().map(|id| id.as_str()).collect();
+                write!(
+                    f,
+                    "Tasks depend on each other in a cycle: {}",
+                    ids.join(" -> ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// A task with the dates it can start and must finish by.
+///
+/// Dates are inclusive, so a one-day task starts and finishes on the same day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledTask {
+    pub task_id: EntityId,
+    pub name: String,
+    pub days: i64,
+    /// The incomplete tasks this task depends on.
+    pub blocked_by: Vec<EntityId>,
+    pub earliest_start: NaiveDate,
+    pub earliest_finish: NaiveDate,
+    pub latest_start: NaiveDate,
+    pub latest_finish: NaiveDate,
+    /// The number of days the task can slip without delaying the plan, negative if it's late.
+    pub slack: i64,
+    /// Whether the task is on the critical path, the longest chain of dependencies which sets
+    /// the earliest finish of the plan.
+    pub critical: bool,
+}
+
+impl ScheduledTask {
+    /// Checks if all the tasks this task depends on are completed.
+    pub fn is_ready(&self) -> bool {
+        self.blocked_by.is_empty()
+    }
+}
+
+/// A plan for a set of tasks, in an order which respects their dependencies.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Schedule {
+    pub start: NaiveDate,
+    pub finish: NaiveDate,
+    pub deadline: Option<NaiveDate>,
+    pub tasks: Vec<ScheduledTask>,
+}
+
+impl Schedule {
+    /// Gets the tasks which can be started now.
+    pub fn ready(&self) -> Vec<&ScheduledTask> {
+        self.tasks.iter().filter(|task| task.is_ready()).collect()
+    }
+
+    /// Gets the tasks on the critical path, in order.
+    pub fn critical_path(&self) -> Vec<&ScheduledTask> {
+        self.tasks.iter().filter(|task| task.critical).collect()
+    }
+
+    /// Checks if the plan finishes after its deadline.
+    pub fn is_late(&self) -> bool {
+        self.deadline.is_some_and(|deadline| self.finish > deadline)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Start: {}", self.start)?;
+        writeln!(f, "Finish: {}", self.finish)?;
+        if let Some(deadline) = self.deadline {
+            let late = (self.finish - deadline).num_days();
+            if late > 0 {
+                writeln!(f, "Deadline: {} ({} days late)", deadline, late)?;
+            } else {
+                writeln!(f, "Deadline: {}", deadline)?;
+            }
+        }
+
+        writeln!(f, "\nReady to start")?;
+        for task in self.ready() {
+            writeln!(f, "- {}: {} ({} days)", task.task_id, task.name, task.days)?;
+        }
+
+        writeln!(f, "\nCritical path")?;
+        for task in self.critical_path() {
+            writeln!(
+                f,
+                "- {}: {} ({} to {}, {} days slack)",
+                task.task_id, task.name, task.earliest_start, task.earliest_finish, task.slack
+            )?;
+        }
+
+        writeln!(f, "\nAll tasks")?;
+        for task in &self.tasks {
+            writeln!(
+                f,
+                "- {}: {} to {} (latest {} to {})",
+                task.task_id,
+                task.earliest_start,
+                task.earliest_finish,
+                task.latest_start,
+                task.latest_finish
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EntityGraph {
+    /// Gets the tasks which reference a project through their `source_ref` field.
+    pub fn project_tasks(&self, project_id: &EntityId) -> Result<Vec<&Entity>, GraphError> {
+        let &project = self
+            .entity_map
+            .get(project_id)
+            .ok_or_else(|| GraphError::EntityNotFound(project_id.clone()))?;
+
+        let task_type = EntityType::new("task");
+        let source_field = FieldId::new("source_ref");
+        let mut tasks: Vec<NodeIndex> = self
+            .graph
+            .edges_directed(project, Direction::Incoming)
+            .filter(|edge| edge.weight().from_field() == &source_field)
+            .map(|edge| edge.source())
+            .filter(|&index| self.graph[index].entity_type == task_type)
+            .collect();
+        tasks.sort();
+        tasks.dedup();
+
+        Ok(tasks.into_iter().map(|index| &self.graph[index]).collect())
+    }
+
+    /// Orders tasks so that every task comes after the tasks it depends on.
+    ///
+    /// Returns an error with the tasks in a cycle if the dependencies can't be ordered.
+    pub fn dependency_order<'a>(
+        &'a self,
+        tasks: &[&Entity],
+    ) -> Result<Vec<&'a Entity>, ScheduleError> {
+        let nodes = self.task_nodes(tasks, false);
+        let order = self.topological_order(&nodes)?;
+        Ok(order.into_iter().map(|index| &self.graph[index]).collect())
+    }
+
+    /// Plans incomplete tasks and the incomplete tasks they depend on, with the critical path method.
+    ///
+    /// Tasks take the whole days of their `estimate`, rounded up, or a day without one. Each
+    /// task starts once its dependencies finish, from the start date on. Latest dates count
+    /// back from the tasks' due dates and the deadline, or from the plan's earliest finish.
+    pub fn schedule(
+        &self,
+        tasks: &[&Entity],
+        start: NaiveDate,
+        deadline: Option<NaiveDate>,
+    ) -> Result<Schedule, ScheduleError> {
+        let nodes = self.task_nodes(tasks, true);
+        let order = self.topological_order(&nodes)?;
+
+        // Days are offsets from the start, and finishes are exclusive while they're calculated
+        let days: HashMap<NodeIndex, i64> = order
+            .iter()
+            .map(|&index| (index, task_days(&self.graph[index])))
+            .collect();
+
+        let mut earliest_start: HashMap<NodeIndex, i64> = HashMap::new();
+        let mut earliest_finish: HashMap<NodeIndex, i64> = HashMap::new();
+        for &index in &order {
+            let start_offset = self
+                .task_dependencies(index, &nodes)
+                .iter()
+                .map(|dependency| earliest_finish[dependency])
+                .max()
+                .unwrap_or(0);
+            earliest_start.insert(index, start_offset);
+            earliest_finish.insert(index, start_offset + days[&index]);
+        }
+
+        let plan_finish = earliest_finish.values().copied().max().unwrap_or(0);
+        let offset = |date: NaiveDate| (date - start).num_days() + 1;
+        let bound = deadline.map(offset).unwrap_or(plan_finish);
+
+        let dependents = self.task_dependents(&order, &nodes);
+        let mut latest_start: HashMap<NodeIndex, i64> = HashMap::new();
+        let mut latest_finish: HashMap<NodeIndex, i64> = HashMap::new();
+        for &index in order.iter().rev() {
+            let due = match self.graph[index].get_field(&FieldId::new("due_date")) {
+                Some(FieldValue::Date(due_date)) => Some(offset(*due_date)),
+                _ => None,
+            };
+            let finish_offset = dependents[&index]
+                .iter()
+                .map(|dependent| latest_start[dependent])
+                .chain(due)
+                .fold(bound, i64::min);
+            latest_finish.insert(index, finish_offset);
+            latest_start.insert(index, finish_offset - days[&index]);
+        }
+
+        // The critical path runs back from the tasks finishing last through the dependencies
+        // which finish right as their dependent can start, so none of its tasks can slip
+        let mut critical: HashSet<NodeIndex> = HashSet::new();
+        let mut queue: Vec<NodeIndex> = order
+            .iter()
+            .copied()
+            .filter(|index| earliest_finish[index] == plan_finish)
+            .collect();
+        while let Some(index) = queue.pop() {
+            if critical.insert(index) {
+                queue.extend(
+                    self.task_dependencies(index, &nodes)
+                        .into_iter()
+                        .filter(|dependency| earliest_finish[dependency] == earliest_start[&index]),
+                );
+            }
+        }
+
+        let slack = |index: &NodeIndex| latest_start[index] - earliest_start[index];
+        let date = |offset: i64| start + TimeDelta::days(offset);
+
+        let scheduled = order
+            .iter()
+            .map(|index| {
+                let entity = &self.graph[*index];
+                let name = match entity.get_field(&FieldId::new("name")) {
+                    Some(FieldValue::String(name)) => name.clone(),
+                    _ => entity.id.to_string(),
+                };
+
+                ScheduledTask {
+                    task_id: entity.id.clone(),
+                    name,
+                    days: days[index],
+                    blocked_by: self
+                        .task_dependencies(*index, &nodes)
+                        .iter()
+                        .map(|&dependency| self.graph[dependency].id.clone())
+                        .collect(),
+                    earliest_start: date(earliest_start[index]),
+                    earliest_finish: date(earliest_finish[index] - 1),
+                    latest_start: date(latest_start[index]),
+                    latest_finish: date(latest_finish[index] - 1),
+                    slack: slack(index),
+                    critical: critical.contains(index),
+                }
+            })
+            .collect();
+
+        Ok(Schedule {
+            start,
+            finish: date(plan_finish.max(1) - 1),
+            deadline,
+            tasks: scheduled,
+        })
+    }
+
+    /// Finds the nodes for tasks and the tasks they depend on, optionally leaving out completed tasks.
+    ///
+    /// Dependencies on entities other than tasks are skipped, as the build already warns about them.
+    fn task_nodes(&self, tasks: &[&Entity], incomplete_only: bool) -> BTreeSet<NodeIndex> {
+        let task_type = EntityType::new("task");
+        let included = |index: NodeIndex| {
+            let entity = &self.graph[index];
+            entity.entity_type == task_type && (!incomplete_only || !is_completed(entity))
+        };
+
+        let mut nodes = BTreeSet::new();
+        let mut queue: VecDeque<NodeIndex> = tasks
+            .iter()
+            .filter_map(|task| self.entity_map.get(&task.id).copied())
+            .filter(|&index| included(index))
+            .collect();
+
+        while let Some(index) = queue.pop_front() {
+            if !nodes.insert(index) {
+                continue;
+            }
+
+            for edge in self.graph.edges_directed(index, Direction::Outgoing) {
+                if edge.weight().from_field().as_str() == DEPENDS_ON_FIELD
+                    && included(edge.target())
+                {
+                    queue.push_back(edge.target());
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// Gets the tasks in a set of nodes which a task depends on, in the order of the graph.
+    fn task_dependencies(&self, index: NodeIndex, nodes: &BTreeSet<NodeIndex>) -> Vec<NodeIndex> {
+        let mut dependencies: Vec<NodeIndex> = self
+            .graph
+            .edges_directed(index, Direction::Outgoing)
+            .filter(|edge| edge.weight().from_field().as_str() == DEPENDS_ON_FIELD)
+            .map(|edge| edge.target())
+            .filter(|target| nodes.contains(target))
+            .collect();
+        dependencies.sort();
+        dependencies.dedup();
+        dependencies
+    }
+
+    /// Maps each task to the tasks in the set which depend on it.
+    fn task_dependents(
+        &self,
+        order: &[NodeIndex],
+        nodes: &BTreeSet<NodeIndex>,
+    ) -> HashMap<NodeIndex, Vec<NodeIndex>> {
+        let mut dependents: HashMap<NodeIndex, Vec<NodeIndex>> =
+            order.iter().map(|&index| (index, Vec::new())).collect();
+        for &index in order {
+            for dependency in self.task_dependencies(index, nodes) {
+                dependents.entry(dependency).or_default().push(index);
+            }
+        }
+        dependents
+    }
+
+    /// Orders task nodes after their dependencies, keeping the order of the graph where it can.
+    fn topological_order(
+        &self,
+        nodes: &BTreeSet<NodeIndex>,
+    ) -> Result<Vec<NodeIndex>, ScheduleError> {
+        let dependencies: HashMap<NodeIndex, Vec<NodeIndex>> = nodes
+            .iter()
+            .map(|&index| (index, self.task_dependencies(index, nodes)))
+            .collect();
+
+        let mut remaining: HashMap<NodeIndex, usize> = dependencies
+            .iter()
+            .map(|(&index, dependencies)| (index, dependencies.len()))
+            .collect();
+        let mut ready: BTreeSet<NodeIndex> = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(&index, _)| index)
+            .collect();
+        let dependents = self.task_dependents(&nodes.iter().copied().collect::<Vec<_>>(), nodes);
+
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(index) = ready.pop_first() {
+            order.push(index);
+            remaining.remove(&index);
+
+            for dependent in &dependents[&index] {
+                if let Some(count) = remaining.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.insert(*dependent);
+                    }
+                }
+            }
+        }
+
+        let Some(&first) = remaining.keys().min() else {
+            return Ok(order);
+        };
+
+        // Every remaining task waits on another remaining task, so following them must loop
+        let mut path = Vec::new();
+        let mut seen = HashSet::new();
+        let mut index = first;
+        while seen.insert(index) {
+            path.push(index);
+            index = dependencies[&index]
+                .iter()
+                .copied()
+                .find(|dependency| remaining.contains_key(dependency))
+                .unwrap_or(index);
+        }
+
+        let loop_start = path.iter().position(|&i| i == index).unwrap_or_default();
+        let mut cycle: Vec<EntityId> = path[loop_start..]
+            .iter()
+            .map(|&i| self.graph[i].id.clone())
+            .collect();
+        cycle.push(self.graph[index].id.clone());
+
+        Err(ScheduleError::DependencyCycle(cycle))
+    }
+}
+
+/// Checks if a task is marked as completed.
+fn is_completed(task: &Entity) -> bool {
+    matches!(
+        task.get_field(&FieldId::new("is_completed")),
+        Some(FieldValue::Boolean(true))
+    )
+}
+
+/// Gets the whole days a task is estimated to take, rounded up.
+fn task_days(task: &Entity) -> i64 {
+    let day_minutes = Duration::from_days(1).minutes();
+    let days = match task.get_field(&FieldId::new(ESTIMATE_FIELD)) {
+        Some(FieldValue::Duration(duration)) => {
+            (duration.minutes() + day_minutes - 1) / day_minutes
+        }
+        Some(FieldValue::Quantity(quantity)) => quantity
+            .convert_to("d")
+            .map(|days| days.value.ceil() as i64)
+            .unwrap_or(DEFAULT_TASK_DAYS),
+        _ => DEFAULT_TASK_DAYS,
+    };
+
+    days.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReferenceValue;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn task(id: &str, days: i64, depends_on: &[&str]) -> Entity {
+        let dependencies = depends_on
+            .iter()
+            .map(|&id| FieldValue::Reference(ReferenceValue::Entity(EntityId::new(id))))
+            .collect();
+
+        Entity::new(EntityId::new(id), EntityType::new("task"))
+            .with_field(FieldId::new("name"), id.to_string())
+            .with_field(
+                FieldId::new("source_ref"),
+                FieldValue::Reference(ReferenceValue::Entity(EntityId::new("project.launch"))),
+            )
+            .with_field(
+                FieldId::new(ESTIMATE_FIELD),
+                FieldValue::Duration(Duration::from_days(days)),
+            )
+            .with_field(
+                FieldId::new(DEPENDS_ON_FIELD),
+                FieldValue::List(dependencies),
+            )
+    }
+
+    fn graph(tasks: Vec<Entity>) -> EntityGraph {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entity(Entity::new(
+                EntityId::new("project.launch"),
+                EntityType::new("project"),
+            ))
+            .unwrap();
+        graph.add_entities(tasks).unwrap();
+        graph.build();
+        graph
+    }
+
+    /// Design comes first, then build and docs in parallel, then release.
+    fn launch_graph() -> EntityGraph {
+        graph(vec![
+            task("task.release", 1, &["task.build", "task.docs"]),
+            task("task.docs", 2, &["task.design"]),
+            task("task.build", 5, &["task.design"]),
+            task("task.design", 3, &[]),
+        ])
+    }
+
+    fn ids(tasks: &[&ScheduledTask]) -> Vec<String> {
+        tasks.iter().map(|task| task.task_id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_project_tasks() {
+        let graph = launch_graph();
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        assert_eq!(tasks.len(), 4);
+
+        assert_eq!(
+            graph.project_tasks(&EntityId::new("project.missing")),
+            Err(GraphError::EntityNotFound(EntityId::new("project.missing")))
+        );
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let graph = launch_graph();
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let order: Vec<&str> = graph
+            .dependency_order(&tasks)
+            .unwrap()
+            .iter()
+            .map(|task| task.id.as_str())
+            .collect();
+
+        assert_eq!(
+            order,
+            vec!["task.design", "task.docs", "task.build", "task.release"]
+        );
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let graph = graph(vec![
+            task("task.a", 1, &["task.c"]),
+            task("task.b", 1, &["task.a"]),
+            task("task.c", 1, &["task.b"]),
+            task("task.d", 1, &[]),
+        ]);
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+
+        assert_eq!(
+            graph.dependency_order(&tasks),
+            Err(ScheduleError::DependencyCycle(vec![
+                EntityId::new("task.a"),
+                EntityId::new("task.c"),
+                EntityId::new("task.b"),
+                EntityId::new("task.a"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_schedule_critical_path() {
+        let graph = launch_graph();
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let schedule = graph.schedule(&tasks, date("2025-06-02"), None).unwrap();
+
+        assert_eq!(schedule.finish, date("2025-06-10"));
+        assert_eq!(ids(&schedule.ready()), vec!["task.design"]);
+        assert_eq!(
+            ids(&schedule.critical_path()),
+            vec!["task.design", "task.build", "task.release"]
+        );
+
+        let docs = &schedule.tasks[1];
+        assert_eq!(docs.task_id, EntityId::new("task.docs"));
+        assert_eq!(docs.earliest_start, date("2025-06-05"));
+        assert_eq!(docs.earliest_finish, date("2025-06-06"));
+        assert_eq!(docs.latest_finish, date("2025-06-09"));
+        assert_eq!(docs.slack, 3);
+    }
+
+    #[test]
+    fn test_schedule_with_deadline() {
+        let graph = launch_graph();
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let schedule = graph
+            .schedule(&tasks, date("2025-06-02"), Some(date("2025-06-08")))
+            .unwrap();
+
+        assert!(schedule.is_late());
+        let release = schedule.tasks.last().unwrap();
+        assert_eq!(release.latest_finish, date("2025-06-08"));
+        assert_eq!(release.slack, -2);
+        assert!(release.critical);
+    }
+
+    #[test]
+    fn test_schedule_skips_completed_tasks() {
+        let graph = graph(vec![
+            task("task.design", 3, &[]).with_field(FieldId::new("is_completed"), true),
+            task("task.build", 5, &["task.design"]),
+        ]);
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let schedule = graph.schedule(&tasks, date("2025-06-02"), None).unwrap();
+
+        assert_eq!(schedule.tasks.len(), 1);
+        assert_eq!(ids(&schedule.ready()), vec!["task.build"]);
+        assert_eq!(schedule.finish, date("2025-06-06"));
+    }
+
+    #[test]
+    fn test_schedule_critical_path_ignores_due_dates_off_the_chain() {
+        let graph = graph(vec![
+            task("task.design", 3, &[]),
+            task("task.build", 5, &["task.design"]),
+            task("task.invoice", 1, &[]).with_field(
+                FieldId::new("due_date"),
+                FieldValue::Date(date("2025-06-02")),
+            ),
+            task("task.call", 1, &[]),
+        ]);
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let schedule = graph.schedule(&tasks, date("2025-06-02"), None).unwrap();
+
+        assert_eq!(
+            ids(&schedule.critical_path()),
+            vec!["task.design", "task.build"]
+        );
+        let invoice = schedule
+            .tasks
+            .iter()
+            .find(|task| task.task_id.as_str() == "task.invoice")
+            .unwrap();
+        assert_eq!(invoice.slack, 0);
+        assert!(!invoice.critical);
+    }
+
+    #[test]
+    fn test_schedule_skips_dependencies_on_other_entities() {
+        let graph = graph(vec![task("task.build", 5, &["project.launch"])]);
+        let tasks = graph
+            .project_tasks(&EntityId::new("project.launch"))
+            .unwrap();
+        let schedule = graph.schedule(&tasks, date("2025-06-02"), None).unwrap();
+
+        assert_eq!(ids(&schedule.ready()), vec!["task.build"]);
+        assert_eq!(schedule.tasks.len(), 1);
+        assert!(schedule.tasks[0].blocked_by.is_empty());
+    }
+}
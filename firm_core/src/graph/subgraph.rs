@@ -0,0 +1,342 @@
+use petgraph::Direction;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::collections::{HashSet, VecDeque};
+
+use super::{EntityGraph, GraphError};
+use crate::{EntityId, EntityType, FieldId, FieldValue, ReferenceValue};
+
+/// A reference from an entity field to an entity which isn't in the graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingReference {
+    pub entity_id: EntityId,
+    pub field_id: FieldId,
+    pub target_id: EntityId,
+}
+
+/// Options for extracting the neighbourhood of entities from an entity graph.
+#[derive(Debug, Clone)]
+pub struct SubgraphOptions {
+    /// The number of relationships to follow from the seed entities.
+    pub depth: usize,
+    /// The direction to follow relationships in, or both directions if not set.
+    pub direction: Option<Direction>,
+    /// The reference fields to follow relationships from, or all fields if empty.
+    pub fields: Vec<FieldId>,
+    /// The entity types to include besides the seeds, or all types if empty.
+    pub entity_types: Vec<EntityType>,
+}
+
+impl Default for SubgraphOptions {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            direction: None,
+            fields: Vec::new(),
+            entity_types: Vec::new(),
+        }
+    }
+}
+
+impl SubgraphOptions {
+    /// Builder method to follow a number of relationships from the seeds.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Builder method to only follow relationships in one direction.
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Builder method to follow relationships from a field. Without any, all fields are followed.
+    pub fn with_field(mut self, field_id: FieldId) -> Self {
+        self.fields.push(field_id);
+        self
+    }
+
+    /// Builder method to include an entity type. Without any, all types are included.
+    pub fn with_entity_type(mut self, entity_type: EntityType) -> Self {
+        self.entity_types.push(entity_type);
+        self
+    }
+}
+
+impl EntityGraph {
+    /// Extracts the entities around one or more seed entities as a new, built entity graph.
+    ///
+    /// Relationships are followed from the seeds as the options allow, only passing through
+    /// entities of the included types. The new graph has every relationship between the
+    /// extracted entities, even those from fields which weren't followed.
+    ///
+    /// The extracted entities keep their references to entities outside the subgraph, so they
+    /// still match their schemas. Use [`EntityGraph::dangling_references`] to find them.
+    pub fn subgraph(
+        &self,
+        seeds: &[EntityId],
+        options: &SubgraphOptions,
+    ) -> Result<EntityGraph, GraphError> {
+        let seeds = seeds
+            .iter()
+            .map(|entity_id| {
+                self.entity_map
+                    .get(entity_id)
+                    .copied()
+                    .ok_or_else(|| GraphError::EntityNotFound(entity_id.clone()))
+            })
+            .collect::<Result<Vec<NodeIndex>, GraphError>>()?;
+
+        // Keep the order of the original graph so the subgraph is stable
+        let mut nearby: Vec<NodeIndex> = self.neighbourhood(&seeds, options).into_iter().collect();
+        nearby.sort();
+
+        let mut subgraph = EntityGraph::new();
+        subgraph.add_entities(
+            nearby
+                .into_iter()
+                .map(|index| self.graph[index].clone())
+                .collect(),
+        )?;
+        subgraph.build();
+
+        Ok(subgraph)
+    }
+
+    /// Finds the references to entities which aren't in the graph, like those leaving a subgraph.
+    pub fn dangling_references(&self) -> Vec<DanglingReference> {
+        let mut dangling = Vec::new();
+
+        for entity in self.graph.node_weights() {
+            for (field_id, value) in &entity.fields {
+                for target_id in referenced_entities(value) {
+                    if !self.entity_map.contains_key(target_id) {
+                        dangling.push(DanglingReference {
+                            entity_id: entity.id.clone(),
+                            field_id: field_id.clone(),
+                            target_id: target_id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        dangling
+    }
+
+    /// Finds the seeds and the entities reachable from them within the bounds of the options.
+    pub(super) fn neighbourhood(
+        &self,
+        seeds: &[NodeIndex],
+        options: &SubgraphOptions,
+    ) -> HashSet<NodeIndex> {
+        let mut visited: HashSet<NodeIndex> = seeds.iter().copied().collect();
+        let mut queue: VecDeque<(NodeIndex, usize)> = seeds.iter().map(|&seed| (seed, 0)).collect();
+
+        let directions = match options.direction {
+            Some(direction) => vec![direction],
+            None => vec![Direction::Outgoing, Direction::Incoming],
+        };
+
+        while let Some((index, distance)) = queue.pop_front() {
+            if distance == options.depth {
+                continue;
+            }
+
+            for &direction in &directions {
+                for edge in self.graph.edges_directed(index, direction) {
+                    if !options.fields.is_empty()
+                        && !options.fields.contains(edge.weight().from_field())
+                    {
+                        continue;
+                    }
+
+                    let neighbour = match direction {
+                        Direction::Outgoing => edge.target(),
+                        Direction::Incoming => edge.source(),
+                    };
+                    let included = options.entity_types.is_empty()
+                        || options
+                            .entity_types
+                            .contains(&self.graph[neighbour].entity_type);
+
+                    if included && visited.insert(neighbour) {
+                        queue.push_back((neighbour, distance + 1));
+                    }
+                }
+            }
+        }
+
+        visited
+    }
+}
+
+/// Gets the entities a field value refers to, including those in lists and records.
+fn referenced_entities(value: &FieldValue) -> Vec<&EntityId> {
+    match value {
+        FieldValue::Reference(ReferenceValue::Entity(target_id))
+        | FieldValue::Reference(ReferenceValue::Field(target_id, _)) => vec![target_id],
+        FieldValue::List(items) => items.iter().flat_map(referenced_entities).collect(),
+        FieldValue::Record(fields) => fields
+            .iter()
+            .flat_map(|(_, value)| referenced_entities(value))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Entity, FieldValue, ReferenceValue};
+
+    fn reference(id: &str) -> FieldValue {
+        FieldValue::Reference(ReferenceValue::Entity(EntityId::new(id)))
+    }
+
+    /// An account with a contact, a person behind the contact and a task assigned to the person.
+    fn sample_graph() -> EntityGraph {
+        let mut graph = EntityGraph::new();
+        graph
+            .add_entities(vec![
+                Entity::new(EntityId::new("account.acme"), EntityType::new("account"))
+                    .with_field(FieldId::new("name"), "Acme"),
+                Entity::new(EntityId::new("person.jane"), EntityType::new("person"))
+                    .with_field(FieldId::new("name"), "Jane"),
+                Entity::new(
+                    EntityId::new("contact.jane_at_acme"),
+                    EntityType::new("contact"),
+                )
+                .with_field(FieldId::new("person_ref"), reference("person.jane"))
+                .with_field(FieldId::new("account_ref"), reference("account.acme")),
+                Entity::new(EntityId::new("task.follow_up"), EntityType::new("task"))
+                    .with_field(FieldId::new("assignee_ref"), reference("person.jane"))
+                    .with_field(FieldId::new("account_ref"), reference("account.acme")),
+            ])
+            .unwrap();
+        graph.build();
+        graph
+    }
+
+    fn ids(graph: &EntityGraph) -> Vec<&str> {
+        graph
+            .entities()
+            .iter()
+            .map(|entity| entity.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_subgraph_by_depth() {
+        let graph = sample_graph();
+        let seeds = [EntityId::new("person.jane")];
+
+        let subgraph = graph.subgraph(&seeds, &SubgraphOptions::default()).unwrap();
+        assert_eq!(
+            ids(&subgraph),
+            vec!["person.jane", "contact.jane_at_acme", "task.follow_up"]
+        );
+
+        let subgraph = graph
+            .subgraph(&seeds, &SubgraphOptions::default().with_depth(2))
+            .unwrap();
+        assert_eq!(subgraph.entities().len(), 4);
+    }
+
+    #[test]
+    fn test_subgraph_rebuilds_relationships() {
+        let graph = sample_graph();
+        let subgraph = graph
+            .subgraph(
+                &[EntityId::new("contact.jane_at_acme")],
+                &SubgraphOptions::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            ids(&subgraph),
+            vec!["account.acme", "person.jane", "contact.jane_at_acme"]
+        );
+        let related = subgraph
+            .get_related(&EntityId::new("person.jane"), None)
+            .unwrap();
+        assert_eq!(related.len(), 1);
+    }
+
+    #[test]
+    fn test_subgraph_dangling_references() {
+        let graph = sample_graph();
+        assert!(graph.dangling_references().is_empty());
+
+        let subgraph = graph
+            .subgraph(
+                &[EntityId::new("task.follow_up")],
+                &SubgraphOptions::default().with_field(FieldId::new("assignee_ref")),
+            )
+            .unwrap();
+
+        assert_eq!(ids(&subgraph), vec!["person.jane", "task.follow_up"]);
+        assert_eq!(
+            subgraph.dangling_references(),
+            vec![DanglingReference {
+                entity_id: EntityId::new("task.follow_up"),
+                field_id: FieldId::new("account_ref"),
+                target_id: EntityId::new("account.acme"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_subgraph_by_direction_and_field() {
+        let graph = sample_graph();
+        let seeds = [EntityId::new("task.follow_up")];
+
+        let options = SubgraphOptions::default().with_direction(Direction::Incoming);
+        let subgraph = graph.subgraph(&seeds, &options).unwrap();
+        assert_eq!(ids(&subgraph), vec!["task.follow_up"]);
+
+        let options = SubgraphOptions::default()
+            .with_direction(Direction::Outgoing)
+            .with_field(FieldId::new("assignee_ref"));
+        let subgraph = graph.subgraph(&seeds, &options).unwrap();
+        assert_eq!(ids(&subgraph), vec!["person.jane", "task.follow_up"]);
+    }
+
+    #[test]
+    fn test_subgraph_by_entity_type() {
+        let graph = sample_graph();
+        let options = SubgraphOptions::default()
+            .with_depth(3)
+            .with_entity_type(EntityType::new("contact"))
+            .with_entity_type(EntityType::new("person"));
+        let subgraph = graph
+            .subgraph(&[EntityId::new("account.acme")], &options)
+            .unwrap();
+
+        assert_eq!(
+            ids(&subgraph),
+            vec!["account.acme", "person.jane", "contact.jane_at_acme"]
+        );
+    }
+
+    #[test]
+    fn test_subgraph_with_several_seeds() {
+        let graph = sample_graph();
+        let seeds = [EntityId::new("account.acme"), EntityId::new("person.jane")];
+        let subgraph = graph
+            .subgraph(&seeds, &SubgraphOptions::default().with_depth(0))
+            .unwrap();
+        assert_eq!(ids(&subgraph), vec!["account.acme", "person.jane"]);
+
+        assert_eq!(
+            graph
+                .subgraph(
+                    &[EntityId::new("account.missing")],
+                    &SubgraphOptions::default()
+                )
+                .err(),
+            Some(GraphError::EntityNotFound(EntityId::new("account.missing")))
+        );
+    }
+}
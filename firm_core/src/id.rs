@@ -100,4 +100,12 @@ mod tests {
         let camel_case_id = EntityId::new("person.johnDoe");
         assert_eq!(camel_case_id.to_string(), "person.john_doe");
     }
+
+    #[test]
+    fn test_serializes_as_string() {
+        let id = EntityId::new("person.john_doe");
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, "\"person.john_doe\"");
+        assert_eq!(serde_json::from_str::<EntityId>(&json).unwrap(), id);
+    }
 }
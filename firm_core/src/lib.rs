@@ -3,13 +3,23 @@
 //! This crate provides the fundamental building blocks for managing
 //! business entities, their associated data and their relationships.
 
+pub mod compare;
 pub mod entity;
 pub mod field;
 pub mod graph;
 pub mod id;
+pub mod migration;
+pub mod money;
+pub mod quantity;
 pub mod schema;
+pub mod search;
+pub mod serde_bridge;
+pub mod suggest;
+pub mod temporal;
+pub mod typed;
 
-pub use entity::Entity;
+pub use entity::{Entity, Fields};
 pub use field::{FieldType, FieldValue, ReferenceValue};
 pub use id::{EntityId, EntityType, FieldId, compose_entity_id, decompose_entity_id};
 pub use schema::EntitySchema;
+pub use serde_bridge::{SerdeError, from_entity, to_entity};
@@ -0,0 +1,465 @@
+//! Migrations which update entities when their schema changes.
+//!
+//! A migration is a list of steps for one entity type. Applying it to an entity gives the
+//! field changes to make, so they can be written back to wherever the entity came from.
+//! Steps are idempotent: applying a migration to an entity that was already migrated changes nothing.
+
+use chrono::{NaiveDate, NaiveTime};
+use rust_decimal::prelude::ToPrimitive;
+use std::fmt;
+
+use crate::{Entity, EntitySchema, EntityType, FieldId, FieldType, FieldValue};
+
+/// A change to one field of an entity.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    /// Sets the value of a field, adding it if the entity doesn't have it.
+    Set(FieldId, FieldValue),
+    /// Renames a field, keeping its value.
+    Rename(FieldId, FieldId),
+}
+
+/// A single step of a migration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationStep {
+    /// Renames a field.
+    Rename { from: FieldId, to: FieldId },
+    /// Converts the value of a field to another type, or the type declared by the schema.
+    Convert {
+        field: FieldId,
+        to: Option<FieldType>,
+    },
+    /// Sets a field on entities which don't have it, to a value or the default declared by the schema.
+    Fill {
+        field: FieldId,
+        value: Option<FieldValue>,
+    },
+}
+
+impl fmt::Display for MigrationStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationStep::Rename { from, to } => write!(f, "rename '{}' to '{}'", from, to),
+            MigrationStep::Convert {
+                field,
+                to: Some(to),
+            } => write!(f, "convert '{}' to {}", field, to),
+            MigrationStep::Convert { field, to: None } => {
+                write!(f, "convert '{}' to its schema type", field)
+            }
+            MigrationStep::Fill {
+                field,
+                value: Some(value),
+            } => write!(f, "fill '{}' with {}", field, value),
+            MigrationStep::Fill { field, value: None } => {
+                write!(f, "fill '{}' with its default", field)
+            }
+        }
+    }
+}
+
+/// Errors that can occur when applying a migration to an entity.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    FieldExists(FieldId),
+    UnknownTargetType(FieldId),
+    MissingDefault(FieldId),
+    ConversionFailed(FieldId, String),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::FieldExists(field_id) => {
+                write!(f, "Can't rename to '{}' because the field exists", field_id)
+            }
+            MigrationError::UnknownTargetType(field_id) => write!(
+                f,
+                "Can't convert '{}' because the schema doesn't declare its type",
+                field_id
+            ),
+            MigrationError::MissingDefault(field_id) => write!(
+                f,
+                "Can't fill '{}' because the schema doesn't declare a default",
+                field_id
+            ),
+            MigrationError::ConversionFailed(field_id, error) => {
+                write!(f, "Can't convert '{}': {}", field_id, error)
+            }
+        }
+    }
+}
+
+/// Migrates all entities of a type with a list of steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub entity_type: EntityType,
+    pub steps: Vec<MigrationStep>,
+}
+
+impl Migration {
+    /// Creates an empty migration for an entity type.
+    pub fn new(entity_type: EntityType) -> Self {
+        Self {
+            entity_type,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Builder method to rename a field.
+    pub fn with_rename(mut self, from: FieldId, to: FieldId) -> Self {
+        self.steps.push(MigrationStep::Rename { from, to });
+        self
+    }
+
+    /// Builder method to convert a field to another type, or the type declared by the schema.
+    pub fn with_conversion(mut self, field: FieldId, to: Option<FieldType>) -> Self {
+        self.steps.push(MigrationStep::Convert { field, to });
+        self
+    }
+
+    /// Builder method to fill a field on entities which don't have it.
+    pub fn with_fill(mut self, field: FieldId, value: Option<FieldValue>) -> Self {
+        self.steps.push(MigrationStep::Fill { field, value });
+        self
+    }
+
+    /// Checks whether the migration has any steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Gets the changes which migrate an entity, using the schema for target types and defaults.
+    /// Entities of other types are left unchanged.
+    pub fn apply(
+        &self,
+        entity: &Entity,
+        schema: &EntitySchema,
+    ) -> Result<Vec<FieldChange>, MigrationError> {
+        if entity.entity_type != self.entity_type {
+            return Ok(Vec::new());
+        }
+
+        // Work on a copy, so each step sees the result of the steps before it
+        let mut migrated = entity.clone();
+        let mut changes = Vec::new();
+
+        for step in &self.steps {
+            match step {
+                MigrationStep::Rename { from, to } => {
+                    if migrated.get_field(from).is_none() {
+                        continue;
+                    }
+                    if migrated.get_field(to).is_some() {
+                        return Err(MigrationError::FieldExists(to.clone()));
+                    }
+
+                    migrated.fields.rename(from, to);
+                    changes.push(FieldChange::Rename(from.clone(), to.clone()));
+                }
+                MigrationStep::Convert { field, to } => {
+                    let Some(value) = migrated.get_field(field) else {
+                        continue;
+                    };
+
+                    let target_type = match to {
+                        Some(to) => to.clone(),
+                        None => schema
+                            .fields
+                            .get(field)
+                            .map(|field_schema| field_schema.field_type.clone())
+                            .ok_or_else(|| MigrationError::UnknownTargetType(field.clone()))?,
+                    };
+
+                    if value.get_type() == target_type {
+                        continue;
+                    }
+
+                    let converted = convert_value(value, &target_type)
+                        .map_err(|err| MigrationError::ConversionFailed(field.clone(), err))?;
+                    set_field(&mut migrated, field, converted.clone());
+                    changes.push(FieldChange::Set(field.clone(), converted));
+                }
+                MigrationStep::Fill { field, value } => {
+                    if migrated.get_field(field).is_some() {
+                        continue;
+                    }
+
+                    let value = match value {
+                        Some(value) => value.clone(),
+                        None => schema
+                            .fields
+                            .get(field)
+                            .and_then(|field_schema| field_schema.default.as_ref())
+                            .map(|default| default.resolve())
+                            .ok_or_else(|| MigrationError::MissingDefault(field.clone()))?,
+                    };
+
+                    set_field(&mut migrated, field, value.clone());
+                    changes.push(FieldChange::Set(field.clone(), value));
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Converts a value to another field type.
+///
+/// Only conversions that keep the meaning of the value are allowed:
+/// - Integers become floats, and floats become integers if they are whole numbers.
+/// - Strings are parsed as booleans, integers or floats.
+/// - Booleans, numbers, currencies and dates become strings.
+/// - Any value becomes a list with the value as its only item.
+pub fn convert_value(value: &FieldValue, to: &FieldType) -> Result<FieldValue, String> {
+    let failed = || format!("can't convert {} '{}' to {}", value.get_type(), value, to);
+
+    match (value, to) {
+        (value, to) if &value.get_type() == to => Ok(value.clone()),
+        (FieldValue::Integer(i), FieldType::Float) => Ok(FieldValue::Float(*i as f64)),
+        (FieldValue::Float(f), FieldType::Integer) if f.fract() == 0.0 => {
+            Ok(FieldValue::Integer(*f as i64))
+        }
+        (FieldValue::Currency { amount, .. }, FieldType::Float) => {
+            amount.to_f64().map(FieldValue::Float).ok_or_else(failed)
+        }
+        (FieldValue::String(s), FieldType::Boolean) => s
+            .trim()
+            .parse()
+            .map(FieldValue::Boolean)
+            .map_err(|_| failed()),
+        (FieldValue::String(s), FieldType::Integer) => s
+            .trim()
+            .parse()
+            .map(FieldValue::Integer)
+            .map_err(|_| failed()),
+        (FieldValue::String(s), FieldType::Float) => s
+            .trim()
+            .parse()
+            .map(FieldValue::Float)
+            .map_err(|_| failed()),
+        (
+            FieldValue::Boolean(_)
+            | FieldValue::Integer(_)
+            | FieldValue::Float(_)
+            | FieldValue::Currency { .. }
+            | FieldValue::Duration(_)
+            | FieldValue::Recurrence(_)
+            | FieldValue::Quantity(_),
+            FieldType::String,
+        ) => Ok(FieldValue::String(value.to_string())),
+        (FieldValue::DateTime(date), FieldType::String) => {
+            Ok(FieldValue::String(date.to_rfc3339()))
+        }
+        (FieldValue::Date(date), FieldType::String) => Ok(FieldValue::String(date.to_string())),
+        (FieldValue::String(s), FieldType::Date) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map(FieldValue::Date)
+            .map_err(|_| failed()),
+        (FieldValue::String(s), FieldType::Duration) => {
+            s.parse().map(FieldValue::Duration).map_err(|_| failed())
+        }
+        (FieldValue::String(s), FieldType::Recurrence) => {
+            s.parse().map(FieldValue::Recurrence).map_err(|_| failed())
+        }
+        (FieldValue::String(s), FieldType::Quantity) => {
+            s.parse().map(FieldValue::Quantity).map_err(|_| failed())
+        }
+        (FieldValue::Quantity(quantity), FieldType::Float) => Ok(FieldValue::Float(quantity.value)),
+        (FieldValue::DateTime(date), FieldType::Date) => Ok(FieldValue::Date(date.date_naive())),
+        // Dates become datetimes at midnight UTC, so the result doesn't depend on the machine
+        (FieldValue::Date(date), FieldType::DateTime) => Ok(FieldValue::DateTime(
+            date.and_time(NaiveTime::MIN).and_utc().fixed_offset(),
+        )),
+        (value, FieldType::List) => Ok(FieldValue::List(vec![value.clone()])),
+        _ => Err(failed()),
+    }
+}
+
+/// Sets a field on an entity, replacing its value if it exists.
+fn set_field(entity: &mut Entity, field_id: &FieldId, value: FieldValue) {
+    entity.fields.insert(field_id.clone(), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityId;
+    use crate::schema::FieldDefault;
+    use crate::temporal::{Duration, Recurrence, RecurrenceUnit};
+    use assert_matches::assert_matches;
+
+    fn deal_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("deal"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("probability"), FieldType::Float)
+            .with_required_field(FieldId::new("region"), FieldType::String)
+            .with_default(
+                &FieldId::new("region"),
+                FieldDefault::Value(FieldValue::String(String::from("EU"))),
+            )
+    }
+
+    fn deal() -> Entity {
+        Entity::new(EntityId::new("deal.big"), EntityType::new("deal"))
+            .with_field(FieldId::new("title"), "Big deal")
+            .with_field(FieldId::new("probability"), 50i64)
+    }
+
+    fn migration() -> Migration {
+        Migration::new(EntityType::new("deal"))
+            .with_rename(FieldId::new("title"), FieldId::new("name"))
+            .with_conversion(FieldId::new("probability"), None)
+            .with_fill(FieldId::new("region"), None)
+    }
+
+    #[test]
+    fn test_apply_migration() {
+        let changes = migration().apply(&deal(), &deal_schema()).unwrap();
+
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange::Rename(FieldId::new("title"), FieldId::new("name")),
+                FieldChange::Set(FieldId::new("probability"), FieldValue::Float(50.0)),
+                FieldChange::Set(
+                    FieldId::new("region"),
+                    FieldValue::String(String::from("EU"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_migration_is_idempotent() {
+        let migrated = Entity::new(EntityId::new("deal.big"), EntityType::new("deal"))
+            .with_field(FieldId::new("name"), "Big deal")
+            .with_field(FieldId::new("probability"), 50.0)
+            .with_field(FieldId::new("region"), "US");
+
+        let changes = migration().apply(&migrated, &deal_schema()).unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_apply_migration_rename_to_existing_field() {
+        let entity = deal().with_field(FieldId::new("name"), "Also big deal");
+
+        let result = migration().apply(&entity, &deal_schema());
+        assert_eq!(
+            result,
+            Err(MigrationError::FieldExists(FieldId::new("name")))
+        );
+    }
+
+    #[test]
+    fn test_apply_migration_fill_without_default() {
+        let migration =
+            Migration::new(EntityType::new("deal")).with_fill(FieldId::new("name"), None);
+
+        let result = migration.apply(&deal(), &deal_schema());
+        assert_eq!(
+            result,
+            Err(MigrationError::MissingDefault(FieldId::new("name")))
+        );
+    }
+
+    #[test]
+    fn test_convert_value() {
+        assert_eq!(
+            convert_value(&FieldValue::Float(3.0), &FieldType::Integer),
+            Ok(FieldValue::Integer(3))
+        );
+        assert_eq!(
+            convert_value(
+                &FieldValue::String(String::from(" 42 ")),
+                &FieldType::Integer
+            ),
+            Ok(FieldValue::Integer(42))
+        );
+        assert_eq!(
+            convert_value(&FieldValue::Integer(7), &FieldType::List),
+            Ok(FieldValue::List(vec![FieldValue::Integer(7)]))
+        );
+        assert_matches!(
+            convert_value(&FieldValue::Float(3.5), &FieldType::Integer),
+            Err(_)
+        );
+        assert_matches!(
+            convert_value(&FieldValue::Boolean(true), &FieldType::DateTime),
+            Err(_)
+        );
+    }
+
+    #[test]
+    fn test_convert_between_dates_and_datetimes() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let datetime = chrono::DateTime::parse_from_rfc3339("2025-03-01T23:30:00+02:00").unwrap();
+
+        assert_eq!(
+            convert_value(&FieldValue::DateTime(datetime), &FieldType::Date),
+            Ok(FieldValue::Date(date))
+        );
+        assert_eq!(
+            convert_value(&FieldValue::Date(date), &FieldType::DateTime),
+            Ok(FieldValue::DateTime(
+                chrono::DateTime::parse_from_rfc3339("2025-03-01T00:00:00+00:00").unwrap()
+            ))
+        );
+        assert_eq!(
+            convert_value(
+                &FieldValue::String(String::from("2025-03-01")),
+                &FieldType::Date
+            ),
+            Ok(FieldValue::Date(date))
+        );
+    }
+
+    #[test]
+    fn test_convert_strings_to_durations_and_recurrences() {
+        assert_eq!(
+            convert_value(
+                &FieldValue::String(String::from("2h30m")),
+                &FieldType::Duration
+            ),
+            Ok(FieldValue::Duration(Duration::from_minutes(150)))
+        );
+        assert_eq!(
+            convert_value(
+                &FieldValue::String(String::from("every 2 weeks")),
+                &FieldType::Recurrence
+            ),
+            Ok(FieldValue::Recurrence(Recurrence::new(
+                2,
+                RecurrenceUnit::Week
+            )))
+        );
+        assert!(
+            convert_value(
+                &FieldValue::String(String::from("soon")),
+                &FieldType::Duration
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_convert_quantities() {
+        use crate::quantity::Quantity;
+
+        assert_eq!(
+            convert_value(
+                &FieldValue::String(String::from("120 h")),
+                &FieldType::Quantity
+            ),
+            Ok(FieldValue::Quantity(Quantity::new(120.0, "h")))
+        );
+        assert_eq!(
+            convert_value(
+                &FieldValue::Quantity(Quantity::new(5.0, "TB")),
+                &FieldType::Float
+            ),
+            Ok(FieldValue::Float(5.0))
+        );
+    }
+}
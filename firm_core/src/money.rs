@@ -0,0 +1,639 @@
+//! Arithmetic on currency amounts, with conversion through dated exchange rates.
+//!
+//! Amounts in different currencies are never added or compared directly. They are first converted with
+//! the exchange rates a workspace defines as `exchange_rate` entities, using the rate valid on a given date.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::field::{Currency, Decimal};
+use crate::schema::ValidationError;
+use crate::{Entity, EntityType, FieldId, FieldType, FieldValue};
+
+/// The entity type of exchange rates in a workspace.
+pub const EXCHANGE_RATE_TYPE: &str = "exchange_rate";
+
+/// The errors you can get when working with money.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoneyError {
+    /// Two amounts were in different currencies.
+    MixedCurrencies { left: Currency, right: Currency },
+    /// No exchange rate between two currencies was valid on the date.
+    MissingExchangeRate {
+        from: Currency,
+        to: Currency,
+        date: NaiveDate,
+    },
+    /// A field value was not a currency amount.
+    NotACurrency(FieldType),
+    /// The result of a calculation was too large to hold.
+    Overflow(Currency),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::MixedCurrencies { left, right } => write!(
+                f,
+                "Amounts in {} and {} can't be combined without converting them",
+                left.code(),
+                right.code()
+            ),
+            MoneyError::MissingExchangeRate { from, to, date } => write!(
+                f,
+                "No exchange rate from {} to {} is valid on {}",
+                from.code(),
+                to.code(),
+                date
+            ),
+            MoneyError::NotACurrency(field_type) => {
+                write!(f, "Expected a currency amount but got a {}", field_type)
+            }
+            MoneyError::Overflow(currency) => {
+                write!(
+                    f,
+                    "The amount in {} is too large to calculate",
+                    currency.code()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+/// An amount of money in a currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Creates an amount of money.
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Creates a zero amount in a currency.
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// Adds another amount in the same currency.
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.check_same_currency(other)?;
+        self.with_amount(self.amount.checked_add(other.amount))
+    }
+
+    /// Subtracts another amount in the same currency.
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.check_same_currency(other)?;
+        self.with_amount(self.amount.checked_sub(other.amount))
+    }
+
+    /// Multiplies the amount by a factor, like a probability or a quantity.
+    pub fn scale(&self, factor: Decimal) -> Result<Money, MoneyError> {
+        self.with_amount(self.amount.checked_mul(factor))
+    }
+
+    /// Compares with another amount in the same currency.
+    pub fn compare(&self, other: &Money) -> Result<Ordering, MoneyError> {
+        self.check_same_currency(other)?;
+        Ok(self.amount.cmp(&other.amount))
+    }
+
+    /// Rounds the amount to the minor unit of its currency, like cents for EUR.
+    pub fn rounded(&self) -> Money {
+        let decimal_places = self.currency.exponent().unwrap_or(2);
+        Money::new(self.amount.round_dp(decimal_places.into()), self.currency)
+    }
+
+    /// Converts the amount to another currency, with the exchange rate valid on a date.
+    pub fn convert(
+        &self,
+        to: Currency,
+        rates: &ExchangeRates,
+        date: NaiveDate,
+    ) -> Result<Money, MoneyError> {
+        if self.currency == to {
+            return Ok(*self);
+        }
+
+        let rate = rates
+            .rate(self.currency, to, date)
+            .ok_or(MoneyError::MissingExchangeRate {
+                from: self.currency,
+                to,
+                date,
+            })?;
+
+        self.amount
+            .checked_mul(rate)
+            .map(|amount| Money::new(amount, to))
+            .ok_or(MoneyError::Overflow(to))
+    }
+
+    /// Totals amounts in any currencies in one currency, converting each with the exchange rate valid on a date.
+    pub fn total<'a>(
+        amounts: impl IntoIterator<Item = &'a Money>,
+        currency: Currency,
+        rates: &ExchangeRates,
+        date: NaiveDate,
+    ) -> Result<Money, MoneyError> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, amount| {
+                total.checked_add(&amount.convert(currency, rates, date)?)
+            })
+    }
+
+    /// Makes an amount in the same currency from the result of a checked calculation.
+    fn with_amount(&self, amount: Option<Decimal>) -> Result<Money, MoneyError> {
+        amount
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow(self.currency))
+    }
+
+    fn check_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::MixedCurrencies {
+                left: self.currency,
+                right: other.currency,
+            })
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency.code())
+    }
+}
+
+/// Convert from a currency FieldValue to Money.
+impl TryFrom<&FieldValue> for Money {
+    type Error = MoneyError;
+
+    fn try_from(value: &FieldValue) -> Result<Self, MoneyError> {
+        match value {
+            FieldValue::Currency { amount, currency } => Ok(Money::new(*amount, *currency)),
+            other => Err(MoneyError::NotACurrency(other.get_type())),
+        }
+    }
+}
+
+/// Convert from Money to FieldValue.
+impl From<Money> for FieldValue {
+    fn from(value: Money) -> Self {
+        FieldValue::Currency {
+            amount: value.amount,
+            currency: value.currency,
+        }
+    }
+}
+
+/// The price of one unit of a currency in another currency, from a date until a newer rate replaces it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRate {
+    pub currency: Currency,
+    pub rate: Money,
+    pub valid_from: NaiveDate,
+}
+
+impl ExchangeRate {
+    /// Creates an exchange rate, where one unit of the currency is worth the rate.
+    pub fn new(currency: Currency, rate: Money, valid_from: NaiveDate) -> Self {
+        Self {
+            currency,
+            rate,
+            valid_from,
+        }
+    }
+
+    /// Reads an exchange rate from an `exchange_rate` entity.
+    /// Returns the problems found if the entity is missing its currency, rate or date, or if they are invalid.
+    pub fn from_entity(entity: &Entity) -> Result<Self, Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let currency_id = FieldId::new("currency");
+        let currency = match entity.get_field(&currency_id) {
+            Some(FieldValue::String(code)) => {
+                let currency = Currency::from_code(code.trim());
+                if currency.is_none() {
+                    errors.push(ValidationError::invalid_exchange_rate(
+                        &entity.id,
+                        &currency_id,
+                        &format!("'{}' is not a known currency code", code),
+                    ));
+                }
+                currency
+            }
+            other => {
+                errors.push(field_error(entity, &currency_id, FieldType::String, other));
+                None
+            }
+        };
+
+        let rate_id = FieldId::new("rate");
+        let rate = match entity.get_field(&rate_id) {
+            Some(FieldValue::Currency { amount, currency }) => {
+                if *amount <= Decimal::ZERO {
+                    errors.push(ValidationError::invalid_exchange_rate(
+                        &entity.id,
+                        &rate_id,
+                        "the rate must be more than zero",
+                    ));
+                }
+                Some(Money::new(*amount, *currency))
+            }
+            other => {
+                errors.push(field_error(entity, &rate_id, FieldType::Currency, other));
+                None
+            }
+        };
+
+        let valid_from_id = FieldId::new("valid_from");
+        let valid_from = match entity.get_field(&valid_from_id) {
+            Some(FieldValue::Date(date)) => Some(*date),
+            Some(FieldValue::DateTime(datetime)) => Some(datetime.date_naive()),
+            other => {
+                errors.push(field_error(entity, &valid_from_id, FieldType::Date, other));
+                None
+            }
+        };
+
+        match (currency, rate, valid_from) {
+            (Some(currency), Some(rate), Some(valid_from)) if errors.is_empty() => {
+                Ok(Self::new(currency, rate, valid_from))
+            }
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Makes the error for a field of an exchange rate which is missing or of the wrong type.
+fn field_error(
+    entity: &Entity,
+    field_id: &FieldId,
+    expected: FieldType,
+    value: Option<&FieldValue>,
+) -> ValidationError {
+    match value {
+        Some(value) => ValidationError::mismatched_field_type(
+            &entity.id,
+            field_id,
+            &expected,
+            &value.get_type(),
+        ),
+        None => ValidationError::missing_field(&entity.id, field_id),
+    }
+}
+
+/// A set of dated exchange rates to convert amounts with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExchangeRates {
+    rates: Vec<ExchangeRate>,
+}
+
+impl ExchangeRates {
+    /// Creates an empty set of exchange rates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the exchange rates from the `exchange_rate` entities among a set of entities.
+    /// Other entities, and exchange rates which can't be read, are ignored.
+    /// Use [`ExchangeRates::find_warnings`] to report the exchange rates which are ignored.
+    pub fn from_entities<'a>(entities: impl IntoIterator<Item = &'a Entity>) -> Self {
+        let exchange_rate_type = EntityType::new(EXCHANGE_RATE_TYPE);
+
+        Self {
+            rates: entities
+                .into_iter()
+                .filter(|entity| entity.entity_type == exchange_rate_type)
+                .filter_map(|entity| ExchangeRate::from_entity(entity).ok())
+                .collect(),
+        }
+    }
+
+    /// Finds the `exchange_rate` entities among a set of entities which can't be read as exchange rates.
+    /// These are left out of conversions, so they're reported as warnings rather than failing a build.
+    pub fn find_warnings<'a>(
+        entities: impl IntoIterator<Item = &'a Entity>,
+    ) -> Vec<ValidationError> {
+        let exchange_rate_type = EntityType::new(EXCHANGE_RATE_TYPE);
+
+        entities
+            .into_iter()
+            .filter(|entity| entity.entity_type == exchange_rate_type)
+            .filter_map(|entity| ExchangeRate::from_entity(entity).err())
+            .flatten()
+            .collect()
+    }
+
+    /// Builder method to add an exchange rate.
+    pub fn with_rate(mut self, rate: ExchangeRate) -> Self {
+        self.rates.push(rate);
+        self
+    }
+
+    /// Checks if there are no exchange rates.
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Gets the rate to multiply amounts in one currency by to get amounts in another, valid on a date.
+    ///
+    /// A rate defined in either direction can be used. If there's no rate between the currencies,
+    /// they can be converted through a third currency both have rates to.
+    pub fn rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        if let Some(rate) = self.direct_rate(from, to, date) {
+            return Some(rate);
+        }
+
+        // Intermediate currencies are tried in the order their rates were added
+        let mut intermediates: Vec<Currency> = Vec::new();
+        for currency in self
+            .rates
+            .iter()
+            .flat_map(|rate| [rate.currency, rate.rate.currency])
+        {
+            if currency != from && currency != to && !intermediates.contains(&currency) {
+                intermediates.push(currency);
+            }
+        }
+
+        intermediates.into_iter().find_map(|via| {
+            let first = self.direct_rate(from, via, date)?;
+            let second = self.direct_rate(via, to, date)?;
+            first.checked_mul(second)
+        })
+    }
+
+    /// Gets the newest rate between two currencies valid on a date, in either direction.
+    fn direct_rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        self.rates
+            .iter()
+            .filter(|rate| rate.valid_from <= date)
+            .filter_map(|rate| {
+                if rate.currency == from && rate.rate.currency == to {
+                    Some((rate.valid_from, rate.rate.amount))
+                } else if rate.currency == to && rate.rate.currency == from {
+                    Decimal::ONE
+                        .checked_div(rate.rate.amount)
+                        .map(|inverse| (rate.valid_from, inverse))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|(valid_from, _)| *valid_from)
+            .map(|(_, rate)| rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityId;
+    use crate::schema::ValidationErrorType;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn eur(amount: Decimal) -> Money {
+        Money::new(amount, Currency::EUR)
+    }
+
+    fn rates() -> ExchangeRates {
+        ExchangeRates::new()
+            .with_rate(ExchangeRate::new(
+                Currency::EUR,
+                Money::new(Decimal::new(745, 2), Currency::DKK),
+                date(2025, 1, 1),
+            ))
+            .with_rate(ExchangeRate::new(
+                Currency::EUR,
+                Money::new(Decimal::new(750, 2), Currency::DKK),
+                date(2025, 6, 1),
+            ))
+            .with_rate(ExchangeRate::new(
+                Currency::USD,
+                Money::new(Decimal::new(90, 2), Currency::EUR),
+                date(2025, 1, 1),
+            ))
+    }
+
+    #[test]
+    fn test_add_and_compare_same_currency() {
+        let total = eur(Decimal::new(1050, 2))
+            .checked_add(&eur(Decimal::new(225, 2)))
+            .unwrap();
+        assert_eq!(total, eur(Decimal::new(1275, 2)));
+        assert_eq!(
+            total.checked_sub(&eur(Decimal::new(75, 2))).unwrap(),
+            eur(Decimal::new(1200, 2))
+        );
+        assert_eq!(
+            total.scale(Decimal::new(5, 1)),
+            Ok(eur(Decimal::new(6375, 3)))
+        );
+        assert_eq!(
+            total.scale(Decimal::new(5, 1)).unwrap().rounded(),
+            eur(Decimal::new(638, 2))
+        );
+        assert_eq!(total.compare(&eur(Decimal::new(20, 0))), Ok(Ordering::Less));
+    }
+
+    #[test]
+    fn test_overflow_is_refused() {
+        let largest = eur(Decimal::MAX);
+
+        assert_eq!(
+            largest.checked_add(&eur(Decimal::ONE)),
+            Err(MoneyError::Overflow(Currency::EUR))
+        );
+        assert_eq!(
+            eur(Decimal::MIN).checked_sub(&eur(Decimal::ONE)),
+            Err(MoneyError::Overflow(Currency::EUR))
+        );
+        assert_eq!(
+            largest.scale(Decimal::TWO),
+            Err(MoneyError::Overflow(Currency::EUR))
+        );
+
+        let rates = ExchangeRates::new().with_rate(ExchangeRate::new(
+            Currency::EUR,
+            Money::new(Decimal::new(745, 2), Currency::DKK),
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        ));
+        assert_eq!(
+            largest.convert(
+                Currency::DKK,
+                &rates,
+                NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+            ),
+            Err(MoneyError::Overflow(Currency::DKK))
+        );
+    }
+
+    #[test]
+    fn test_mixed_currencies_are_refused() {
+        let dollars = Money::new(Decimal::new(5, 0), Currency::USD);
+        assert_eq!(
+            eur(Decimal::new(10, 0)).checked_add(&dollars),
+            Err(MoneyError::MixedCurrencies {
+                left: Currency::EUR,
+                right: Currency::USD
+            })
+        );
+        assert!(eur(Decimal::new(10, 0)).compare(&dollars).is_err());
+    }
+
+    #[test]
+    fn test_convert_with_rate_valid_on_date() {
+        let rates = rates();
+
+        let spring = eur(Decimal::new(100, 0)).convert(Currency::DKK, &rates, date(2025, 3, 1));
+        assert_eq!(
+            spring,
+            Ok(Money::new(Decimal::new(74500, 2), Currency::DKK))
+        );
+
+        let summer = eur(Decimal::new(100, 0)).convert(Currency::DKK, &rates, date(2025, 7, 1));
+        assert_eq!(
+            summer,
+            Ok(Money::new(Decimal::new(75000, 2), Currency::DKK))
+        );
+
+        let before_rates =
+            eur(Decimal::new(100, 0)).convert(Currency::DKK, &rates, date(2024, 12, 31));
+        assert_eq!(
+            before_rates,
+            Err(MoneyError::MissingExchangeRate {
+                from: Currency::EUR,
+                to: Currency::DKK,
+                date: date(2024, 12, 31)
+            })
+        );
+    }
+
+    #[test]
+    fn test_convert_with_inverse_and_cross_rates() {
+        let rates = rates();
+
+        let inverse = Money::new(Decimal::new(745, 0), Currency::DKK)
+            .convert(Currency::EUR, &rates, date(2025, 3, 1))
+            .unwrap();
+        assert_eq!(inverse.rounded(), eur(Decimal::new(10000, 2)));
+
+        let cross = Money::new(Decimal::new(100, 0), Currency::USD)
+            .convert(Currency::DKK, &rates, date(2025, 3, 1))
+            .unwrap();
+        assert_eq!(cross, Money::new(Decimal::new(6705, 1), Currency::DKK));
+    }
+
+    #[test]
+    fn test_total_in_chosen_currency() {
+        let amounts = [
+            eur(Decimal::new(100, 0)),
+            Money::new(Decimal::new(100, 0), Currency::USD),
+            Money::new(Decimal::new(745, 0), Currency::DKK),
+        ];
+
+        let total = Money::total(&amounts, Currency::EUR, &rates(), date(2025, 3, 1)).unwrap();
+        assert_eq!(total.rounded(), eur(Decimal::new(29000, 2)));
+    }
+
+    #[test]
+    fn test_exchange_rates_from_entities() {
+        let exchange_rate = Entity::new(
+            EntityId::new("exchange_rate.eur_dkk"),
+            EntityType::new(EXCHANGE_RATE_TYPE),
+        )
+        .with_field(FieldId::new("currency"), "EUR")
+        .with_field(
+            FieldId::new("rate"),
+            FieldValue::Currency {
+                amount: Decimal::new(745, 2),
+                currency: Currency::DKK,
+            },
+        )
+        .with_field(FieldId::new("valid_from"), date(2025, 1, 1));
+        let person = Entity::new(EntityId::new("person.jane"), EntityType::new("person"));
+
+        let rates = ExchangeRates::from_entities([&exchange_rate, &person]);
+        assert_eq!(
+            rates.rate(Currency::EUR, Currency::DKK, date(2025, 2, 1)),
+            Some(Decimal::new(745, 2))
+        );
+        assert!(ExchangeRates::find_warnings([&exchange_rate, &person]).is_empty());
+    }
+
+    #[test]
+    fn test_exchange_rate_warnings() {
+        let exchange_rate = |id: &str, code: &str, amount: Decimal| {
+            Entity::new(EntityId::new(id), EntityType::new(EXCHANGE_RATE_TYPE))
+                .with_field(FieldId::new("currency"), code)
+                .with_field(
+                    FieldId::new("rate"),
+                    FieldValue::Currency {
+                        amount,
+                        currency: Currency::DKK,
+                    },
+                )
+        };
+        let dated =
+            |entity: Entity| entity.with_field(FieldId::new("valid_from"), date(2025, 1, 1));
+
+        let unknown_code = dated(exchange_rate("exchange_rate.xyz", "XYZ", Decimal::ONE));
+        let zero_rate = dated(exchange_rate("exchange_rate.zero", "EUR", Decimal::ZERO));
+        let undated = exchange_rate("exchange_rate.undated", "EUR", Decimal::ONE);
+        let valid = dated(exchange_rate(
+            "exchange_rate.eur",
+            "EUR",
+            Decimal::new(745, 2),
+        ));
+        let entities = [&unknown_code, &zero_rate, &undated, &valid];
+
+        let warnings = ExchangeRates::find_warnings(entities);
+        let fields: Vec<(&str, &str)> = warnings
+            .iter()
+            .map(|warning| {
+                (
+                    warning.entity_id.as_ref().unwrap().as_str(),
+                    warning.field.as_ref().unwrap().as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("exchange_rate.xyz", "currency"),
+                ("exchange_rate.zero", "rate"),
+                ("exchange_rate.undated", "valid_from"),
+            ]
+        );
+        assert_eq!(
+            warnings[0].error_type,
+            ValidationErrorType::InvalidExchangeRate
+        );
+        assert_eq!(
+            warnings[2].error_type,
+            ValidationErrorType::MissingRequiredField {
+                required: FieldId::new("valid_from")
+            }
+        );
+
+        assert!(!ExchangeRates::from_entities(entities).is_empty());
+    }
+}
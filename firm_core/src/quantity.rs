@@ -0,0 +1,245 @@
+//! Quantities with a unit of measure, like `120 h` or `5 TB`.
+//!
+//! Units Firm knows belong to a dimension, and quantities can be converted between units of the same dimension.
+//! Other units, like `users`, are kept as written and are only compatible with themselves.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The kind of measurement a unit belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dimension {
+    Time,
+    Data,
+    Length,
+    Mass,
+}
+
+impl Dimension {
+    /// Gets the known units of the dimension, from smallest to largest.
+    pub fn units(&self) -> Vec<&'static str> {
+        UNITS
+            .iter()
+            .filter(|(_, dimension, _)| dimension == self)
+            .map(|(name, _, _)| *name)
+            .collect()
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Dimension::Time => "time",
+            Dimension::Data => "data",
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+        }
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "time" => Ok(Dimension::Time),
+            "data" => Ok(Dimension::Data),
+            "length" => Ok(Dimension::Length),
+            "mass" => Ok(Dimension::Mass),
+            _ => Err(format!(
+                "Unknown dimension '{}' (expected time, data, length or mass)",
+                s
+            )),
+        }
+    }
+}
+
+/// The known units, with their dimension and their size in the base unit of that dimension.
+const UNITS: [(&str, Dimension, f64); 22] = [
+    ("s", Dimension::Time, 1.0),
+    ("min", Dimension::Time, 60.0),
+    ("h", Dimension::Time, 3_600.0),
+    ("d", Dimension::Time, 86_400.0),
+    ("w", Dimension::Time, 604_800.0),
+    ("B", Dimension::Data, 1.0),
+    ("KB", Dimension::Data, 1e3),
+    ("MB", Dimension::Data, 1e6),
+    ("GB", Dimension::Data, 1e9),
+    ("TB", Dimension::Data, 1e12),
+    ("KiB", Dimension::Data, 1_024.0),
+    ("MiB", Dimension::Data, 1_048_576.0),
+    ("GiB", Dimension::Data, 1_073_741_824.0),
+    ("TiB", Dimension::Data, 1_099_511_627_776.0),
+    ("mm", Dimension::Length, 0.001),
+    ("cm", Dimension::Length, 0.01),
+    ("m", Dimension::Length, 1.0),
+    ("km", Dimension::Length, 1_000.0),
+    ("mg", Dimension::Mass, 0.001),
+    ("g", Dimension::Mass, 1.0),
+    ("kg", Dimension::Mass, 1_000.0),
+    ("t", Dimension::Mass, 1_000_000.0),
+];
+
+/// Looks up the dimension and base unit factor of a known unit.
+fn unit_info(unit: &str) -> Option<(Dimension, f64)> {
+    UNITS
+        .iter()
+        .find(|(name, _, _)| *name == unit)
+        .map(|(_, dimension, factor)| (*dimension, *factor))
+}
+
+/// A number with a unit of measure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: String,
+}
+
+impl Quantity {
+    /// Creates a quantity from a value and a unit.
+    pub fn new(value: f64, unit: impl Into<String>) -> Self {
+        Self {
+            value,
+            unit: unit.into(),
+        }
+    }
+
+    /// Gets the dimension of the quantity's unit, if it's a unit Firm knows.
+    pub fn dimension(&self) -> Option<Dimension> {
+        unit_info(&self.unit).map(|(dimension, _)| dimension)
+    }
+
+    /// Checks if the quantity can be converted to a unit.
+    /// Units are compatible if they're the same, or known units of the same dimension.
+    pub fn is_convertible_to(&self, unit: &str) -> bool {
+        self.unit == unit
+            || matches!(
+                (unit_info(&self.unit), unit_info(unit)),
+                (Some((from, _)), Some((to, _))) if from == to
+            )
+    }
+
+    /// Converts the quantity to another unit of the same dimension.
+    pub fn convert_to(&self, unit: &str) -> Option<Quantity> {
+        if self.unit == unit {
+            return Some(self.clone());
+        }
+
+        let (from_dimension, from_factor) = unit_info(&self.unit)?;
+        let (to_dimension, to_factor) = unit_info(unit)?;
+        if from_dimension != to_dimension {
+            return None;
+        }
+
+        Some(Quantity::new(self.value * from_factor / to_factor, unit))
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let [value, unit] = parts[..] else {
+            return Err(format!(
+                "Quantity '{}' should be a number and a unit, like '120 h'",
+                s
+            ));
+        };
+
+        let value: f64 = value
+            .parse()
+            .map_err(|_| format!("Quantity value '{}' is not a number", value))?;
+
+        if !value.is_finite() || !unit.chars().all(|c| c.is_alphabetic() || c == '_') {
+            return Err(format!("Quantity '{}' is not valid", s));
+        }
+
+        Ok(Quantity::new(value, unit))
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Quantity", &self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_quantity() {
+        let quantity: Quantity = "120 h".parse().unwrap();
+        assert_eq!(quantity, Quantity::new(120.0, "h"));
+        assert_eq!(quantity.to_string(), "120 h");
+
+        let quantity: Quantity = "1.5 TB".parse().unwrap();
+        assert_eq!(quantity.to_string(), "1.5 TB");
+
+        let quantity: Quantity = "40 users".parse().unwrap();
+        assert_eq!(quantity.dimension(), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_quantity() {
+        assert!("120".parse::<Quantity>().is_err());
+        assert!("h 120".parse::<Quantity>().is_err());
+        assert!("120 h extra".parse::<Quantity>().is_err());
+        assert!("120 h/s".parse::<Quantity>().is_err());
+    }
+
+    #[test]
+    fn test_convert_between_units() {
+        let hours = Quantity::new(120.0, "h");
+        assert_eq!(hours.dimension(), Some(Dimension::Time));
+        assert_eq!(hours.convert_to("d"), Some(Quantity::new(5.0, "d")));
+
+        let storage = Quantity::new(5.0, "TB");
+        assert_eq!(storage.convert_to("GB"), Some(Quantity::new(5000.0, "GB")));
+        assert_eq!(
+            Quantity::new(2.0, "GiB").convert_to("MiB"),
+            Some(Quantity::new(2048.0, "MiB"))
+        );
+    }
+
+    #[test]
+    fn test_convert_between_incompatible_units() {
+        let hours = Quantity::new(120.0, "h");
+        assert!(!hours.is_convertible_to("GB"));
+        assert_eq!(hours.convert_to("GB"), None);
+
+        let users = Quantity::new(40.0, "users");
+        assert!(users.is_convertible_to("users"));
+        assert_eq!(users.convert_to("seats"), None);
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!("time".parse(), Ok(Dimension::Time));
+        assert_eq!(Dimension::Data.to_string(), "data");
+        assert!("volume".parse::<Dimension>().is_err());
+        assert_eq!(Dimension::Time.units(), vec!["s", "min", "h", "d", "w"]);
+    }
+}
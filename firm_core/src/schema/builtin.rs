@@ -1,30 +1,118 @@
-use crate::{EntitySchema, EntityType, FieldId, FieldType};
+use super::{Comparison, FieldConstraints, FieldDefault, RuleSeverity, SchemaRule};
+use crate::graph::{DEPENDS_ON_FIELD, ESTIMATE_FIELD};
+use crate::{EntitySchema, EntityType, FieldId, FieldType, FieldValue, money::EXCHANGE_RATE_TYPE};
+
+/// The groups of built-in schemas that a workspace can opt in or out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinGroup {
+    /// People, organizations and industries.
+    Core,
+    /// Accounts, channels, leads, contacts, interactions and opportunities.
+    CustomerRelations,
+    /// Strategies, objectives, key results, projects, tasks and reviews.
+    WorkManagement,
+    /// Files and other assets.
+    Resources,
+    /// Exchange rates between currencies. Workspaces have to opt in to this group.
+    Finance,
+}
+
+impl BuiltinGroup {
+    /// Gets all built-in schema groups.
+    pub fn all() -> Vec<BuiltinGroup> {
+        vec![
+            BuiltinGroup::Core,
+            BuiltinGroup::CustomerRelations,
+            BuiltinGroup::WorkManagement,
+            BuiltinGroup::Resources,
+            BuiltinGroup::Finance,
+        ]
+    }
+
+    /// Gets the built-in schema groups that workspaces have unless they opt out.
+    pub fn defaults() -> Vec<BuiltinGroup> {
+        vec![
+            BuiltinGroup::Core,
+            BuiltinGroup::CustomerRelations,
+            BuiltinGroup::WorkManagement,
+            BuiltinGroup::Resources,
+        ]
+    }
+}
+
+impl std::fmt::Display for BuiltinGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuiltinGroup::Core => write!(f, "core"),
+            BuiltinGroup::CustomerRelations => write!(f, "customer_relations"),
+            BuiltinGroup::WorkManagement => write!(f, "work_management"),
+            BuiltinGroup::Resources => write!(f, "resources"),
+            BuiltinGroup::Finance => write!(f, "finance"),
+        }
+    }
+}
+
+impl std::str::FromStr for BuiltinGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "core" => Ok(BuiltinGroup::Core),
+            "customer_relations" => Ok(BuiltinGroup::CustomerRelations),
+            "work_management" => Ok(BuiltinGroup::WorkManagement),
+            "resources" => Ok(BuiltinGroup::Resources),
+            "finance" => Ok(BuiltinGroup::Finance),
+            _ => Err(format!("Unknown built-in schema group: '{}'", s)),
+        }
+    }
+}
 
 impl EntitySchema {
     /// Instantiates all built-in schemas.
     pub fn all_builtin() -> Vec<EntitySchema> {
-        vec![
-            // Core entities
-            EntitySchema::person(),
-            EntitySchema::organization(),
-            EntitySchema::industry(),
-            // Customer relations
-            EntitySchema::account(),
-            EntitySchema::channel(),
-            EntitySchema::lead(),
-            EntitySchema::contact(),
-            EntitySchema::interaction(),
-            EntitySchema::opportunity(),
-            // Work management
-            EntitySchema::strategy(),
-            EntitySchema::objective(),
-            EntitySchema::key_result(),
-            EntitySchema::project(),
-            EntitySchema::task(),
-            EntitySchema::review(),
-            // Resources
-            EntitySchema::file_asset(),
-        ]
+        Self::builtin_groups(&BuiltinGroup::all())
+    }
+
+    /// Instantiates the built-in schemas for a selection of groups.
+    pub fn builtin_groups(groups: &[BuiltinGroup]) -> Vec<EntitySchema> {
+        groups
+            .iter()
+            .flat_map(|group| Self::builtin_group(group))
+            .collect()
+    }
+
+    /// Instantiates the common metadata fields as a reusable field group.
+    pub fn metadata_group() -> EntitySchema {
+        EntitySchema::new(EntityType::new("metadata")).with_metadata()
+    }
+
+    /// Instantiates the built-in schemas for a single group.
+    pub fn builtin_group(group: &BuiltinGroup) -> Vec<EntitySchema> {
+        match group {
+            BuiltinGroup::Core => vec![
+                EntitySchema::person(),
+                EntitySchema::organization(),
+                EntitySchema::industry(),
+            ],
+            BuiltinGroup::CustomerRelations => vec![
+                EntitySchema::account(),
+                EntitySchema::channel(),
+                EntitySchema::lead(),
+                EntitySchema::contact(),
+                EntitySchema::interaction(),
+                EntitySchema::opportunity(),
+            ],
+            BuiltinGroup::WorkManagement => vec![
+                EntitySchema::strategy(),
+                EntitySchema::objective(),
+                EntitySchema::key_result(),
+                EntitySchema::project(),
+                EntitySchema::task(),
+                EntitySchema::review(),
+            ],
+            BuiltinGroup::Resources => vec![EntitySchema::file_asset()],
+            BuiltinGroup::Finance => vec![EntitySchema::exchange_rate()],
+        }
     }
 
     /// An individual person (an Agent in the REA model).
@@ -37,6 +125,7 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("email"), FieldType::String)
             .with_optional_field(FieldId::new("phone"), FieldType::String)
             .with_optional_field(FieldId::new("urls"), FieldType::List)
+            .with_constraints(&FieldId::new("email"), email_constraints())
     }
 
     /// An organization, company, or group (an Agent in the REA model).
@@ -52,6 +141,15 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("urls"), FieldType::List)
             .with_optional_field(FieldId::new("vat_id"), FieldType::String)
             .with_optional_field(FieldId::new("industry_ref"), FieldType::Reference)
+            .with_constraints(&FieldId::new("email"), email_constraints())
+            .with_constraints(
+                &FieldId::new("vat_id"),
+                FieldConstraints {
+                    unique: true,
+                    severity: RuleSeverity::Warning,
+                    ..Default::default()
+                },
+            )
     }
 
     /// Represents an industry or business sector.
@@ -67,6 +165,26 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("classification_system"), FieldType::String)
     }
 
+    /// Represents the rate between two currencies from a given date.
+    ///
+    /// One unit of the currency is worth the rate until a newer rate replaces it. Totals of
+    /// amounts in different currencies are converted with these rates.
+    pub fn exchange_rate() -> Self {
+        Self::new(EntityType::new(EXCHANGE_RATE_TYPE))
+            .with_metadata()
+            .with_required_field(FieldId::new("currency"), FieldType::String)
+            .with_required_field(FieldId::new("rate"), FieldType::Currency)
+            .with_required_field(FieldId::new("valid_from"), FieldType::Date)
+            .with_constraints(
+                &FieldId::new("currency"),
+                FieldConstraints {
+                    pattern: Some(r"^[A-Z]{3}$".to_string()),
+                    severity: RuleSeverity::Warning,
+                    ..Default::default()
+                },
+            )
+    }
+
     /// Represents a business relationship with an organization, typically a customer.
     ///
     /// This is a contextual entity that links to an organization and tracks the state
@@ -150,6 +268,24 @@ impl EntitySchema {
             .with_required_field(FieldId::new("status"), FieldType::String)
             .with_optional_field(FieldId::new("value"), FieldType::Currency)
             .with_optional_field(FieldId::new("probability"), FieldType::Integer)
+            .with_constraints(
+                &FieldId::new("probability"),
+                FieldConstraints {
+                    min: Some(0.0),
+                    max: Some(100.0),
+                    severity: RuleSeverity::Warning,
+                    ..Default::default()
+                },
+            )
+            .with_rule(
+                SchemaRule::required_if(
+                    FieldId::new("value"),
+                    FieldId::new("status"),
+                    Some(FieldValue::String(String::from("won"))),
+                )
+                .with_message("Won opportunities need a value")
+                .with_severity(RuleSeverity::Warning),
+            )
     }
 
     /// Represents a high-level, long-term plan or goal.
@@ -164,8 +300,17 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("source_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("owner_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("status"), FieldType::String)
-            .with_optional_field(FieldId::new("start_date"), FieldType::DateTime)
-            .with_optional_field(FieldId::new("end_date"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("start_date"), FieldType::Date)
+            .with_optional_field(FieldId::new("end_date"), FieldType::Date)
+            .with_rule(
+                SchemaRule::compare(
+                    FieldId::new("end_date"),
+                    Comparison::Greater,
+                    FieldId::new("start_date"),
+                )
+                .with_message("A strategy must end after it starts")
+                .with_severity(RuleSeverity::Warning),
+            )
     }
 
     /// Represents a specific, measurable goal that contributes to a strategy.
@@ -180,8 +325,8 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("strategy_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("owner_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("status"), FieldType::String)
-            .with_optional_field(FieldId::new("start_date"), FieldType::DateTime)
-            .with_optional_field(FieldId::new("end_date"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("start_date"), FieldType::Date)
+            .with_optional_field(FieldId::new("end_date"), FieldType::Date)
     }
 
     /// Represents a measurable outcome used to track an objective.
@@ -210,13 +355,14 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("description"), FieldType::String)
             .with_optional_field(FieldId::new("owner_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("objective_refs"), FieldType::List)
-            .with_optional_field(FieldId::new("due_date"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("due_date"), FieldType::Date)
     }
 
     /// Represents a single, actionable unit of work.
     ///
     /// Tasks are the most granular items in work management and are typically associated
-    /// with a project or another source entity.
+    /// with a project or another source entity. Tasks can depend on other tasks, and be
+    /// planned with their estimates.
     pub fn task() -> Self {
         Self::new(EntityType::new("task"))
             .with_metadata()
@@ -224,9 +370,32 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("description"), FieldType::String)
             .with_optional_field(FieldId::new("source_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("assignee_ref"), FieldType::Reference)
-            .with_optional_field(FieldId::new("due_date"), FieldType::DateTime)
+            .with_optional_field(FieldId::new(DEPENDS_ON_FIELD), FieldType::List)
+            .with_optional_field(FieldId::new(ESTIMATE_FIELD), FieldType::Duration)
+            .with_optional_field(FieldId::new("due_date"), FieldType::Date)
             .with_optional_field(FieldId::new("is_completed"), FieldType::Boolean)
             .with_optional_field(FieldId::new("completed_at"), FieldType::DateTime)
+            .with_constraints(
+                &FieldId::new(DEPENDS_ON_FIELD),
+                FieldConstraints {
+                    references: Some(EntityType::new("task")),
+                    severity: RuleSeverity::Warning,
+                    ..Default::default()
+                },
+            )
+            .with_default(
+                &FieldId::new("is_completed"),
+                FieldDefault::Value(FieldValue::Boolean(false)),
+            )
+            .with_rule(
+                SchemaRule::required_if(
+                    FieldId::new("completed_at"),
+                    FieldId::new("is_completed"),
+                    Some(FieldValue::Boolean(true)),
+                )
+                .with_message("Completed tasks need a completion date")
+                .with_severity(RuleSeverity::Warning),
+            )
     }
 
     /// Represents a periodic review or meeting (an Event in the REA model).
@@ -237,7 +406,7 @@ impl EntitySchema {
         Self::new(EntityType::new("review"))
             .with_metadata()
             .with_required_field(FieldId::new("name"), FieldType::String)
-            .with_required_field(FieldId::new("date"), FieldType::DateTime)
+            .with_required_field(FieldId::new("date"), FieldType::Date)
             .with_optional_field(FieldId::new("owner_ref"), FieldType::Reference)
             .with_optional_field(FieldId::new("source_refs"), FieldType::List)
             .with_optional_field(FieldId::new("attendee_refs"), FieldType::List)
@@ -257,3 +426,82 @@ impl EntitySchema {
             .with_optional_field(FieldId::new("owner_ref"), FieldType::Reference)
     }
 }
+
+/// Constraints for a loosely validated email address.
+fn email_constraints() -> FieldConstraints {
+    FieldConstraints {
+        pattern: Some(r"^[^@\s]+@[^@\s]+\.[^@\s]+$".to_string()),
+        severity: RuleSeverity::Warning,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_builtin_covers_every_group() {
+        let from_groups: usize = BuiltinGroup::all()
+            .iter()
+            .map(|group| EntitySchema::builtin_group(group).len())
+            .sum();
+
+        assert_eq!(EntitySchema::all_builtin().len(), from_groups);
+    }
+
+    #[test]
+    fn test_builtin_groups_selection() {
+        let schemas = EntitySchema::builtin_groups(&[BuiltinGroup::Core]);
+        let types: Vec<String> = schemas.iter().map(|s| s.entity_type.to_string()).collect();
+
+        assert_eq!(types, vec!["person", "organization", "industry"]);
+    }
+
+    #[test]
+    fn test_exchange_rates_are_opt_in() {
+        let has_exchange_rate = |groups: &[BuiltinGroup]| {
+            EntitySchema::builtin_groups(groups)
+                .iter()
+                .any(|s| s.entity_type.as_str() == EXCHANGE_RATE_TYPE)
+        };
+
+        assert!(!has_exchange_rate(&BuiltinGroup::defaults()));
+        assert!(has_exchange_rate(&[BuiltinGroup::Finance]));
+    }
+
+    #[test]
+    fn test_builtin_group_from_str() {
+        assert_eq!(
+            "work_management".parse::<BuiltinGroup>(),
+            Ok(BuiltinGroup::WorkManagement)
+        );
+        assert!("everything".parse::<BuiltinGroup>().is_err());
+    }
+
+    #[test]
+    fn test_builtin_constraints_only_warn() {
+        for schema in EntitySchema::all_builtin() {
+            for (field_id, field_schema) in &schema.fields {
+                assert!(
+                    field_schema.constraints.is_empty()
+                        || field_schema.constraints.severity == RuleSeverity::Warning,
+                    "Constraints of '{}.{}' should only warn",
+                    schema.entity_type,
+                    field_id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_builtin_rules_refer_to_declared_fields() {
+        for schema in EntitySchema::all_builtin() {
+            assert!(
+                schema.check_rules().is_ok(),
+                "Rules of '{}' are invalid",
+                schema.entity_type
+            );
+        }
+    }
+}
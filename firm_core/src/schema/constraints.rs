@@ -0,0 +1,555 @@
+use regex::Regex;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use super::{EntitySchema, RuleSeverity, ValidationError, ValidationResult};
+use crate::{
+    Entity, EntityId, EntityType, FieldId, FieldType, FieldValue, ReferenceValue,
+    decompose_entity_id, quantity::Dimension,
+};
+
+/// Constraints on the values a field can take, beyond its type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldConstraints {
+    /// The smallest allowed value of an integer, float or currency field.
+    pub min: Option<f64>,
+    /// The largest allowed value of an integer, float or currency field.
+    pub max: Option<f64>,
+    /// A regular expression that string values must match.
+    pub pattern: Option<String>,
+    /// The minimum number of characters in a string.
+    pub min_length: Option<usize>,
+    /// The maximum number of characters in a string.
+    pub max_length: Option<usize>,
+    /// The minimum number of items in a list.
+    pub min_items: Option<usize>,
+    /// The maximum number of items in a list.
+    pub max_items: Option<usize>,
+    /// Whether the value must be unique across all entities of the type.
+    pub unique: bool,
+    /// The entity type that references must point to, for reference fields and lists of references.
+    pub references: Option<EntityType>,
+    /// The dimension that quantities must be measured in, like time or data.
+    pub dimension: Option<Dimension>,
+    /// Whether breaking the constraints makes the entity invalid, or is only reported.
+    #[serde(default)]
+    pub severity: RuleSeverity,
+}
+
+impl FieldConstraints {
+    /// Checks if there are no constraints, whatever their severity.
+    pub fn is_empty(&self) -> bool {
+        self == &Self {
+            severity: self.severity,
+            ..Self::default()
+        }
+    }
+
+    /// Builder method to set whether breaking the constraints makes the entity invalid.
+    pub fn with_severity(mut self, severity: RuleSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Checks that the constraints make sense for a field type.
+    pub fn check_definition(&self, field_type: &FieldType) -> Result<(), String> {
+        let is_numeric = matches!(
+            field_type,
+            FieldType::Integer | FieldType::Float | FieldType::Currency
+        );
+
+        if (self.min.is_some() || self.max.is_some()) && !is_numeric {
+            return Err(format!(
+                "min and max only apply to integer, float and currency fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if (self.pattern.is_some() || self.min_length.is_some() || self.max_length.is_some())
+            && field_type != &FieldType::String
+        {
+            return Err(format!(
+                "pattern and length bounds only apply to string fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if (self.min_items.is_some() || self.max_items.is_some()) && field_type != &FieldType::List
+        {
+            return Err(format!(
+                "item bounds only apply to list fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if self.references.is_some()
+            && !matches!(field_type, FieldType::Reference | FieldType::List)
+        {
+            return Err(format!(
+                "reference targets only apply to reference and list fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if self.dimension.is_some() && field_type != &FieldType::Quantity {
+            return Err(format!(
+                "dimensions only apply to quantity fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if let Some(pattern) = &self.pattern {
+            compile_pattern(pattern).map_err(|err| format!("invalid pattern: {}", err))?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks a field value against the constraints.
+    /// Uniqueness can only be checked across entities, so it's left to `EntitySchema::validate_uniqueness`.
+    pub fn check(
+        &self,
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        value: &FieldValue,
+    ) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        let number = match value {
+            FieldValue::Integer(value) => Some(*value as f64),
+            FieldValue::Float(value) => Some(*value),
+            FieldValue::Currency { amount, .. } => amount.to_f64(),
+            _ => None,
+        };
+
+        if let Some(number) = number {
+            let below = self.min.is_some_and(|min| number < min);
+            let above = self.max.is_some_and(|max| number > max);
+            if below || above {
+                errors.push(ValidationError::value_out_of_range(
+                    entity_id, field_id, self.min, self.max, number,
+                ));
+            }
+        }
+
+        if let FieldValue::String(text) = value {
+            if let Some(pattern) = &self.pattern {
+                let matches = compile_pattern(pattern).is_ok_and(|regex| regex.is_match(text));
+                if !matches {
+                    errors.push(ValidationError::pattern_mismatch(
+                        entity_id, field_id, pattern,
+                    ));
+                }
+            }
+
+            let length = text.chars().count();
+            if !in_bounds(length, self.min_length, self.max_length) {
+                errors.push(ValidationError::length_out_of_range(
+                    entity_id,
+                    field_id,
+                    self.min_length,
+                    self.max_length,
+                    length,
+                ));
+            }
+        }
+
+        if let FieldValue::List(items) = value {
+            if !in_bounds(items.len(), self.min_items, self.max_items) {
+                errors.push(ValidationError::item_count_out_of_range(
+                    entity_id,
+                    field_id,
+                    self.min_items,
+                    self.max_items,
+                    items.len(),
+                ));
+            }
+        }
+
+        match (&self.dimension, value) {
+            (Some(dimension), FieldValue::Quantity(quantity))
+                if quantity.dimension().as_ref() != Some(dimension) =>
+            {
+                errors.push(ValidationError::mismatched_unit(
+                    entity_id,
+                    field_id,
+                    dimension,
+                    &quantity.unit,
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(target_type) = &self.references {
+            let references: Vec<&ReferenceValue> = match value {
+                FieldValue::Reference(reference) => vec![reference],
+                FieldValue::List(items) => items
+                    .iter()
+                    .filter_map(|item| match item {
+                        FieldValue::Reference(reference) => Some(reference),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            for reference in references {
+                let target_id = match reference {
+                    ReferenceValue::Entity(target_id) => target_id,
+                    ReferenceValue::Field(target_id, _) => target_id,
+                };
+
+                let (actual_type, _) = decompose_entity_id(target_id.as_str());
+                if actual_type != target_type.as_str() {
+                    errors.push(ValidationError::mismatched_reference_type(
+                        entity_id,
+                        field_id,
+                        target_type,
+                        target_id,
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Compiled patterns, so each is only compiled once rather than for every value checked.
+static PATTERNS: LazyLock<Mutex<HashMap<String, Result<Regex, regex::Error>>>> =
+    LazyLock::new(Default::default);
+
+/// Compiles a pattern, or gets it from the patterns compiled before.
+fn compile_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut patterns = PATTERNS.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(compiled) = patterns.get(pattern) {
+        return compiled.clone();
+    }
+
+    let compiled = Regex::new(pattern);
+    patterns.insert(pattern.to_string(), compiled.clone());
+    compiled
+}
+
+/// Checks if a count is within optional bounds.
+fn in_bounds(count: usize, min: Option<usize>, max: Option<usize>) -> bool {
+    min.is_none_or(|min| count >= min) && max.is_none_or(|max| count <= max)
+}
+
+impl EntitySchema {
+    /// Builder method to constrain the values of a field.
+    /// Has no effect if the field is not in the schema.
+    pub fn with_constraints(mut self, id: &FieldId, constraints: FieldConstraints) -> Self {
+        if let Some(field_schema) = self.fields.get_mut(id) {
+            field_schema.constraints = constraints;
+        }
+        self
+    }
+
+    /// Validates that fields marked as unique have distinct values across entities of the schema's type.
+    /// Entities of other types are ignored, as are unique constraints which only warn.
+    pub fn validate_uniqueness<'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a Entity>,
+    ) -> ValidationResult {
+        let errors = self.find_duplicates(entities, RuleSeverity::Error);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Finds duplicate values of unique fields whose constraints only warn, like those of built-in schemas.
+    pub fn find_uniqueness_warnings<'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a Entity>,
+    ) -> Vec<ValidationError> {
+        self.find_duplicates(entities, RuleSeverity::Warning)
+    }
+
+    /// Finds duplicate values of the unique fields whose constraints have the given severity.
+    fn find_duplicates<'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a Entity>,
+        severity: RuleSeverity,
+    ) -> Vec<ValidationError> {
+        let unique_fields: Vec<&FieldId> = self
+            .ordered_fields()
+            .into_iter()
+            .filter(|(_, field_schema)| {
+                field_schema.constraints.unique && field_schema.constraints.severity == severity
+            })
+            .map(|(field_id, _)| field_id)
+            .collect();
+
+        if unique_fields.is_empty() {
+            return Vec::new();
+        }
+
+        let mut seen: HashMap<(&FieldId, String), &EntityId> = HashMap::new();
+        let mut errors = Vec::new();
+
+        for entity in entities {
+            if entity.entity_type != self.entity_type {
+                continue;
+            }
+
+            for field_id in &unique_fields {
+                let Some(value) = entity.get_field(field_id) else {
+                    continue;
+                };
+
+                match seen.get(&(*field_id, value.to_string())) {
+                    Some(first) => errors.push(ValidationError::duplicate_value(
+                        &entity.id, field_id, first,
+                    )),
+                    None => {
+                        seen.insert((*field_id, value.to_string()), &entity.id);
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::ValidationErrorType;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_check_definition_rejects_mismatched_type() {
+        let constraints = FieldConstraints {
+            min: Some(0.0),
+            ..Default::default()
+        };
+
+        assert!(constraints.check_definition(&FieldType::Integer).is_ok());
+        assert!(constraints.check_definition(&FieldType::String).is_err());
+    }
+
+    #[test]
+    fn test_check_definition_rejects_invalid_pattern() {
+        let constraints = FieldConstraints {
+            pattern: Some("[unclosed".to_string()),
+            ..Default::default()
+        };
+
+        assert!(constraints.check_definition(&FieldType::String).is_err());
+    }
+
+    #[test]
+    fn test_compile_pattern_reuses_compiled_regex() {
+        let first = compile_pattern(r"^[A-Z]{2}-[0-9]+$").unwrap();
+        let second = compile_pattern(r"^[A-Z]{2}-[0-9]+$").unwrap();
+
+        assert_eq!(first.as_str(), second.as_str());
+        assert!(second.is_match("AB-12"));
+        assert!(compile_pattern("[unclosed").is_err());
+        assert!(PATTERNS.lock().unwrap().contains_key("[unclosed"));
+    }
+
+    #[test]
+    fn test_check_range() {
+        let constraints = FieldConstraints {
+            min: Some(0.0),
+            max: Some(100.0),
+            ..Default::default()
+        };
+        let entity_id = EntityId::new("opportunity.deal");
+        let field_id = FieldId::new("probability");
+
+        assert!(
+            constraints
+                .check(&entity_id, &field_id, &FieldValue::Integer(50))
+                .is_empty()
+        );
+
+        let errors = constraints.check(&entity_id, &field_id, &FieldValue::Integer(250));
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::ValueOutOfRange { actual, .. } if *actual == 250.0
+        );
+    }
+
+    #[test]
+    fn test_check_pattern_and_length() {
+        let constraints = FieldConstraints {
+            pattern: Some(r"^[^@\s]+@[^@\s]+$".to_string()),
+            max_length: Some(10),
+            ..Default::default()
+        };
+        let entity_id = EntityId::new("person.john");
+        let field_id = FieldId::new("email");
+
+        let errors = constraints.check(
+            &entity_id,
+            &field_id,
+            &FieldValue::String("not an email address".to_string()),
+        );
+
+        assert_eq!(errors.len(), 2);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::PatternMismatch { .. }
+        );
+        assert_matches!(
+            &errors[1].error_type,
+            ValidationErrorType::LengthOutOfRange { actual, .. } if *actual == 20
+        );
+    }
+
+    #[test]
+    fn test_check_item_count() {
+        let constraints = FieldConstraints {
+            min_items: Some(1),
+            ..Default::default()
+        };
+
+        let errors = constraints.check(
+            &EntityId::new("review.weekly"),
+            &FieldId::new("attendee_refs"),
+            &FieldValue::List(vec![]),
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::ItemCountOutOfRange { actual: 0, .. }
+        );
+    }
+
+    #[test]
+    fn test_check_reference_target() {
+        let constraints = FieldConstraints {
+            references: Some(EntityType::new("person")),
+            ..Default::default()
+        };
+        assert!(constraints.check_definition(&FieldType::Reference).is_ok());
+        assert!(constraints.check_definition(&FieldType::String).is_err());
+
+        let entity_id = EntityId::new("task.write_docs");
+        let field_id = FieldId::new("assignee_ref");
+        let person = FieldValue::Reference(ReferenceValue::Entity(EntityId::new("person.john")));
+        let organization =
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::new("organization.acme")));
+
+        assert!(constraints.check(&entity_id, &field_id, &person).is_empty());
+
+        let errors = constraints.check(
+            &entity_id,
+            &field_id,
+            &FieldValue::List(vec![person, organization]),
+        );
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::MismatchedReferenceType { actual, .. }
+                if actual == &EntityId::new("organization.acme")
+        );
+    }
+
+    #[test]
+    fn test_check_quantity_dimension() {
+        use crate::quantity::Quantity;
+
+        let constraints = FieldConstraints {
+            dimension: Some(Dimension::Time),
+            ..Default::default()
+        };
+        assert!(constraints.check_definition(&FieldType::Quantity).is_ok());
+        assert!(constraints.check_definition(&FieldType::Float).is_err());
+
+        let entity_id = EntityId::new("project.migration");
+        let field_id = FieldId::new("estimate");
+        let hours = FieldValue::Quantity(Quantity::new(120.0, "h"));
+        assert!(constraints.check(&entity_id, &field_id, &hours).is_empty());
+
+        let storage = FieldValue::Quantity(Quantity::new(5.0, "TB"));
+        let errors = constraints.check(&entity_id, &field_id, &storage);
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::MismatchedUnit { expected: Dimension::Time, actual } if actual == "TB"
+        );
+    }
+
+    #[test]
+    fn test_validate_uniqueness() {
+        let schema = EntitySchema::new(EntityType::new("organization"))
+            .with_optional_field(FieldId::new("vat_id"), FieldType::String)
+            .with_constraints(
+                &FieldId::new("vat_id"),
+                FieldConstraints {
+                    unique: true,
+                    ..Default::default()
+                },
+            );
+
+        let first = Entity::new(
+            EntityId::new("organization.acme"),
+            EntityType::new("organization"),
+        )
+        .with_field(FieldId::new("vat_id"), "US123");
+        let second = Entity::new(
+            EntityId::new("organization.acme_inc"),
+            EntityType::new("organization"),
+        )
+        .with_field(FieldId::new("vat_id"), "US123");
+        let third = Entity::new(
+            EntityId::new("organization.globex"),
+            EntityType::new("organization"),
+        )
+        .with_field(FieldId::new("vat_id"), "US456");
+
+        let errors = schema
+            .validate_uniqueness([&first, &second, &third])
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].entity_id,
+            Some(EntityId::new("organization.acme_inc"))
+        );
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::DuplicateValue { duplicate_of } if duplicate_of == &EntityId::new("organization.acme")
+        );
+    }
+
+    #[test]
+    fn test_uniqueness_warnings() {
+        let schema = EntitySchema::new(EntityType::new("organization"))
+            .with_optional_field(FieldId::new("vat_id"), FieldType::String)
+            .with_constraints(
+                &FieldId::new("vat_id"),
+                FieldConstraints {
+                    unique: true,
+                    ..Default::default()
+                }
+                .with_severity(RuleSeverity::Warning),
+            );
+
+        let first = Entity::new(
+            EntityId::new("organization.acme"),
+            EntityType::new("organization"),
+        )
+        .with_field(FieldId::new("vat_id"), "US123");
+        let second = Entity::new(
+            EntityId::new("organization.acme_inc"),
+            EntityType::new("organization"),
+        )
+        .with_field(FieldId::new("vat_id"), "US123");
+
+        assert!(schema.validate_uniqueness([&first, &second]).is_ok());
+        assert_eq!(schema.find_uniqueness_warnings([&first, &second]).len(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::EntitySchema;
+use crate::{Entity, FieldId, FieldType, FieldValue};
+
+/// Defines how a field is populated when an entity does not set it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FieldDefault {
+    /// A fixed value.
+    Value(FieldValue),
+    /// The current date and time.
+    Now,
+    /// The current date.
+    Today,
+}
+
+impl FieldDefault {
+    /// Produces the value to populate the field with.
+    pub fn resolve(&self) -> FieldValue {
+        match self {
+            FieldDefault::Value(value) => value.clone(),
+            FieldDefault::Now => FieldValue::DateTime(Local::now().fixed_offset()),
+            FieldDefault::Today => FieldValue::Date(Local::now().date_naive()),
+        }
+    }
+
+    /// Gets the type of value produced by the default.
+    pub fn get_type(&self) -> FieldType {
+        match self {
+            FieldDefault::Value(value) => value.get_type(),
+            FieldDefault::Now => FieldType::DateTime,
+            FieldDefault::Today => FieldType::Date,
+        }
+    }
+}
+
+impl EntitySchema {
+    /// Builder method to set a default for a field, which is applied when the workspace is built.
+    /// Has no effect if the field is not in the schema.
+    pub fn with_default(mut self, id: &FieldId, default: FieldDefault) -> Self {
+        if let Some(field_schema) = self.fields.get_mut(id) {
+            field_schema.default = Some(default);
+            field_schema.materialize_default = false;
+        }
+        self
+    }
+
+    /// Builder method to set a default for a field, which is written to the DSL when an entity is created.
+    /// Has no effect if the field is not in the schema.
+    pub fn with_materialized_default(mut self, id: &FieldId, default: FieldDefault) -> Self {
+        if let Some(field_schema) = self.fields.get_mut(id) {
+            field_schema.default = Some(default);
+            field_schema.materialize_default = true;
+        }
+        self
+    }
+
+    /// Populates fields the entity does not set with their defaults, if they are applied when building.
+    /// Returns the IDs of the fields that were populated.
+    pub fn apply_defaults(&self, entity: &mut Entity) -> Vec<FieldId> {
+        let mut applied = Vec::new();
+
+        for (field_id, field_schema) in self.ordered_fields() {
+            if field_schema.materialize_default || entity.get_field(field_id).is_some() {
+                continue;
+            }
+
+            if let Some(default) = &field_schema.default {
+                entity.fields.push((field_id.clone(), default.resolve()));
+                applied.push(field_id.clone());
+            }
+        }
+
+        applied
+    }
+
+    /// Gets the values of defaults that should be written to the DSL when an entity is created.
+    pub fn materialized_defaults(&self) -> Vec<(FieldId, FieldValue)> {
+        self.ordered_fields()
+            .into_iter()
+            .filter(|(_, field_schema)| field_schema.materialize_default)
+            .filter_map(|(field_id, field_schema)| {
+                field_schema
+                    .default
+                    .as_ref()
+                    .map(|default| (field_id.clone(), default.resolve()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityId, EntityType};
+
+    fn task_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("task"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("is_completed"), FieldType::Boolean)
+            .with_optional_field(FieldId::new("created_at"), FieldType::DateTime)
+            .with_default(
+                &FieldId::new("is_completed"),
+                FieldDefault::Value(FieldValue::Boolean(false)),
+            )
+            .with_materialized_default(&FieldId::new("created_at"), FieldDefault::Now)
+    }
+
+    #[test]
+    fn test_apply_defaults_populates_missing_fields() {
+        let schema = task_schema();
+        let mut entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("name"), "Write docs");
+
+        let applied = schema.apply_defaults(&mut entity);
+
+        assert_eq!(applied, vec![FieldId::new("is_completed")]);
+        assert_eq!(
+            entity.get_field(&FieldId::new("is_completed")),
+            Some(&FieldValue::Boolean(false))
+        );
+        assert_eq!(entity.get_field(&FieldId::new("created_at")), None);
+    }
+
+    #[test]
+    fn test_apply_defaults_keeps_existing_values() {
+        let schema = task_schema();
+        let mut entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("name"), "Write docs")
+            .with_field(FieldId::new("is_completed"), true);
+
+        let applied = schema.apply_defaults(&mut entity);
+
+        assert!(applied.is_empty());
+        assert_eq!(
+            entity.get_field(&FieldId::new("is_completed")),
+            Some(&FieldValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn test_materialized_defaults() {
+        let schema = task_schema();
+
+        let materialized = schema.materialized_defaults();
+
+        assert_eq!(materialized.len(), 1);
+        assert_eq!(materialized[0].0, FieldId::new("created_at"));
+        assert!(materialized[0].1.is_type(&FieldType::DateTime));
+    }
+}
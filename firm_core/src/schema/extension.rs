@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use super::{EntitySchema, FieldSchema, ordered_fields};
+use crate::{FieldId, FieldType};
+
+/// A field that is defined with different types by two schemas.
+///
+/// Conflicts inside record fields are reported with the path to the nested field, like `address.city`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub field: FieldId,
+    pub existing: FieldType,
+    pub incoming: FieldType,
+}
+
+impl EntitySchema {
+    /// Extends the schema with the fields of another schema.
+    ///
+    /// New fields are appended after the existing ones, keeping the order they had in the extension.
+    /// Fields that already exist keep their position and type, but take the mode and any default, constraints or states of the extension.
+    /// Record fields are merged the same way, so an extension can add or adjust the fields nested inside them.
+    /// The extension's rules are added, and its policy for unknown fields takes precedence, if set.
+    pub fn extend(mut self, extension: &EntitySchema) -> Result<Self, Vec<FieldConflict>> {
+        let mut conflicts = Vec::new();
+
+        extend_fields(&mut self.fields, &extension.fields, None, &mut conflicts);
+
+        for rule in &extension.rules {
+            if !self.rules.contains(rule) {
+                self.rules.push(rule.clone());
+            }
+        }
+
+        if extension.unknown_fields.is_some() {
+            self.unknown_fields = extension.unknown_fields;
+        }
+
+        if conflicts.is_empty() {
+            Ok(self)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+/// Merges the incoming fields into the existing ones, recursing into record fields.
+fn extend_fields(
+    fields: &mut HashMap<FieldId, FieldSchema>,
+    incoming: &HashMap<FieldId, FieldSchema>,
+    parent: Option<&FieldId>,
+    conflicts: &mut Vec<FieldConflict>,
+) {
+    for (field_id, field_schema) in ordered_fields(incoming) {
+        let path = match parent {
+            Some(parent) => FieldId::from(format!("{}.{}", parent, field_id)),
+            None => field_id.clone(),
+        };
+
+        match fields.get_mut(field_id) {
+            Some(existing) if existing.field_type != field_schema.field_type => {
+                conflicts.push(FieldConflict {
+                    field: path,
+                    existing: existing.field_type.clone(),
+                    incoming: field_schema.field_type.clone(),
+                });
+            }
+            Some(existing) => {
+                existing.field_mode = field_schema.field_mode.clone();
+
+                if !field_schema.constraints.is_empty() {
+                    existing.constraints = field_schema.constraints.clone();
+                }
+
+                if field_schema.states.is_some() {
+                    existing.states = field_schema.states.clone();
+                }
+
+                if field_schema.default.is_some() {
+                    existing.default = field_schema.default.clone();
+                    existing.materialize_default = field_schema.materialize_default;
+                }
+
+                if let Some(incoming_record) = &field_schema.record_fields {
+                    let existing_record = existing.record_fields.get_or_insert_with(HashMap::new);
+                    extend_fields(existing_record, incoming_record, Some(&path), conflicts);
+                }
+            }
+            None => {
+                let order = fields.len();
+                fields.insert(
+                    field_id.clone(),
+                    FieldSchema {
+                        order,
+                        ..field_schema.clone()
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityType;
+    use crate::schema::FieldMode;
+
+    #[test]
+    fn test_extend_appends_new_fields_in_order() {
+        let base = EntitySchema::new(EntityType::new("person"))
+            .with_required_field(FieldId::new("name"), FieldType::String);
+        let extension = EntitySchema::new(EntityType::new("person"))
+            .with_optional_field(FieldId::new("linkedin"), FieldType::String)
+            .with_optional_field(FieldId::new("github"), FieldType::String);
+
+        let extended = base.extend(&extension).unwrap();
+        let ordered: Vec<&str> = extended
+            .ordered_fields()
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect();
+
+        assert_eq!(ordered, vec!["name", "linkedin", "github"]);
+    }
+
+    #[test]
+    fn test_extend_overrides_field_mode() {
+        let base = EntitySchema::new(EntityType::new("person"))
+            .with_optional_field(FieldId::new("email"), FieldType::String);
+        let extension = EntitySchema::new(EntityType::new("person"))
+            .with_required_field(FieldId::new("email"), FieldType::String);
+
+        let extended = base.extend(&extension).unwrap();
+
+        assert_eq!(
+            extended.fields[&FieldId::new("email")].field_mode,
+            FieldMode::Required
+        );
+    }
+
+    #[test]
+    fn test_extend_reports_conflicting_types() {
+        let base = EntitySchema::new(EntityType::new("person"))
+            .with_required_field(FieldId::new("name"), FieldType::String);
+        let extension = EntitySchema::new(EntityType::new("person"))
+            .with_required_field(FieldId::new("name"), FieldType::Integer);
+
+        let conflicts = base.extend(&extension).unwrap_err();
+
+        assert_eq!(
+            conflicts,
+            vec![FieldConflict {
+                field: FieldId::new("name"),
+                existing: FieldType::String,
+                incoming: FieldType::Integer,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extend_merges_record_fields() {
+        let base = EntitySchema::new(EntityType::new("organization")).with_raw_field(
+            FieldId::new("address"),
+            FieldSchema::new(FieldType::Record, FieldMode::Optional, 0).with_record_fields([
+                (
+                    FieldId::new("street"),
+                    FieldType::String,
+                    FieldMode::Optional,
+                ),
+                (FieldId::new("city"), FieldType::String, FieldMode::Optional),
+            ]),
+        );
+        let extension = EntitySchema::new(EntityType::new("organization")).with_raw_field(
+            FieldId::new("address"),
+            FieldSchema::new(FieldType::Record, FieldMode::Optional, 0).with_record_fields([
+                (FieldId::new("city"), FieldType::String, FieldMode::Required),
+                (
+                    FieldId::new("postcode"),
+                    FieldType::String,
+                    FieldMode::Optional,
+                ),
+            ]),
+        );
+
+        let extended = base.extend(&extension).unwrap();
+        let record_fields = extended.fields[&FieldId::new("address")]
+            .record_fields
+            .as_ref()
+            .unwrap();
+        let ordered: Vec<&str> = ordered_fields(record_fields)
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect();
+
+        assert_eq!(ordered, vec!["street", "city", "postcode"]);
+        assert_eq!(
+            record_fields[&FieldId::new("city")].field_mode,
+            FieldMode::Required
+        );
+    }
+
+    #[test]
+    fn test_extend_reports_conflicting_record_field_types() {
+        let base = EntitySchema::new(EntityType::new("organization")).with_raw_field(
+            FieldId::new("address"),
+            FieldSchema::new(FieldType::Record, FieldMode::Optional, 0).with_record_fields([(
+                FieldId::new("postcode"),
+                FieldType::String,
+                FieldMode::Optional,
+            )]),
+        );
+        let extension = EntitySchema::new(EntityType::new("organization")).with_raw_field(
+            FieldId::new("address"),
+            FieldSchema::new(FieldType::Record, FieldMode::Optional, 0).with_record_fields([(
+                FieldId::new("postcode"),
+                FieldType::Integer,
+                FieldMode::Optional,
+            )]),
+        );
+
+        let conflicts = base.extend(&extension).unwrap_err();
+
+        assert_eq!(
+            conflicts,
+            vec![FieldConflict {
+                field: FieldId::from("address.postcode"),
+                existing: FieldType::String,
+                incoming: FieldType::Integer,
+            }]
+        );
+    }
+}
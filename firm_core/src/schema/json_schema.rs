@@ -0,0 +1,466 @@
+use serde_json::{Map, Value, json};
+
+use std::collections::HashMap;
+
+use super::{EntitySchema, FieldDefault, FieldSchema, UnknownFieldPolicy, ordered_fields};
+use crate::{FieldId, FieldType};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+const QUANTITY_NUMBER_PATTERN: &str = "-?[0-9]+(\\.[0-9]+)?";
+const QUANTITY_PATTERN: &str = "^-?[0-9]+(\\.[0-9]+)? [A-Za-z_]+$";
+
+impl EntitySchema {
+    /// Describes the JSON shape of entities of this type as a JSON Schema (draft 2020-12) document.
+    ///
+    /// Entities serialize with their fields as `[field_id, value]` pairs, where each value is
+    /// tagged with its type, like `{"Integer": 42}`. The document requires the schema's required fields,
+    /// checks the type of each declared field and includes its constraints, states and default.
+    /// Constraints JSON Schema can't express, like bounds on currency amounts or uniqueness
+    /// across entities, are added to the field's description instead.
+    pub fn to_json_schema(&self) -> Value {
+        let ordered_fields = self.ordered_fields();
+        let field_names: Vec<&str> = ordered_fields.iter().map(|(id, _)| id.as_str()).collect();
+
+        let mut field_entries: Vec<Value> = ordered_fields
+            .iter()
+            .map(|(field_id, field_schema)| {
+                json!({
+                    "type": "array",
+                    "prefixItems": [
+                        { "const": field_id.as_str() },
+                        field_value_schema(field_schema),
+                    ],
+                    "minItems": 2,
+                    "maxItems": 2,
+                })
+            })
+            .collect();
+
+        // Undeclared fields can have any value, unless the schema rejects them
+        if self.unknown_field_policy() != UnknownFieldPolicy::Error {
+            field_entries.push(json!({
+                "type": "array",
+                "prefixItems": [
+                    { "type": "string", "not": { "enum": field_names } },
+                    { "$ref": "#/$defs/field_value" },
+                ],
+                "minItems": 2,
+                "maxItems": 2,
+            }));
+        }
+
+        let required_fields: Vec<Value> = ordered_fields
+            .iter()
+            .filter(|(_, field_schema)| field_schema.is_required())
+            .map(|(field_id, _)| {
+                json!({ "contains": { "prefixItems": [{ "const": field_id.as_str() }] } })
+            })
+            .collect();
+
+        let mut fields = json!({
+            "type": "array",
+            "items": { "anyOf": field_entries },
+        });
+        if !required_fields.is_empty() {
+            fields["allOf"] = Value::Array(required_fields);
+        }
+
+        json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": self.entity_type.as_str(),
+            "description": format!("A Firm entity of type '{}'", self.entity_type),
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "pattern": format!("^{}\\.", regex::escape(self.entity_type.as_str())),
+                },
+                "entity_type": { "const": self.entity_type.as_str() },
+                "fields": fields,
+            },
+            "required": ["id", "entity_type", "fields"],
+            "additionalProperties": false,
+            "$defs": {
+                "field_value": {
+                    "anyOf": [
+                        tagged_value(&FieldType::Boolean, value_schema(&FieldType::Boolean)),
+                        tagged_value(&FieldType::String, value_schema(&FieldType::String)),
+                        tagged_value(&FieldType::Integer, value_schema(&FieldType::Integer)),
+                        tagged_value(&FieldType::Float, value_schema(&FieldType::Float)),
+                        tagged_value(&FieldType::Currency, value_schema(&FieldType::Currency)),
+                        tagged_value(&FieldType::Reference, value_schema(&FieldType::Reference)),
+                        tagged_value(&FieldType::List, value_schema(&FieldType::List)),
+                        tagged_value(&FieldType::DateTime, value_schema(&FieldType::DateTime)),
+                        tagged_value(&FieldType::Date, value_schema(&FieldType::Date)),
+                        tagged_value(&FieldType::Duration, value_schema(&FieldType::Duration)),
+                        tagged_value(&FieldType::Recurrence, value_schema(&FieldType::Recurrence)),
+                        tagged_value(&FieldType::Quantity, value_schema(&FieldType::Quantity)),
+                        tagged_value(&FieldType::Record, value_schema(&FieldType::Record)),
+                        tagged_value(&FieldType::Path, value_schema(&FieldType::Path)),
+                    ],
+                },
+            },
+        })
+    }
+}
+
+/// Gets the JSON Schema of a declared field's value, including its constraints, states and default.
+fn field_value_schema(field_schema: &FieldSchema) -> Value {
+    let field_type = &field_schema.field_type;
+    let constraints = &field_schema.constraints;
+    let mut schema = value_schema(field_type);
+    let mut notes = Vec::new();
+
+    match field_type {
+        FieldType::Integer | FieldType::Float => {
+            if let Some(min) = constraints.min {
+                schema["minimum"] = json!(min);
+            }
+            if let Some(max) = constraints.max {
+                schema["maximum"] = json!(max);
+            }
+        }
+        FieldType::Currency => {
+            // Amounts are encoded as strings, so their bounds can only be described
+            if let Some(min) = constraints.min {
+                notes.push(format!("The amount must be at least {}.", min));
+            }
+            if let Some(max) = constraints.max {
+                notes.push(format!("The amount must be at most {}.", max));
+            }
+        }
+        FieldType::String => {
+            if let Some(pattern) = &constraints.pattern {
+                schema["pattern"] = json!(pattern);
+            }
+            if let Some(min_length) = constraints.min_length {
+                schema["minLength"] = json!(min_length);
+            }
+            if let Some(max_length) = constraints.max_length {
+                schema["maxLength"] = json!(max_length);
+            }
+            if let Some(states) = &field_schema.states {
+                schema["enum"] = json!(states.states);
+            }
+        }
+        FieldType::Quantity => {
+            if let Some(dimension) = &constraints.dimension {
+                let units: Vec<String> = dimension
+                    .units()
+                    .iter()
+                    .map(|unit| regex::escape(unit))
+                    .collect();
+                schema["pattern"] = json!(format!(
+                    "^{} ({})$",
+                    QUANTITY_NUMBER_PATTERN,
+                    units.join("|")
+                ));
+            }
+        }
+        FieldType::Reference => {
+            if let Some(target_type) = &constraints.references {
+                let pattern = format!("^{}\\.", regex::escape(target_type.as_str()));
+                schema["properties"]["Entity"]["pattern"] = json!(pattern);
+                schema["properties"]["Field"]["prefixItems"][0]["pattern"] = json!(pattern);
+            }
+        }
+        FieldType::List => {
+            if let Some(target_type) = &constraints.references {
+                notes.push(format!(
+                    "References must point to '{}' entities.",
+                    target_type
+                ));
+            }
+            if let Some(min_items) = constraints.min_items {
+                schema["minItems"] = json!(min_items);
+            }
+            if let Some(max_items) = constraints.max_items {
+                schema["maxItems"] = json!(max_items);
+            }
+        }
+        _ => {}
+    }
+
+    // Records, and lists of records, describe the fields nested inside them
+    if let Some(record_fields) = &field_schema.record_fields {
+        let record = record_schema(record_fields);
+        match field_type {
+            FieldType::Record => schema = record,
+            FieldType::List => schema["items"] = tagged_value(&FieldType::Record, record),
+            _ => {}
+        }
+    }
+
+    if constraints.unique {
+        notes.push("The value must be unique across entities of this type.".to_string());
+    }
+
+    let mut tagged = tagged_value(field_type, schema);
+    if !notes.is_empty() {
+        tagged["description"] = json!(notes.join(" "));
+    }
+
+    // Defaults are filled in when the workspace is built, so they show up in entity JSON
+    if let Some(FieldDefault::Value(value)) = &field_schema.default {
+        if let Ok(encoded) = serde_json::to_value(value) {
+            tagged["default"] = encoded;
+        }
+    }
+
+    tagged
+}
+
+/// Gets the JSON Schema of a record with declared fields, which are encoded as a map.
+fn record_schema(record_fields: &HashMap<FieldId, FieldSchema>) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (field_id, field_schema) in ordered_fields(record_fields) {
+        properties.insert(field_id.to_string(), field_value_schema(field_schema));
+        if field_schema.is_required() {
+            required.push(field_id.as_str());
+        }
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": { "$ref": "#/$defs/field_value" },
+    })
+}
+
+/// Gets the JSON Schema of the untagged encoding of a field type.
+fn value_schema(field_type: &FieldType) -> Value {
+    match field_type {
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::String | FieldType::Path => json!({ "type": "string" }),
+        FieldType::Integer => json!({ "type": "integer" }),
+        FieldType::Float => json!({ "type": "number" }),
+        FieldType::Currency => json!({
+            "type": "object",
+            "properties": {
+                "amount": { "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$" },
+                "currency": { "type": "string", "pattern": "^[A-Z]{3}$" },
+            },
+            "required": ["amount", "currency"],
+            "additionalProperties": false,
+        }),
+        FieldType::Reference => json!({
+            "type": "object",
+            "properties": {
+                "Entity": { "type": "string" },
+                "Field": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "string" }, { "type": "string" }],
+                    "minItems": 2,
+                    "maxItems": 2,
+                },
+            },
+            "minProperties": 1,
+            "maxProperties": 1,
+            "additionalProperties": false,
+        }),
+        FieldType::List => json!({
+            "type": "array",
+            "items": { "$ref": "#/$defs/field_value" },
+        }),
+        FieldType::DateTime => json!({ "type": "string", "format": "date-time" }),
+        FieldType::Date => json!({ "type": "string", "format": "date" }),
+        FieldType::Duration => json!({
+            "type": "string",
+            "pattern": "^([0-9]+w)?([0-9]+d)?([0-9]+h)?([0-9]+m)?$",
+            "minLength": 2,
+        }),
+        FieldType::Recurrence => json!({
+            "type": "string",
+            "pattern": "^every ([1-9][0-9]* )?(day|week|month|year)s?$",
+        }),
+        FieldType::Quantity => json!({
+            "type": "string",
+            "pattern": QUANTITY_PATTERN,
+        }),
+        FieldType::Record => json!({
+            "type": "object",
+            "additionalProperties": { "$ref": "#/$defs/field_value" },
+        }),
+    }
+}
+
+/// Wraps the schema of a value in the tag of its type, matching how field values are serialized.
+fn tagged_value(field_type: &FieldType, schema: Value) -> Value {
+    let tag = format!("{:?}", field_type);
+
+    let mut properties = Map::new();
+    properties.insert(tag.clone(), schema);
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": [tag],
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantity::Dimension;
+    use crate::schema::{FieldConstraints, FieldMode, FieldStates};
+    use crate::{Entity, EntityId, EntityType, FieldId, FieldValue};
+
+    fn deal_schema() -> EntitySchema {
+        let mut schema = EntitySchema::new(EntityType::new("deal"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("probability"), FieldType::Integer)
+            .with_optional_field(FieldId::new("status"), FieldType::String)
+            .with_default(
+                &FieldId::new("status"),
+                FieldDefault::Value(FieldValue::String(String::from("open"))),
+            )
+            .with_states(
+                &FieldId::new("status"),
+                FieldStates::new(["open", "won", "lost"]),
+            );
+
+        schema
+            .fields
+            .get_mut(&FieldId::new("probability"))
+            .unwrap()
+            .constraints = FieldConstraints {
+            min: Some(0.0),
+            max: Some(100.0),
+            unique: true,
+            ..FieldConstraints::default()
+        };
+
+        schema
+    }
+
+    #[test]
+    fn test_json_schema_document() {
+        let document = deal_schema().to_json_schema();
+
+        assert_eq!(document["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(document["properties"]["entity_type"]["const"], "deal");
+        assert_eq!(
+            document["properties"]["fields"]["allOf"],
+            json!([{ "contains": { "prefixItems": [{ "const": "name" }] } }])
+        );
+    }
+
+    #[test]
+    fn test_json_schema_field_constraints() {
+        let document = deal_schema().to_json_schema();
+        let entries = &document["properties"]["fields"]["items"]["anyOf"];
+
+        let probability = &entries[1]["prefixItems"][1];
+        assert_eq!(probability["properties"]["Integer"]["minimum"], json!(0.0));
+        assert_eq!(
+            probability["properties"]["Integer"]["maximum"],
+            json!(100.0)
+        );
+        assert!(
+            probability["description"]
+                .as_str()
+                .unwrap()
+                .contains("unique")
+        );
+
+        let status = &entries[2]["prefixItems"][1];
+        assert_eq!(
+            status["properties"]["String"]["enum"],
+            json!(["open", "won", "lost"])
+        );
+        assert_eq!(status["default"], json!({ "String": "open" }));
+    }
+
+    #[test]
+    fn test_json_schema_matches_entity_encoding() {
+        let entity = Entity::new(EntityId::new("deal.big"), EntityType::new("deal"))
+            .with_field(FieldId::new("name"), "Big deal")
+            .with_field(FieldId::new("probability"), 50i64);
+        let encoded = serde_json::to_value(&entity).unwrap();
+
+        let document = deal_schema().to_json_schema();
+        let properties = document["properties"].as_object().unwrap();
+        for key in encoded.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key));
+        }
+
+        // Each field is a pair of its name and a value tagged with its type
+        assert_eq!(
+            encoded["fields"][1],
+            json!(["probability", { "Integer": 50 }])
+        );
+    }
+
+    #[test]
+    fn test_json_schema_rejects_unknown_fields() {
+        let lenient = deal_schema().to_json_schema();
+        let strict = deal_schema()
+            .with_unknown_fields(UnknownFieldPolicy::Error)
+            .to_json_schema();
+
+        let entries = |document: &Value| {
+            document["properties"]["fields"]["items"]["anyOf"]
+                .as_array()
+                .unwrap()
+                .len()
+        };
+        assert_eq!(entries(&lenient), 4);
+        assert_eq!(entries(&strict), 3);
+    }
+
+    #[test]
+    fn test_json_schema_record_fields() {
+        let document = EntitySchema::new(EntityType::new("organization"))
+            .add_field_schema(
+                FieldId::new("address"),
+                FieldSchema::new(FieldType::Record, FieldMode::Optional, 0).with_record_fields([
+                    (
+                        FieldId::new("street"),
+                        FieldType::String,
+                        FieldMode::Required,
+                    ),
+                    (FieldId::new("city"), FieldType::String, FieldMode::Optional),
+                ]),
+            )
+            .to_json_schema();
+
+        let address = &document["properties"]["fields"]["items"]["anyOf"][0]["prefixItems"][1];
+        let record = &address["properties"]["Record"];
+        assert_eq!(
+            record["properties"]["street"],
+            json!({
+                "type": "object",
+                "properties": { "String": { "type": "string" } },
+                "required": ["String"],
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(record["required"], json!(["street"]));
+    }
+
+    #[test]
+    fn test_json_schema_quantity_dimension() {
+        let document = EntitySchema::new(EntityType::new("plan"))
+            .with_optional_field(FieldId::new("storage"), FieldType::Quantity)
+            .with_constraints(
+                &FieldId::new("storage"),
+                FieldConstraints {
+                    dimension: Some(Dimension::Data),
+                    ..Default::default()
+                },
+            )
+            .to_json_schema();
+
+        let storage = &document["properties"]["fields"]["items"]["anyOf"][0]["prefixItems"][1];
+        let pattern = storage["properties"]["Quantity"]["pattern"]
+            .as_str()
+            .unwrap();
+        let regex = regex::Regex::new(pattern).unwrap();
+        assert!(regex.is_match("5 TB"));
+        assert!(regex.is_match("1.5 GiB"));
+        assert!(!regex.is_match("120 h"));
+    }
+}
@@ -4,9 +4,23 @@ use std::{collections::HashMap, fmt::Display};
 use crate::{EntityType, FieldId, FieldType};
 
 mod builtin;
+mod constraints;
+mod defaults;
+mod extension;
+mod json_schema;
+mod rules;
+mod states;
+mod unknown_fields;
 mod validation;
 mod validation_errors;
 
+pub use builtin::BuiltinGroup;
+pub use constraints::FieldConstraints;
+pub use defaults::FieldDefault;
+pub use extension::FieldConflict;
+pub use rules::{Comparison, RuleCheck, RuleSeverity, SchemaRule};
+pub use states::{FieldStates, TransitionError};
+pub use unknown_fields::UnknownFieldPolicy;
 pub use validation::ValidationResult;
 pub use validation_errors::{ValidationError, ValidationErrorType};
 
@@ -18,11 +32,21 @@ pub enum FieldMode {
 }
 
 /// Defines the schema for an unnamed field which can be either required or optional.
+///
+/// A field can have a default, which is either applied when the workspace is built
+/// or materialized into the DSL when an entity is created.
+/// It can also have constraints on its values beyond the type, or a set of states it moves between.
+/// Record fields, and lists of records, can declare the fields nested inside them.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FieldSchema {
     pub field_type: FieldType,
     pub field_mode: FieldMode,
     pub order: usize,
+    pub default: Option<FieldDefault>,
+    pub materialize_default: bool,
+    pub constraints: FieldConstraints,
+    pub states: Option<FieldStates>,
+    pub record_fields: Option<HashMap<FieldId, FieldSchema>>,
 }
 
 impl FieldSchema {
@@ -31,9 +55,31 @@ impl FieldSchema {
             field_type,
             field_mode,
             order,
+            default: None,
+            materialize_default: false,
+            constraints: FieldConstraints::default(),
+            states: None,
+            record_fields: None,
         }
     }
 
+    /// Builder method to declare the fields nested inside record values, in order.
+    pub fn with_record_fields(
+        mut self,
+        fields: impl IntoIterator<Item = (FieldId, FieldType, FieldMode)>,
+    ) -> Self {
+        let record_fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(order, (id, field_type, field_mode))| {
+                (id, FieldSchema::new(field_type, field_mode, order))
+            })
+            .collect();
+
+        self.record_fields = Some(record_fields);
+        self
+    }
+
     /// Get the expected field type.
     pub fn expected_type(&self) -> &FieldType {
         &self.field_type
@@ -50,6 +96,8 @@ impl FieldSchema {
 pub struct EntitySchema {
     pub entity_type: EntityType,
     pub fields: HashMap<FieldId, FieldSchema>,
+    pub unknown_fields: Option<UnknownFieldPolicy>,
+    pub rules: Vec<SchemaRule>,
     insertion_order: u16,
 }
 
@@ -59,6 +107,8 @@ impl EntitySchema {
         Self {
             entity_type: entity_type,
             fields: HashMap::new(),
+            unknown_fields: None,
+            rules: Vec::new(),
             insertion_order: 0,
         }
     }
@@ -95,7 +145,11 @@ impl EntitySchema {
         )
         .with_raw_field(
             FieldId::new("created_at"),
-            FieldSchema::new(FieldType::DateTime, FieldMode::Optional, 101),
+            FieldSchema {
+                default: Some(FieldDefault::Now),
+                materialize_default: true,
+                ..FieldSchema::new(FieldType::DateTime, FieldMode::Optional, 101)
+            },
         )
         .with_raw_field(
             FieldId::new("updated_at"),
@@ -105,9 +159,7 @@ impl EntitySchema {
 
     /// Get schema fields sorted by their order.
     pub fn ordered_fields(&self) -> Vec<(&FieldId, &FieldSchema)> {
-        let mut ordered: Vec<_> = self.fields.iter().collect();
-        ordered.sort_by_key(|&(_, field_schema)| field_schema.order);
-        ordered
+        ordered_fields(&self.fields)
     }
 
     /// Gets the next order for a field, preserving insertion order.
@@ -124,12 +176,60 @@ impl Display for EntitySchema {
             writeln!(f, "\n{}", field_id)?;
             writeln!(f, "- Type: {}", field_schema.expected_type())?;
             writeln!(f, "- Required: {}", field_schema.is_required())?;
+
+            if let Some(default) = &field_schema.default {
+                match default {
+                    FieldDefault::Value(value) => writeln!(f, "- Default: {}", value)?,
+                    FieldDefault::Now => writeln!(f, "- Default: now")?,
+                    FieldDefault::Today => writeln!(f, "- Default: today")?,
+                }
+            }
+
+            if let Some(states) = &field_schema.states {
+                writeln!(f, "- States: {}", states.states.join(", "))?;
+            }
+
+            if let Some(record_fields) = &field_schema.record_fields {
+                writeln!(f, "- Fields:")?;
+                for (record_field_id, record_field) in ordered_fields(record_fields) {
+                    writeln!(
+                        f,
+                        "  - {}: {}{}",
+                        record_field_id,
+                        record_field.expected_type(),
+                        if record_field.is_required() {
+                            " (required)"
+                        } else {
+                            ""
+                        }
+                    )?;
+                }
+            }
+        }
+
+        if !self.rules.is_empty() {
+            writeln!(f, "\nRules")?;
+            for rule in &self.rules {
+                match rule.severity {
+                    RuleSeverity::Error => writeln!(f, "- {}", rule.describe())?,
+                    RuleSeverity::Warning => writeln!(f, "- {} (warning)", rule.describe())?,
+                }
+            }
         }
 
         Ok(())
     }
 }
 
+/// Sorts fields by their order.
+pub(crate) fn ordered_fields(
+    fields: &HashMap<FieldId, FieldSchema>,
+) -> Vec<(&FieldId, &FieldSchema)> {
+    let mut ordered: Vec<_> = fields.iter().collect();
+    ordered.sort_by_key(|&(_, field_schema)| field_schema.order);
+    ordered
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,448 @@
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt};
+
+use super::{EntitySchema, ValidationError};
+use crate::{Entity, FieldId, FieldValue};
+
+/// Defines how a rule compares the values of two fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    /// Checks whether the ordering between two values satisfies the comparison.
+    pub fn holds_for(&self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::NotEqual => ordering != Ordering::Equal,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::Less => write!(f, "<"),
+            Comparison::LessOrEqual => write!(f, "<="),
+            Comparison::Greater => write!(f, ">"),
+            Comparison::GreaterOrEqual => write!(f, ">="),
+            Comparison::Equal => write!(f, "=="),
+            Comparison::NotEqual => write!(f, "!="),
+        }
+    }
+}
+
+impl std::str::FromStr for Comparison {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "<" => Ok(Comparison::Less),
+            "<=" => Ok(Comparison::LessOrEqual),
+            ">" => Ok(Comparison::Greater),
+            ">=" => Ok(Comparison::GreaterOrEqual),
+            "==" => Ok(Comparison::Equal),
+            "!=" => Ok(Comparison::NotEqual),
+            _ => Err(format!("Unknown comparison: '{}'", s)),
+        }
+    }
+}
+
+/// Defines what a rule checks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RuleCheck {
+    /// The field is required when another field is set, or when it has a given value.
+    RequiredIf {
+        field: FieldId,
+        when: FieldId,
+        equals: Option<FieldValue>,
+    },
+    /// The values of two fields must compare in a given way, when both are set.
+    Compare {
+        field: FieldId,
+        comparison: Comparison,
+        other: FieldId,
+    },
+}
+
+/// Defines whether breaking a rule makes the entity invalid, or is only reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleSeverity {
+    /// Breaking the rule makes the entity invalid.
+    #[default]
+    Error,
+    /// Breaking the rule is reported, but the entity is still valid.
+    Warning,
+}
+
+/// A validation rule which spans several fields of an entity, with an optional custom message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaRule {
+    pub check: RuleCheck,
+    pub message: Option<String>,
+    #[serde(default)]
+    pub severity: RuleSeverity,
+}
+
+impl SchemaRule {
+    /// Creates a rule requiring a field when another field is set, or when it equals a given value.
+    pub fn required_if(field: FieldId, when: FieldId, equals: Option<FieldValue>) -> Self {
+        Self {
+            check: RuleCheck::RequiredIf {
+                field,
+                when,
+                equals,
+            },
+            message: None,
+            severity: RuleSeverity::default(),
+        }
+    }
+
+    /// Creates a rule comparing the values of two fields.
+    pub fn compare(field: FieldId, comparison: Comparison, other: FieldId) -> Self {
+        Self {
+            check: RuleCheck::Compare {
+                field,
+                comparison,
+                other,
+            },
+            message: None,
+            severity: RuleSeverity::default(),
+        }
+    }
+
+    /// Builder method to report violations of the rule with a custom message.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Builder method to set whether breaking the rule makes the entity invalid.
+    pub fn with_severity(mut self, severity: RuleSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Gets the fields involved in the rule.
+    pub fn fields(&self) -> Vec<&FieldId> {
+        match &self.check {
+            RuleCheck::RequiredIf { field, when, .. } => vec![field, when],
+            RuleCheck::Compare { field, other, .. } => vec![field, other],
+        }
+    }
+
+    /// Describes the rule, which is used as the message for violations unless a custom one is set.
+    pub fn describe(&self) -> String {
+        match &self.check {
+            RuleCheck::RequiredIf {
+                field,
+                when,
+                equals: Some(value),
+            } => format!("'{}' is required when '{}' is {}", field, when, value),
+            RuleCheck::RequiredIf {
+                field,
+                when,
+                equals: None,
+            } => format!("'{}' is required when '{}' is set", field, when),
+            RuleCheck::Compare {
+                field,
+                comparison,
+                other,
+            } => format!("'{}' must be {} '{}'", field, comparison, other),
+        }
+    }
+
+    /// Checks the rule against an entity.
+    /// Comparisons only apply when both fields are set and their values can be compared.
+    pub fn check(&self, entity: &Entity) -> Option<ValidationError> {
+        let is_violated = match &self.check {
+            RuleCheck::RequiredIf {
+                field,
+                when,
+                equals,
+            } => {
+                let applies = match (entity.get_field(when), equals) {
+                    (Some(actual), Some(expected)) => actual == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+
+                applies && entity.get_field(field).is_none()
+            }
+            RuleCheck::Compare {
+                field,
+                comparison,
+                other,
+            } => match (entity.get_field(field), entity.get_field(other)) {
+                (Some(left), Some(right)) => compare_values(left, right)
+                    .is_some_and(|ordering| !comparison.holds_for(ordering)),
+                _ => false,
+            },
+        };
+
+        is_violated.then(|| {
+            let message = self.message.clone().unwrap_or_else(|| self.describe());
+            let fields = self.fields().into_iter().cloned().collect();
+            ValidationError::rule_violation(&entity.id, fields, &message)
+        })
+    }
+}
+
+impl EntitySchema {
+    /// Builder method to add a rule spanning several fields.
+    pub fn with_rule(mut self, rule: SchemaRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Checks that all fields involved in the schema rules are declared in the schema.
+    pub fn check_rules(&self) -> Result<(), String> {
+        for rule in &self.rules {
+            if let Some(field) = rule
+                .fields()
+                .into_iter()
+                .find(|f| !self.fields.contains_key(f))
+            {
+                return Err(format!(
+                    "rule \"{}\" refers to field '{}', which is not declared",
+                    rule.describe(),
+                    field
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the rules with warning severity that an entity breaks.
+    pub fn find_rule_warnings(&self, entity: &Entity) -> Vec<ValidationError> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.severity == RuleSeverity::Warning)
+            .filter_map(|rule| rule.check(entity))
+            .collect()
+    }
+}
+
+/// Orders two field values, if they are of comparable types.
+/// Values which can't be ordered are still equal to themselves.
+fn compare_values(left: &FieldValue, right: &FieldValue) -> Option<Ordering> {
+    left.compare_for_matching(right)
+        .ok()
+        .or_else(|| (left == right).then_some(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::ValidationErrorType;
+    use crate::{EntityId, EntityType, FieldType};
+    use assert_matches::assert_matches;
+    use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+    fn date(day: u32) -> FieldValue {
+        FieldValue::DateTime(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(2025, 1, day, 0, 0, 0)
+                .unwrap(),
+        )
+    }
+
+    fn campaign_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("campaign"))
+            .with_optional_field(FieldId::new("status"), FieldType::String)
+            .with_optional_field(FieldId::new("budget"), FieldType::Integer)
+            .with_optional_field(FieldId::new("start_date"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("end_date"), FieldType::DateTime)
+            .with_rule(
+                SchemaRule::required_if(
+                    FieldId::new("budget"),
+                    FieldId::new("status"),
+                    Some(FieldValue::String(String::from("approved"))),
+                )
+                .with_message("Approved campaigns need a budget"),
+            )
+            .with_rule(SchemaRule::compare(
+                FieldId::new("end_date"),
+                Comparison::Greater,
+                FieldId::new("start_date"),
+            ))
+    }
+
+    fn campaign() -> Entity {
+        Entity::new(
+            EntityId::new("campaign.launch"),
+            EntityType::new("campaign"),
+        )
+    }
+
+    #[test]
+    fn test_required_if_with_value() {
+        let schema = campaign_schema();
+
+        let draft = campaign().with_field(FieldId::new("status"), "draft");
+        assert!(schema.validate(&draft).is_ok());
+
+        let approved = campaign().with_field(FieldId::new("status"), "approved");
+        let errors = schema.validate(&approved).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Approved campaigns need a budget");
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::RuleViolation { fields } if fields == &vec![FieldId::new("budget"), FieldId::new("status")]
+        );
+    }
+
+    #[test]
+    fn test_required_if_set() {
+        let schema = EntitySchema::new(EntityType::new("task"))
+            .with_optional_field(FieldId::new("completed_at"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("completed_by_ref"), FieldType::Reference)
+            .with_rule(SchemaRule::required_if(
+                FieldId::new("completed_by_ref"),
+                FieldId::new("completed_at"),
+                None,
+            ));
+
+        let entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("completed_at"), date(1));
+        let errors = schema.validate(&entity).unwrap_err();
+
+        assert_eq!(
+            errors[0].message,
+            "'completed_by_ref' is required when 'completed_at' is set"
+        );
+    }
+
+    #[test]
+    fn test_compare_fields() {
+        let schema = campaign_schema();
+
+        let valid = campaign()
+            .with_field(FieldId::new("start_date"), date(1))
+            .with_field(FieldId::new("end_date"), date(2));
+        assert!(schema.validate(&valid).is_ok());
+
+        let invalid = campaign()
+            .with_field(FieldId::new("start_date"), date(2))
+            .with_field(FieldId::new("end_date"), date(1));
+        let errors = schema.validate(&invalid).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "'end_date' must be > 'start_date'");
+        assert_eq!(errors[0].field, Some(FieldId::new("end_date")));
+    }
+
+    #[test]
+    fn test_compare_date_with_datetime() {
+        let schema = EntitySchema::new(EntityType::new("task"))
+            .with_optional_field(FieldId::new("due_date"), FieldType::Date)
+            .with_optional_field(FieldId::new("completed_at"), FieldType::DateTime)
+            .with_rule(SchemaRule::compare(
+                FieldId::new("completed_at"),
+                Comparison::LessOrEqual,
+                FieldId::new("due_date"),
+            ));
+        let task = |due_day: u32, completed_day: u32| {
+            Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+                .with_field(
+                    FieldId::new("due_date"),
+                    FieldValue::Date(NaiveDate::from_ymd_opt(2025, 1, due_day).unwrap()),
+                )
+                .with_field(FieldId::new("completed_at"), date(completed_day))
+        };
+
+        assert!(schema.validate(&task(2, 1)).is_ok());
+        assert!(schema.validate(&task(2, 2)).is_ok());
+        assert!(schema.validate(&task(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_compare_quantities_in_different_units() {
+        use crate::quantity::Quantity;
+
+        let schema = EntitySchema::new(EntityType::new("plan"))
+            .with_optional_field(FieldId::new("used"), FieldType::Quantity)
+            .with_optional_field(FieldId::new("quota"), FieldType::Quantity)
+            .with_rule(SchemaRule::compare(
+                FieldId::new("used"),
+                Comparison::LessOrEqual,
+                FieldId::new("quota"),
+            ));
+        let plan = |used: Quantity, quota: Quantity| {
+            Entity::new(EntityId::new("plan.pro"), EntityType::new("plan"))
+                .with_field(FieldId::new("used"), FieldValue::Quantity(used))
+                .with_field(FieldId::new("quota"), FieldValue::Quantity(quota))
+        };
+
+        let within = plan(Quantity::new(800.0, "GB"), Quantity::new(1.0, "TB"));
+        assert!(schema.validate(&within).is_ok());
+
+        let over = plan(Quantity::new(1500.0, "GB"), Quantity::new(1.0, "TB"));
+        assert!(schema.validate(&over).is_err());
+    }
+
+    #[test]
+    fn test_compare_skips_unset_fields() {
+        let entity = campaign().with_field(FieldId::new("end_date"), date(1));
+        assert!(campaign_schema().validate(&entity).is_ok());
+    }
+
+    #[test]
+    fn test_warning_rules_do_not_fail_validation() {
+        let schema = EntitySchema::new(EntityType::new("task"))
+            .with_optional_field(FieldId::new("is_completed"), FieldType::Boolean)
+            .with_optional_field(FieldId::new("completed_at"), FieldType::DateTime)
+            .with_rule(
+                SchemaRule::required_if(
+                    FieldId::new("completed_at"),
+                    FieldId::new("is_completed"),
+                    Some(FieldValue::Boolean(true)),
+                )
+                .with_severity(RuleSeverity::Warning),
+            );
+
+        let entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("is_completed"), true);
+
+        assert!(schema.validate(&entity).is_ok());
+
+        let warnings = schema.find_rule_warnings(&entity);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "'completed_at' is required when 'is_completed' is true"
+        );
+        assert!(campaign_schema().find_rule_warnings(&campaign()).is_empty());
+    }
+
+    #[test]
+    fn test_check_rules_undeclared_field() {
+        let schema = campaign_schema().with_rule(SchemaRule::compare(
+            FieldId::new("budget"),
+            Comparison::LessOrEqual,
+            FieldId::new("limit"),
+        ));
+
+        let result = schema.check_rules();
+        assert!(result.unwrap_err().contains("'limit'"));
+    }
+
+    #[test]
+    fn test_comparison_from_str() {
+        assert_eq!(">=".parse::<Comparison>(), Ok(Comparison::GreaterOrEqual));
+        assert!("=>".parse::<Comparison>().is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{EntitySchema, ValidationError};
+use crate::{Entity, EntityId, FieldId, FieldType, FieldValue};
+
+/// The states a field can be in, and the allowed transitions between them.
+///
+/// Without any transitions, the field can move freely between its states.
+/// A stamp field can be set to the current time whenever the field transitions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldStates {
+    pub states: Vec<String>,
+    pub transitions: Vec<(String, String)>,
+    pub stamp: Option<FieldId>,
+}
+
+impl FieldStates {
+    /// Creates a set of states which the field can move freely between.
+    pub fn new(states: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            states: states.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Builder method to allow a transition between two states.
+    pub fn with_transition(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.transitions.push((from.into(), to.into()));
+        self
+    }
+
+    /// Builder method to stamp a datetime field with the current time on each transition.
+    pub fn with_stamp(mut self, field_id: FieldId) -> Self {
+        self.stamp = Some(field_id);
+        self
+    }
+
+    /// Checks whether the given value is one of the states.
+    pub fn contains(&self, state: &str) -> bool {
+        self.states.iter().any(|s| s == state)
+    }
+
+    /// Gets the states which can be reached from a state, or any state if the field is not set yet.
+    pub fn next_states(&self, from: Option<&str>) -> Vec<&str> {
+        match from {
+            Some(from) if !self.transitions.is_empty() => self
+                .transitions
+                .iter()
+                .filter(|(transition_from, _)| transition_from == from)
+                .map(|(_, to)| to.as_str())
+                .collect(),
+            _ => self.states.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// Checks that the states can apply to a field type and that transitions refer to known states.
+    pub fn check_definition(&self, field_type: &FieldType) -> Result<(), String> {
+        if field_type != &FieldType::String {
+            return Err(format!(
+                "states only apply to string fields, not '{}'",
+                field_type
+            ));
+        }
+
+        if self.states.is_empty() {
+            return Err("at least one state must be declared".to_string());
+        }
+
+        for (from, to) in &self.transitions {
+            if let Some(unknown) = [from, to].into_iter().find(|s| !self.contains(s)) {
+                return Err(format!(
+                    "transition '{} -> {}' refers to unknown state '{}'",
+                    from, to, unknown
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a value is one of the states.
+    pub fn check(
+        &self,
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        value: &FieldValue,
+    ) -> Option<ValidationError> {
+        match value {
+            FieldValue::String(state) if !self.contains(state) => Some(
+                ValidationError::invalid_state(entity_id, field_id, state, &self.states),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur when transitioning an entity field to a new state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionError {
+    NoStates(FieldId),
+    UnknownState(String, Vec<String>),
+    IllegalTransition {
+        from: String,
+        to: String,
+        allowed: Vec<String>,
+    },
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransitionError::NoStates(field_id) => {
+                write!(f, "Field '{}' does not declare any states", field_id)
+            }
+            TransitionError::UnknownState(state, states) => write!(
+                f,
+                "Unknown state '{}' (expected one of: {})",
+                state,
+                states.join(", ")
+            ),
+            TransitionError::IllegalTransition { from, to, allowed } if allowed.is_empty() => {
+                write!(
+                    f,
+                    "Can't move from '{}' to '{}' because '{}' is a final state",
+                    from, to, from
+                )
+            }
+            TransitionError::IllegalTransition { from, to, allowed } => write!(
+                f,
+                "Can't move from '{}' to '{}' (allowed: {})",
+                from,
+                to,
+                allowed.join(", ")
+            ),
+        }
+    }
+}
+
+impl EntitySchema {
+    /// Builder method to declare the states of a field.
+    pub fn with_states(mut self, field_id: &FieldId, states: FieldStates) -> Self {
+        if let Some(field) = self.fields.get_mut(field_id) {
+            field.states = Some(states);
+        }
+
+        self
+    }
+
+    /// Checks that the stamp field of each field with states is declared as a datetime field.
+    /// Returns the field with states and what's wrong with its stamp otherwise.
+    pub fn check_states(&self) -> Result<(), (FieldId, String)> {
+        for (field_id, field_schema) in self.ordered_fields() {
+            let Some(stamp) = field_schema
+                .states
+                .as_ref()
+                .and_then(|states| states.stamp.as_ref())
+            else {
+                continue;
+            };
+
+            match self.fields.get(stamp) {
+                None => {
+                    return Err((
+                        field_id.clone(),
+                        format!("stamp field '{}' is not declared", stamp),
+                    ));
+                }
+                Some(stamp_schema) if stamp_schema.expected_type() != &FieldType::DateTime => {
+                    return Err((
+                        field_id.clone(),
+                        format!(
+                            "stamp field '{}' must be a datetime, not '{}'",
+                            stamp,
+                            stamp_schema.expected_type()
+                        ),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the first field, in order, which declares states.
+    pub fn state_field(&self) -> Option<&FieldId> {
+        self.ordered_fields()
+            .into_iter()
+            .find(|(_, field_schema)| field_schema.states.is_some())
+            .map(|(field_id, _)| field_id)
+    }
+
+    /// Moves a field of an entity to a new state, if the transition is allowed.
+    /// Returns the fields that changed, which includes the stamp field if one is declared.
+    pub fn transition(
+        &self,
+        entity: &Entity,
+        field_id: &FieldId,
+        to: &str,
+    ) -> Result<Vec<(FieldId, FieldValue)>, TransitionError> {
+        let states = self
+            .fields
+            .get(field_id)
+            .and_then(|field_schema| field_schema.states.as_ref())
+            .ok_or_else(|| TransitionError::NoStates(field_id.clone()))?;
+
+        if !states.contains(to) {
+            return Err(TransitionError::UnknownState(
+                to.to_string(),
+                states.states.clone(),
+            ));
+        }
+
+        let from = match entity.get_field(field_id) {
+            Some(FieldValue::String(from)) => Some(from.as_str()),
+            _ => None,
+        };
+
+        let allowed = states.next_states(from);
+        if !allowed.contains(&to) {
+            return Err(TransitionError::IllegalTransition {
+                from: from.unwrap_or_default().to_string(),
+                to: to.to_string(),
+                allowed: allowed.into_iter().map(String::from).collect(),
+            });
+        }
+
+        let mut changes = vec![(field_id.clone(), FieldValue::String(to.to_string()))];
+        if let Some(stamp) = &states.stamp {
+            changes.push((
+                stamp.clone(),
+                FieldValue::DateTime(Local::now().fixed_offset()),
+            ));
+        }
+
+        Ok(changes)
+    }
+
+    /// Moves a field of an entity to a new state like [`EntitySchema::transition`].
+    /// Returns a copy of the entity with the changed fields replaced, along with the changes.
+    pub fn transition_entity(
+        &self,
+        entity: &Entity,
+        field_id: &FieldId,
+        to: &str,
+    ) -> Result<(Entity, Vec<(FieldId, FieldValue)>), TransitionError> {
+        let changes = self.transition(entity, field_id, to)?;
+
+        let mut transitioned = entity.clone();
+        for (changed_field, value) in &changes {
+            transitioned
+                .fields
+                .insert(changed_field.clone(), value.clone());
+        }
+
+        Ok((transitioned, changes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityType;
+    use crate::schema::ValidationErrorType;
+    use assert_matches::assert_matches;
+
+    fn deal_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("deal"))
+            .with_required_field(FieldId::new("status"), FieldType::String)
+            .with_optional_field(FieldId::new("status_changed_at"), FieldType::DateTime)
+            .with_states(
+                &FieldId::new("status"),
+                FieldStates::new(["open", "won", "lost"])
+                    .with_transition("open", "won")
+                    .with_transition("open", "lost")
+                    .with_stamp(FieldId::new("status_changed_at")),
+            )
+    }
+
+    fn deal(status: &str) -> Entity {
+        Entity::new(EntityId::new("deal.big"), EntityType::new("deal"))
+            .with_field(FieldId::new("status"), status)
+    }
+
+    #[test]
+    fn test_validate_restricts_values_to_states() {
+        let schema = deal_schema();
+        assert!(schema.validate(&deal("open")).is_ok());
+
+        let errors = schema.validate(&deal("pending")).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::InvalidState { allowed } if allowed.len() == 3
+        );
+    }
+
+    #[test]
+    fn test_transition_allowed_with_stamp() {
+        let changes = deal_schema()
+            .transition(&deal("open"), &FieldId::new("status"), "won")
+            .unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0],
+            (
+                FieldId::new("status"),
+                FieldValue::String(String::from("won"))
+            )
+        );
+        assert_eq!(changes[1].0, FieldId::new("status_changed_at"));
+        assert_matches!(changes[1].1, FieldValue::DateTime(_));
+    }
+
+    #[test]
+    fn test_transition_entity_replaces_fields() {
+        let entity = deal("open").with_field(
+            FieldId::new("status_changed_at"),
+            FieldValue::DateTime(Local::now().fixed_offset() - chrono::Duration::days(7)),
+        );
+
+        let (transitioned, changes) = deal_schema()
+            .transition_entity(&entity, &FieldId::new("status"), "won")
+            .unwrap();
+
+        assert_eq!(transitioned.fields.len(), 2);
+        assert_eq!(
+            transitioned.get_field(&FieldId::new("status")),
+            Some(&FieldValue::from("won"))
+        );
+        assert_eq!(
+            transitioned.get_field(&FieldId::new("status_changed_at")),
+            Some(&changes[1].1)
+        );
+    }
+
+    #[test]
+    fn test_transition_illegal_move() {
+        let result = deal_schema().transition(&deal("won"), &FieldId::new("status"), "open");
+
+        assert_eq!(
+            result,
+            Err(TransitionError::IllegalTransition {
+                from: String::from("won"),
+                to: String::from("open"),
+                allowed: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_transition_unknown_state() {
+        let result = deal_schema().transition(&deal("open"), &FieldId::new("status"), "closed");
+        assert_matches!(result, Err(TransitionError::UnknownState(state, _)) if state == "closed");
+    }
+
+    #[test]
+    fn test_transition_without_states() {
+        let schema = EntitySchema::new(EntityType::new("deal"))
+            .with_required_field(FieldId::new("status"), FieldType::String);
+
+        let result = schema.transition(&deal("open"), &FieldId::new("status"), "won");
+        assert_eq!(
+            result,
+            Err(TransitionError::NoStates(FieldId::new("status")))
+        );
+    }
+
+    #[test]
+    fn test_check_definition() {
+        let states = FieldStates::new(["open"]).with_transition("open", "closed");
+        assert!(states.check_definition(&FieldType::String).is_err());
+        assert!(
+            FieldStates::new(["open"])
+                .check_definition(&FieldType::Integer)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_states_stamp() {
+        assert!(deal_schema().check_states().is_ok());
+
+        let undeclared = EntitySchema::new(EntityType::new("deal"))
+            .with_required_field(FieldId::new("status"), FieldType::String)
+            .with_states(
+                &FieldId::new("status"),
+                FieldStates::new(["open", "won"]).with_stamp(FieldId::new("closed_at")),
+            );
+        assert_matches!(undeclared.check_states(), Err((field_id, _)) if field_id == FieldId::new("status"));
+
+        let date_stamp = undeclared
+            .clone()
+            .with_optional_field(FieldId::new("closed_at"), FieldType::Date);
+        assert_matches!(date_stamp.check_states(), Err((_, message)) if message.contains("datetime"));
+    }
+
+    #[test]
+    fn test_state_field() {
+        assert_eq!(deal_schema().state_field(), Some(&FieldId::new("status")));
+    }
+}
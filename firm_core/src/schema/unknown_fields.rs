@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use super::{EntitySchema, ValidationError};
+use crate::{Entity, suggest::closest_match};
+
+/// Defines how to treat entity fields that are not declared in the schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnknownFieldPolicy {
+    /// Unknown fields are accepted silently.
+    #[default]
+    Allow,
+    /// Unknown fields are reported, but the entity is still valid.
+    Warn,
+    /// Unknown fields make the entity invalid.
+    Error,
+}
+
+impl EntitySchema {
+    /// Builder method to set how the schema treats undeclared fields.
+    pub fn with_unknown_fields(mut self, policy: UnknownFieldPolicy) -> Self {
+        self.unknown_fields = Some(policy);
+        self
+    }
+
+    /// Gets how the schema treats undeclared fields, which is to allow them unless set otherwise.
+    pub fn unknown_field_policy(&self) -> UnknownFieldPolicy {
+        self.unknown_fields.unwrap_or_default()
+    }
+
+    /// Finds the fields of an entity that are not declared in the schema, regardless of policy.
+    /// Each error suggests the closest declared field name, if any is similar.
+    pub fn find_unknown_fields(&self, entity: &Entity) -> Vec<ValidationError> {
+        entity
+            .fields
+            .iter()
+            .filter(|(field_id, _)| !self.fields.contains_key(field_id))
+            .map(|(field_id, _)| {
+                let suggestion = closest_match(
+                    field_id.as_str(),
+                    self.ordered_fields().into_iter().map(|(id, _)| id.as_str()),
+                );
+
+                ValidationError::unknown_field(&entity.id, field_id, suggestion.map(Into::into))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::ValidationErrorType;
+    use crate::{EntityId, EntityType, FieldId, FieldType};
+    use assert_matches::assert_matches;
+
+    fn task_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("task"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("assignee_ref"), FieldType::Reference)
+    }
+
+    fn task_with_typo() -> Entity {
+        Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("name"), "Write docs")
+            .with_field(FieldId::new("asignee_ref"), "person.john")
+    }
+
+    #[test]
+    fn test_find_unknown_fields_with_suggestion() {
+        let errors = task_schema().find_unknown_fields(&task_with_typo());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, Some(FieldId::new("asignee_ref")));
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::UnknownField { suggestion: Some(s) } if s == &FieldId::new("assignee_ref")
+        );
+        assert!(errors[0].message.contains("Did you mean 'assignee_ref'?"));
+    }
+
+    #[test]
+    fn test_validate_allows_unknown_fields_by_default() {
+        assert!(task_schema().validate(&task_with_typo()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_fields_when_strict() {
+        let schema = task_schema().with_unknown_fields(UnknownFieldPolicy::Error);
+
+        let errors = schema.validate(&task_with_typo()).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::UnknownField { .. }
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use log::debug;
+use std::collections::HashMap;
 
-use super::{EntitySchema, ValidationError};
-use crate::Entity;
+use super::{EntitySchema, FieldSchema, RuleSeverity, UnknownFieldPolicy, ValidationError};
+use crate::{Entity, EntityId, FieldId, FieldType, FieldValue};
 
 pub type ValidationResult = Result<(), Vec<ValidationError>>;
 
@@ -26,26 +27,25 @@ impl EntitySchema {
 
         // Check each field in the schema
         for (field_name, field_schema) in &self.fields {
-            match entity.get_field(field_name) {
-                // Entity has the field: Check that it has desired type
-                Some(field_value) => {
-                    let expected_type = field_schema.expected_type();
-                    if !field_value.is_type(expected_type) {
-                        errors.push(ValidationError::mismatched_field_type(
-                            &entity.id,
-                            field_name,
-                            expected_type,
-                            &field_value.get_type(),
-                        ));
-                    }
-                }
-                // Entity does not have the field: Check if it's required
-                None => {
-                    if field_schema.is_required() {
-                        errors.push(ValidationError::missing_field(&entity.id, field_name));
-                    }
-                }
-            }
+            errors.extend(check_field(
+                &entity.id,
+                field_name,
+                field_schema,
+                entity.get_field(field_name),
+            ));
+        }
+
+        // Check rules spanning several fields, leaving out those which only warn
+        errors.extend(
+            self.rules
+                .iter()
+                .filter(|rule| rule.severity == RuleSeverity::Error)
+                .filter_map(|rule| rule.check(entity)),
+        );
+
+        // Check for fields the schema doesn't declare, if the schema is strict about it
+        if self.unknown_field_policy() == UnknownFieldPolicy::Error {
+            errors.extend(self.find_unknown_fields(entity));
         }
 
         if errors.is_empty() {
@@ -59,12 +59,223 @@ impl EntitySchema {
             Err(errors)
         }
     }
+
+    /// Finds the field constraints with warning severity that an entity breaks, like those of built-in schemas.
+    /// Constraints of fields nested in records are checked too.
+    pub fn find_constraint_warnings(&self, entity: &Entity) -> Vec<ValidationError> {
+        self.ordered_fields()
+            .into_iter()
+            .filter_map(|(field_id, field_schema)| {
+                entity.get_field(field_id).map(|value| {
+                    check_constraint_warnings(&entity.id, field_id, field_schema, value)
+                })
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Reads values written in another form of their field's type as that type, like datetimes in date fields or strings in duration fields.
+    /// Fields nested in records are coerced too.
+    /// Returns the IDs of the fields that were changed, with nested fields named by their path.
+    pub fn coerce_values(&self, entity: &mut Entity) -> Vec<FieldId> {
+        let mut coerced = Vec::new();
+
+        for (field_id, value) in entity.fields.iter_mut() {
+            if let Some(field_schema) = self.fields.get(field_id) {
+                coerce_field(field_id, field_schema, value, &mut coerced);
+            }
+        }
+
+        coerced
+    }
+}
+
+/// Coerces a field value, or the fields nested in records, to the type its schema expects.
+fn coerce_field(
+    field_id: &FieldId,
+    field_schema: &FieldSchema,
+    value: &mut FieldValue,
+    coerced: &mut Vec<FieldId>,
+) {
+    if let Some(coerced_value) = value.coerce_to(field_schema.expected_type()) {
+        *value = coerced_value;
+        coerced.push(field_id.clone());
+        return;
+    }
+
+    let Some(record_fields) = &field_schema.record_fields else {
+        return;
+    };
+
+    match value {
+        FieldValue::Record(_) => coerce_record(field_id, record_fields, value, coerced),
+        FieldValue::List(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let item_id = FieldId::from(format!("{}[{}]", field_id, index));
+                coerce_record(&item_id, record_fields, item, coerced);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Coerces the fields nested in a record value.
+fn coerce_record(
+    record_id: &FieldId,
+    record_fields: &HashMap<FieldId, FieldSchema>,
+    record: &mut FieldValue,
+    coerced: &mut Vec<FieldId>,
+) {
+    let FieldValue::Record(fields) = record else {
+        return;
+    };
+
+    for (field_id, value) in fields.iter_mut() {
+        if let Some(field_schema) = record_fields.get(field_id) {
+            let nested_id = FieldId::from(format!("{}.{}", record_id, field_id));
+            coerce_field(&nested_id, field_schema, value, coerced);
+        }
+    }
+}
+
+/// Checks a field value against its schema, including the fields nested in records.
+/// Nested fields are named by their path, like `address.city` or `line_items[0].amount`.
+fn check_field(
+    entity_id: &EntityId,
+    field_id: &FieldId,
+    field_schema: &FieldSchema,
+    value: Option<&FieldValue>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    // Entity does not have the field: Check if it's required
+    let Some(value) = value else {
+        if field_schema.is_required() {
+            errors.push(ValidationError::missing_field(entity_id, field_id));
+        }
+        return errors;
+    };
+
+    // Entity has the field: Check that it has desired type
+    let expected_type = field_schema.expected_type();
+    if !value.is_type(expected_type) {
+        errors.push(ValidationError::mismatched_field_type(
+            entity_id,
+            field_id,
+            expected_type,
+            &value.get_type(),
+        ));
+        return errors;
+    }
+
+    // Only check constraints for values of the right type, leaving out those which only warn
+    if field_schema.constraints.severity == RuleSeverity::Error {
+        errors.extend(field_schema.constraints.check(entity_id, field_id, value));
+    }
+
+    if let Some(states) = &field_schema.states {
+        errors.extend(states.check(entity_id, field_id, value));
+    }
+
+    if let Some(record_fields) = &field_schema.record_fields {
+        match value {
+            FieldValue::Record(_) => {
+                errors.extend(check_record(entity_id, field_id, record_fields, value))
+            }
+            FieldValue::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let item_id = FieldId::from(format!("{}[{}]", field_id, index));
+                    if item.is_type(&FieldType::Record) {
+                        errors.extend(check_record(entity_id, &item_id, record_fields, item));
+                    } else {
+                        errors.push(ValidationError::mismatched_field_type(
+                            entity_id,
+                            &item_id,
+                            &FieldType::Record,
+                            &item.get_type(),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    errors
+}
+
+/// Checks the constraints with warning severity of a field value, including the fields nested in records.
+/// Values of the wrong type are left to validation.
+fn check_constraint_warnings(
+    entity_id: &EntityId,
+    field_id: &FieldId,
+    field_schema: &FieldSchema,
+    value: &FieldValue,
+) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+
+    if !value.is_type(field_schema.expected_type()) {
+        return warnings;
+    }
+
+    if field_schema.constraints.severity == RuleSeverity::Warning {
+        warnings.extend(field_schema.constraints.check(entity_id, field_id, value));
+    }
+
+    let Some(record_fields) = &field_schema.record_fields else {
+        return warnings;
+    };
+
+    let records: Vec<(FieldId, &FieldValue)> = match value {
+        FieldValue::Record(_) => vec![(field_id.clone(), value)],
+        FieldValue::List(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (FieldId::from(format!("{}[{}]", field_id, index)), item))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    for (record_id, record) in records {
+        for (nested_id, nested_schema) in record_fields {
+            if let Some(nested_value) = record.get_record_field(nested_id) {
+                warnings.extend(check_constraint_warnings(
+                    entity_id,
+                    &FieldId::from(format!("{}.{}", record_id, nested_id)),
+                    nested_schema,
+                    nested_value,
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Checks the fields nested in a record value.
+fn check_record(
+    entity_id: &EntityId,
+    record_id: &FieldId,
+    record_fields: &HashMap<FieldId, FieldSchema>,
+    record: &FieldValue,
+) -> Vec<ValidationError> {
+    record_fields
+        .iter()
+        .flat_map(|(field_id, field_schema)| {
+            check_field(
+                entity_id,
+                &FieldId::from(format!("{}.{}", record_id, field_id)),
+                field_schema,
+                record.get_record_field(field_id),
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::ValidationErrorType;
+    use crate::schema::{FieldConstraints, FieldMode, ValidationErrorType};
     use crate::{
         EntityId, EntityType, FieldId,
         field::{FieldType, FieldValue},
@@ -131,6 +342,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_error_constraint_violation() {
+        let schema = EntitySchema::new(EntityType::new("opportunity"))
+            .with_optional_field(FieldId::new("probability"), FieldType::Integer)
+            .with_constraints(
+                &FieldId::new("probability"),
+                FieldConstraints {
+                    min: Some(0.0),
+                    max: Some(100.0),
+                    ..Default::default()
+                },
+            );
+
+        let entity = Entity::new(EntityId::new("deal"), EntityType::new("opportunity"))
+            .with_field(FieldId::new("probability"), FieldValue::Integer(250));
+
+        let errors = schema.validate(&entity).unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        assert_matches!(
+            &errors[0].error_type,
+            ValidationErrorType::ValueOutOfRange { min: Some(_), max: Some(_), actual } if *actual == 250.0
+        );
+    }
+
     #[test]
     fn test_validate_error_mismatched_field_types() {
         let schema = EntitySchema::new(EntityType::new("person"))
@@ -154,4 +390,222 @@ mod tests {
             ValidationErrorType::MismatchedFieldType { expected, actual } if expected == &FieldType::Boolean && actual == &FieldType::String
         );
     }
+
+    #[test]
+    fn test_datetime_values_are_coerced_to_dates() {
+        let schema = EntitySchema::new(EntityType::new("task"))
+            .with_optional_field(FieldId::new("due_date"), FieldType::Date)
+            .with_optional_field(FieldId::new("created_at"), FieldType::DateTime);
+
+        let datetime = chrono::DateTime::parse_from_rfc3339("2025-03-01T23:30:00+02:00").unwrap();
+        let mut entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("due_date"), FieldValue::DateTime(datetime))
+            .with_field(FieldId::new("created_at"), FieldValue::DateTime(datetime));
+
+        assert!(schema.validate(&entity).is_ok());
+
+        let coerced = schema.coerce_values(&mut entity);
+
+        assert_eq!(coerced, vec![FieldId::new("due_date")]);
+        assert_eq!(
+            entity.get_field(&FieldId::new("due_date")),
+            Some(&FieldValue::Date(
+                chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+            ))
+        );
+        assert_eq!(
+            entity.get_field(&FieldId::new("created_at")),
+            Some(&FieldValue::DateTime(datetime))
+        );
+        assert!(schema.validate(&entity).is_ok());
+    }
+
+    #[test]
+    fn test_string_values_are_coerced_to_durations() {
+        let schema = EntitySchema::new(EntityType::new("task"))
+            .with_optional_field(FieldId::new("estimate"), FieldType::Duration)
+            .with_optional_field(FieldId::new("notes"), FieldType::String);
+
+        let mut entity = Entity::new(EntityId::new("task.write"), EntityType::new("task"))
+            .with_field(FieldId::new("estimate"), FieldValue::from("2h30m"))
+            .with_field(FieldId::new("notes"), FieldValue::from("1h"));
+
+        assert!(schema.validate(&entity).is_err());
+
+        let coerced = schema.coerce_values(&mut entity);
+
+        assert_eq!(coerced, vec![FieldId::new("estimate")]);
+        assert_eq!(
+            entity.get_field(&FieldId::new("estimate")),
+            Some(&FieldValue::Duration(
+                crate::temporal::Duration::from_minutes(150)
+            ))
+        );
+        assert_eq!(
+            entity.get_field(&FieldId::new("notes")),
+            Some(&FieldValue::from("1h"))
+        );
+        assert!(schema.validate(&entity).is_ok());
+    }
+
+    #[test]
+    fn test_datetime_values_in_records_are_coerced_to_dates() {
+        let schema = EntitySchema::new(EntityType::new("contract")).add_field_schema(
+            FieldId::new("milestones"),
+            FieldSchema::new(FieldType::List, FieldMode::Optional, 0).with_record_fields([(
+                FieldId::new("due_date"),
+                FieldType::Date,
+                FieldMode::Required,
+            )]),
+        );
+
+        let datetime = chrono::DateTime::parse_from_rfc3339("2025-03-01T23:30:00+02:00").unwrap();
+        let mut entity = Entity::new(
+            EntityId::new("contract.support"),
+            EntityType::new("contract"),
+        )
+        .with_field(
+            FieldId::new("milestones"),
+            FieldValue::List(vec![FieldValue::Record(vec![(
+                FieldId::new("due_date"),
+                FieldValue::DateTime(datetime),
+            )])]),
+        );
+
+        let coerced = schema.coerce_values(&mut entity);
+
+        assert_eq!(coerced, vec![FieldId::from("milestones[0].due_date")]);
+        assert_eq!(
+            entity.get_field(&FieldId::new("milestones")),
+            Some(&FieldValue::List(vec![FieldValue::Record(vec![(
+                FieldId::new("due_date"),
+                FieldValue::Date(chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()),
+            )])]))
+        );
+        assert!(schema.validate(&entity).is_ok());
+    }
+
+    #[test]
+    fn test_constraints_which_only_warn() {
+        let schema = EntitySchema::new(EntityType::new("opportunity"))
+            .with_optional_field(FieldId::new("probability"), FieldType::Integer)
+            .with_constraints(
+                &FieldId::new("probability"),
+                FieldConstraints {
+                    min: Some(0.0),
+                    max: Some(100.0),
+                    ..Default::default()
+                }
+                .with_severity(RuleSeverity::Warning),
+            );
+
+        let entity = Entity::new(
+            EntityId::new("opportunity.renewal"),
+            EntityType::new("opportunity"),
+        )
+        .with_field(FieldId::new("probability"), FieldValue::Integer(120));
+
+        assert!(schema.validate(&entity).is_ok());
+
+        let warnings = schema.find_constraint_warnings(&entity);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, Some(FieldId::new("probability")));
+    }
+
+    #[test]
+    fn test_validate_nested_record_fields() {
+        let schema = EntitySchema::new(EntityType::new("proposal"))
+            .add_field_schema(
+                FieldId::new("address"),
+                FieldSchema::new(FieldType::Record, FieldMode::Required, 0).with_record_fields([
+                    (
+                        FieldId::new("street"),
+                        FieldType::String,
+                        FieldMode::Required,
+                    ),
+                    (FieldId::new("city"), FieldType::String, FieldMode::Optional),
+                ]),
+            )
+            .add_field_schema(
+                FieldId::new("line_items"),
+                FieldSchema::new(FieldType::List, FieldMode::Optional, 1).with_record_fields([(
+                    FieldId::new("amount"),
+                    FieldType::Integer,
+                    FieldMode::Required,
+                )]),
+            );
+
+        let valid = Entity::new(
+            EntityId::new("proposal.website"),
+            EntityType::new("proposal"),
+        )
+        .with_field(
+            FieldId::new("address"),
+            vec![(FieldId::new("street"), FieldValue::from("Main Street 1"))],
+        )
+        .with_field(
+            FieldId::new("line_items"),
+            vec![FieldValue::Record(vec![(
+                FieldId::new("amount"),
+                FieldValue::Integer(100),
+            )])],
+        );
+        assert!(schema.validate(&valid).is_ok());
+
+        let invalid = Entity::new(
+            EntityId::new("proposal.website"),
+            EntityType::new("proposal"),
+        )
+        .with_field(
+            FieldId::new("address"),
+            vec![(FieldId::new("city"), FieldValue::Integer(1))],
+        )
+        .with_field(
+            FieldId::new("line_items"),
+            vec![
+                FieldValue::Record(vec![(FieldId::new("amount"), FieldValue::from("100"))]),
+                FieldValue::Integer(100),
+            ],
+        );
+        let mut errors: Vec<(String, ValidationErrorType)> = schema
+            .validate(&invalid)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| (error.field.unwrap().to_string(), error.error_type))
+            .collect();
+        errors.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    String::from("address.city"),
+                    ValidationErrorType::MismatchedFieldType {
+                        expected: FieldType::String,
+                        actual: FieldType::Integer,
+                    }
+                ),
+                (
+                    String::from("address.street"),
+                    ValidationErrorType::MissingRequiredField {
+                        required: FieldId::from(String::from("address.street")),
+                    }
+                ),
+                (
+                    String::from("line_items[0].amount"),
+                    ValidationErrorType::MismatchedFieldType {
+                        expected: FieldType::Integer,
+                        actual: FieldType::String,
+                    }
+                ),
+                (
+                    String::from("line_items[1]"),
+                    ValidationErrorType::MismatchedFieldType {
+                        expected: FieldType::Record,
+                        actual: FieldType::Integer,
+                    }
+                ),
+            ]
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{EntityId, EntityType, FieldId, FieldType};
+use crate::{EntityId, EntityType, FieldId, FieldType, quantity::Dimension};
 
 /// Defines the types of errors you might encounter when validating a schema.
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +15,43 @@ pub enum ValidationErrorType {
         expected: FieldType,
         actual: FieldType,
     },
+    /// The entity has a numeric field whose value is outside the allowed range.
+    ValueOutOfRange {
+        min: Option<f64>,
+        max: Option<f64>,
+        actual: f64,
+    },
+    /// The entity has a string field which does not match the required pattern.
+    PatternMismatch { pattern: String },
+    /// The entity has a string field whose length is outside the allowed range.
+    LengthOutOfRange {
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    },
+    /// The entity has a list field whose number of items is outside the allowed range.
+    ItemCountOutOfRange {
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    },
+    /// The entity has a reference to an entity of another type than the schema allows.
+    MismatchedReferenceType {
+        expected: EntityType,
+        actual: EntityId,
+    },
+    /// The entity has a quantity field whose unit is not of the dimension the schema requires.
+    MismatchedUnit { expected: Dimension, actual: String },
+    /// The entity has a field that must be unique, but another entity has the same value.
+    DuplicateValue { duplicate_of: EntityId },
+    /// The entity has a field which is not declared in the schema.
+    UnknownField { suggestion: Option<FieldId> },
+    /// The entity has a field whose value is not one of its declared states.
+    InvalidState { allowed: Vec<String> },
+    /// The entity breaks a rule spanning several fields.
+    RuleViolation { fields: Vec<FieldId> },
+    /// The entity is an exchange rate which can't be used to convert amounts.
+    InvalidExchangeRate,
 }
 
 /// Information about an error encountered while validating a schema.
@@ -82,4 +119,228 @@ impl ValidationError {
             },
         }
     }
+
+    /// Shorthand for creating a value out of range error.
+    pub fn value_out_of_range(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        min: Option<f64>,
+        max: Option<f64>,
+        actual: f64,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to be {} but it was {}",
+                field_id,
+                entity_id,
+                describe_bounds(min, max),
+                actual
+            ),
+            error_type: ValidationErrorType::ValueOutOfRange { min, max, actual },
+        }
+    }
+
+    /// Shorthand for creating a pattern mismatch error.
+    pub fn pattern_mismatch(entity_id: &EntityId, field_id: &FieldId, pattern: &str) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to match the pattern '{}'",
+                field_id, entity_id, pattern
+            ),
+            error_type: ValidationErrorType::PatternMismatch {
+                pattern: pattern.to_string(),
+            },
+        }
+    }
+
+    /// Shorthand for creating a length out of range error.
+    pub fn length_out_of_range(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to have a length {} but it was {}",
+                field_id,
+                entity_id,
+                describe_bounds(min, max),
+                actual
+            ),
+            error_type: ValidationErrorType::LengthOutOfRange { min, max, actual },
+        }
+    }
+
+    /// Shorthand for creating an item count out of range error.
+    pub fn item_count_out_of_range(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to have a number of items {} but it had {}",
+                field_id,
+                entity_id,
+                describe_bounds(min, max),
+                actual
+            ),
+            error_type: ValidationErrorType::ItemCountOutOfRange { min, max, actual },
+        }
+    }
+
+    /// Shorthand for creating a mismatched reference type error.
+    pub fn mismatched_reference_type(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        expected: &EntityType,
+        actual: &EntityId,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to reference a '{}' but it referenced '{}'",
+                field_id, entity_id, expected, actual
+            ),
+            error_type: ValidationErrorType::MismatchedReferenceType {
+                expected: expected.clone(),
+                actual: actual.clone(),
+            },
+        }
+    }
+
+    /// Shorthand for creating a mismatched unit error.
+    pub fn mismatched_unit(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        expected: &Dimension,
+        actual: &str,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to be measured in a {} unit ({}) but it was '{}'",
+                field_id,
+                entity_id,
+                expected,
+                expected.units().join(", "),
+                actual
+            ),
+            error_type: ValidationErrorType::MismatchedUnit {
+                expected: *expected,
+                actual: actual.to_string(),
+            },
+        }
+    }
+
+    /// Shorthand for creating a duplicate value error.
+    pub fn duplicate_value(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        duplicate_of: &EntityId,
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to be unique but it has the same value as '{}'",
+                field_id, entity_id, duplicate_of
+            ),
+            error_type: ValidationErrorType::DuplicateValue {
+                duplicate_of: duplicate_of.clone(),
+            },
+        }
+    }
+
+    /// Shorthand for creating an invalid state error.
+    pub fn invalid_state(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        state: &str,
+        allowed: &[String],
+    ) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Expected field '{}' for entity '{}' to be one of {} but it was '{}'",
+                field_id,
+                entity_id,
+                allowed.join(", "),
+                state
+            ),
+            error_type: ValidationErrorType::InvalidState {
+                allowed: allowed.to_vec(),
+            },
+        }
+    }
+
+    /// Shorthand for creating a rule violation error, which refers to the first field of the rule.
+    pub fn rule_violation(entity_id: &EntityId, fields: Vec<FieldId>, message: &str) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: fields.first().cloned(),
+            message: message.to_string(),
+            error_type: ValidationErrorType::RuleViolation { fields },
+        }
+    }
+
+    /// Shorthand for creating an error for an exchange rate which can't be used, with the reason why.
+    pub fn invalid_exchange_rate(entity_id: &EntityId, field_id: &FieldId, reason: &str) -> Self {
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Exchange rate '{}' can't be used to convert amounts: {}",
+                entity_id, reason
+            ),
+            error_type: ValidationErrorType::InvalidExchangeRate,
+        }
+    }
+
+    /// Shorthand for creating an unknown field error, with an optional suggestion for the intended field.
+    pub fn unknown_field(
+        entity_id: &EntityId,
+        field_id: &FieldId,
+        suggestion: Option<FieldId>,
+    ) -> Self {
+        let hint = match &suggestion {
+            Some(suggestion) => format!(". Did you mean '{}'?", suggestion),
+            None => String::new(),
+        };
+
+        Self {
+            entity_id: Some(entity_id.clone()),
+            field: Some(field_id.clone()),
+            message: format!(
+                "Unknown field '{}' for entity '{}'{}",
+                field_id, entity_id, hint
+            ),
+            error_type: ValidationErrorType::UnknownField { suggestion },
+        }
+    }
+}
+
+/// Describes optional bounds for an error message, like "between 0 and 100".
+fn describe_bounds<T: std::fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => "within bounds".to_string(),
+    }
 }
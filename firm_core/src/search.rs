@@ -0,0 +1,580 @@
+//! Full-text search over entity IDs and string fields.
+//!
+//! Text is split into words, lowercased and stripped of diacritics, so `Café` is found by `cafe`.
+//! Query terms match words exactly, as a prefix or with a typo, and all terms must match an
+//! entity for it to be found. Results are ranked by how well and how rarely their words match.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::ops::{Bound, Range};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::graph::EntityGraph;
+use crate::suggest::edit_distance;
+use crate::{Entity, EntityId, EntityType, FieldId, FieldValue};
+
+/// The pseudo field which entity IDs are indexed under.
+pub const ID_FIELD: &str = "id";
+
+/// How much a match in an entity ID counts compared to a match in a field.
+const ID_BOOST: f64 = 2.0;
+/// How much a match on the start of a word counts compared to a whole word.
+const PREFIX_WEIGHT: f64 = 0.6;
+/// How much a match with a typo counts compared to a whole word.
+const FUZZY_WEIGHT: f64 = 0.4;
+/// The number of characters to show on either side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 30;
+
+/// Where a word occurs in the index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Posting {
+    entity_id: EntityId,
+    field_id: FieldId,
+    count: usize,
+}
+
+/// An inverted index from words to the entities and fields they occur in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    words: BTreeMap<String, Vec<Posting>>,
+    entity_types: BTreeMap<EntityId, EntityType>,
+    /// The fingerprint of the graph the index was built from, if it was built from a whole graph.
+    #[serde(default)]
+    fingerprint: Option<u64>,
+}
+
+/// Options for searching the index.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// The entity types to search, or all types if empty.
+    pub entity_types: Vec<EntityType>,
+    /// The maximum number of results, or all results if not set.
+    pub limit: Option<usize>,
+}
+
+impl SearchOptions {
+    /// Builder method to search an entity type. Without any, all types are searched.
+    pub fn with_entity_type(mut self, entity_type: EntityType) -> Self {
+        self.entity_types.push(entity_type);
+        self
+    }
+
+    /// Builder method to limit the number of results.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// An entity which matches a search, with the fields the query terms were found in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub entity_id: EntityId,
+    pub entity_type: EntityType,
+    pub score: f64,
+    pub fields: Vec<FieldId>,
+}
+
+/// An excerpt of a field around the words matching a search.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snippet {
+    pub field_id: FieldId,
+    pub text: String,
+    /// The byte ranges of matching words in the text.
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl SearchIndex {
+    /// Creates an empty search index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a search index for all entities in a graph.
+    pub fn from_graph(graph: &EntityGraph) -> Self {
+        let mut index = Self::new();
+        for entity in graph.entities() {
+            index.add_entity(entity);
+        }
+        index.fingerprint = Some(fingerprint(graph));
+        index
+    }
+
+    /// Checks if the index was built from a graph with the same IDs, types and string fields.
+    /// Indexes built from single entities are never current.
+    pub fn is_current(&self, graph: &EntityGraph) -> bool {
+        self.fingerprint == Some(fingerprint(graph))
+    }
+
+    /// Adds the ID and string fields of an entity to the index, including strings in lists and records.
+    pub fn add_entity(&mut self, entity: &Entity) {
+        self.fingerprint = None;
+        self.entity_types
+            .insert(entity.id.clone(), entity.entity_type.clone());
+        self.add_text(&entity.id, &FieldId::from(ID_FIELD), entity.id.as_str());
+
+        for (field_id, value) in &entity.fields {
+            let mut texts = Vec::new();
+            collect_texts(value, &mut texts);
+            for text in texts {
+                self.add_text(&entity.id, field_id, text);
+            }
+        }
+    }
+
+    /// Gets the number of entities in the index.
+    pub fn len(&self) -> usize {
+        self.entity_types.len()
+    }
+
+    /// Checks if the index has no entities.
+    pub fn is_empty(&self) -> bool {
+        self.entity_types.is_empty()
+    }
+
+    /// Finds the entities matching all terms of a query, best matches first.
+    pub fn search(&self, query: &str, options: &SearchOptions) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: HashMap<&EntityId, f64> = HashMap::new();
+        let mut fields: HashMap<&EntityId, BTreeSet<&FieldId>> = HashMap::new();
+        for (position, term) in terms.iter().enumerate() {
+            let mut term_scores: HashMap<&EntityId, f64> = HashMap::new();
+
+            for (word, weight) in self.matching_words(term) {
+                let postings = &self.words[word];
+                let document_count = postings
+                    .iter()
+                    .map(|posting| &posting.entity_id)
+                    .collect::<BTreeSet<_>>()
+                    .len();
+                let rarity = (1.0 + self.len() as f64 / document_count as f64).ln();
+
+                for posting in postings {
+                    let boost = if posting.field_id.as_str() == ID_FIELD {
+                        ID_BOOST
+                    } else {
+                        1.0
+                    };
+                    let frequency = 1.0 + (posting.count as f64).ln();
+
+                    *term_scores.entry(&posting.entity_id).or_default() +=
+                        weight * rarity * frequency * boost;
+                    fields
+                        .entry(&posting.entity_id)
+                        .or_default()
+                        .insert(&posting.field_id);
+                }
+            }
+
+            // Entities have to match every term, so only those matching the earlier terms are kept
+            if position == 0 {
+                scores = term_scores;
+            } else {
+                scores = scores
+                    .into_iter()
+                    .filter_map(|(id, score)| term_scores.get(id).map(|extra| (id, score + extra)))
+                    .collect();
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter_map(|(entity_id, score)| {
+                let entity_type = self.entity_types.get(entity_id)?;
+                let included =
+                    options.entity_types.is_empty() || options.entity_types.contains(entity_type);
+
+                included.then(|| SearchHit {
+                    entity_id: entity_id.clone(),
+                    entity_type: entity_type.clone(),
+                    score,
+                    fields: fields[entity_id].iter().map(|&id| id.clone()).collect(),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.entity_id.cmp(&b.entity_id))
+        });
+        if let Some(limit) = options.limit {
+            hits.truncate(limit);
+        }
+
+        hits
+    }
+
+    fn add_text(&mut self, entity_id: &EntityId, field_id: &FieldId, text: &str) {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for word in tokenize(text) {
+            *counts.entry(word).or_default() += 1;
+        }
+
+        for (word, count) in counts {
+            let postings = self.words.entry(word).or_default();
+            match postings
+                .iter_mut()
+                .find(|posting| &posting.entity_id == entity_id && &posting.field_id == field_id)
+            {
+                Some(posting) => posting.count += count,
+                None => postings.push(Posting {
+                    entity_id: entity_id.clone(),
+                    field_id: field_id.clone(),
+                    count,
+                }),
+            }
+        }
+    }
+
+    /// Finds the indexed words matching a term, with how much each match counts.
+    fn matching_words(&self, term: &str) -> Vec<(&String, f64)> {
+        let prefixed = self
+            .words
+            .range::<str, _>((Bound::Included(term), Bound::Unbounded))
+            .take_while(|(word, _)| word.starts_with(term))
+            .map(|(word, _)| word);
+
+        let mut matches: Vec<(&String, f64)> = prefixed
+            .map(|word| {
+                let weight = if word == term { 1.0 } else { PREFIX_WEIGHT };
+                (word, weight)
+            })
+            .collect();
+
+        if let Some(max_distance) = max_typos(term) {
+            matches.extend(
+                self.words
+                    .keys()
+                    .filter(|word| !word.starts_with(term))
+                    .filter(|word| edit_distance(term, word) <= max_distance)
+                    .map(|word| (word, FUZZY_WEIGHT)),
+            );
+        }
+
+        matches
+    }
+}
+
+/// Excerpts the fields of an entity around the words matching a query.
+///
+/// Each field gets one snippet, centred on its first match, with the matching words highlighted.
+pub fn snippets(entity: &Entity, field_ids: &[FieldId], query: &str) -> Vec<Snippet> {
+    let terms = tokenize(query);
+
+    field_ids
+        .iter()
+        .filter_map(|field_id| {
+            let texts = match field_id.as_str() {
+                ID_FIELD => vec![entity.id.as_str()],
+                _ => {
+                    let mut texts = Vec::new();
+                    collect_texts(entity.get_field(field_id)?, &mut texts);
+                    texts
+                }
+            };
+
+            texts
+                .into_iter()
+                .find_map(|text| snippet(field_id, text, &terms))
+        })
+        .collect()
+}
+
+fn snippet(field_id: &FieldId, text: &str, terms: &[String]) -> Option<Snippet> {
+    let matches: Vec<Range<usize>> = word_ranges(text)
+        .into_iter()
+        .filter(|range| {
+            let word = fold(&text[range.clone()]);
+            terms.iter().any(|term| word_matches(&word, term))
+        })
+        .collect();
+    let first = matches.first()?;
+
+    // Take some characters on either side of the first match, without cutting words in half
+    let start = match text[..first.start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+    {
+        Some((window_start, _)) => text[window_start..first.start]
+            .char_indices()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(space, c)| window_start + space + c.len_utf8())
+            .unwrap_or(window_start),
+        None => 0,
+    };
+    let end = match text[first.end..].char_indices().nth(SNIPPET_CONTEXT) {
+        Some((window_length, _)) => text[first.end..first.end + window_length]
+            .rfind(char::is_whitespace)
+            .map(|space| first.end + space)
+            .unwrap_or(first.end + window_length),
+        None => text.len(),
+    };
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let excerpt = text[start..end].replace(['\n', '\r', '\t'], " ");
+
+    Some(Snippet {
+        field_id: field_id.clone(),
+        text: format!("{}{}{}", prefix, excerpt, suffix),
+        highlights: matches
+            .into_iter()
+            .filter(|range| range.start >= start && range.end <= end)
+            .map(|range| {
+                let offset = prefix.len() + range.start - start;
+                offset..offset + range.len()
+            })
+            .collect(),
+    })
+}
+
+/// Collects the strings in a value, looking inside lists and records.
+fn collect_texts<'a>(value: &'a FieldValue, texts: &mut Vec<&'a str>) {
+    match value {
+        FieldValue::String(text) => texts.push(text),
+        FieldValue::List(items) => {
+            for item in items {
+                collect_texts(item, texts);
+            }
+        }
+        FieldValue::Record(fields) => {
+            for (_, nested) in fields {
+                collect_texts(nested, texts);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Hashes the IDs, types and string fields of the entities in a graph, which is what gets indexed.
+/// The hash may change between Rust versions, which only means the index gets rebuilt.
+fn fingerprint(graph: &EntityGraph) -> u64 {
+    let mut entities = graph.entities();
+    entities.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut hasher = DefaultHasher::new();
+    for entity in entities {
+        entity.id.hash(&mut hasher);
+        entity.entity_type.hash(&mut hasher);
+        for (field_id, value) in &entity.fields {
+            let mut texts = Vec::new();
+            collect_texts(value, &mut texts);
+            field_id.hash(&mut hasher);
+            texts.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Splits text into folded words.
+fn tokenize(text: &str) -> Vec<String> {
+    word_ranges(text)
+        .into_iter()
+        .map(|range| fold(&text[range]))
+        .collect()
+}
+
+/// Finds the byte ranges of the words in text, which are runs of letters and digits.
+fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(word_start)) => {
+                ranges.push(word_start..index);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        ranges.push(word_start..text.len());
+    }
+
+    ranges
+}
+
+/// Lowercases a word and strips its diacritics.
+fn fold(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Gets the number of typos allowed when matching a term, if it's long enough to allow any.
+fn max_typos(term: &str) -> Option<usize> {
+    match term.chars().count() {
+        0..=3 => None,
+        4..=7 => Some(1),
+        _ => Some(2),
+    }
+}
+
+fn word_matches(word: &str, term: &str) -> bool {
+    word.starts_with(term)
+        || max_typos(term).is_some_and(|max_distance| edit_distance(term, word) <= max_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_index() -> (SearchIndex, Vec<Entity>) {
+        let entities = vec![
+            Entity::new(
+                EntityId::new("account.cafe_nero"),
+                EntityType::new("account"),
+            )
+            .with_field(FieldId::new("name"), "Café Nero")
+            .with_field(
+                FieldId::new("notes"),
+                "Signed a monthly retainer in March.\nRenewal is due in the autumn.",
+            ),
+            Entity::new(EntityId::new("person.jane"), EntityType::new("person"))
+                .with_field(FieldId::new("name"), "Jane Doe")
+                .with_field(
+                    FieldId::new("tags"),
+                    FieldValue::List(vec![FieldValue::String("retainer".to_string())]),
+                ),
+            Entity::new(EntityId::new("task.renewal"), EntityType::new("task"))
+                .with_field(FieldId::new("name"), "Prepare renewal proposal"),
+        ];
+
+        let mut index = SearchIndex::new();
+        for entity in &entities {
+            index.add_entity(entity);
+        }
+        (index, entities)
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.entity_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_folds_case_and_diacritics() {
+        assert_eq!(
+            tokenize("Café NERO, São-Paulo_office"),
+            vec!["cafe", "nero", "sao", "paulo", "office"]
+        );
+    }
+
+    #[test]
+    fn test_search_exact_and_folded() {
+        let (index, _) = sample_index();
+
+        let hits = index.search("retainer", &SearchOptions::default());
+        assert_eq!(ids(&hits), vec!["account.cafe_nero", "person.jane"]);
+        assert_eq!(hits[1].fields, vec![FieldId::new("tags")]);
+
+        let hits = index.search("CAFE", &SearchOptions::default());
+        assert_eq!(ids(&hits), vec!["account.cafe_nero"]);
+        assert_eq!(
+            hits[0].fields,
+            vec![FieldId::new("id"), FieldId::new("name")]
+        );
+    }
+
+    #[test]
+    fn test_search_prefix_and_fuzzy() {
+        let (index, _) = sample_index();
+
+        assert_eq!(
+            ids(&index.search("renew", &SearchOptions::default())),
+            vec!["task.renewal", "account.cafe_nero"]
+        );
+        assert_eq!(
+            ids(&index.search("retianer", &SearchOptions::default())),
+            vec!["account.cafe_nero", "person.jane"]
+        );
+    }
+
+    #[test]
+    fn test_search_requires_every_term() {
+        let (index, _) = sample_index();
+
+        let hits = index.search("retainer march", &SearchOptions::default());
+        assert_eq!(ids(&hits), vec!["account.cafe_nero"]);
+        assert!(
+            index
+                .search("retainer zebra", &SearchOptions::default())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_search_options() {
+        let (index, _) = sample_index();
+
+        let options = SearchOptions::default().with_entity_type(EntityType::new("person"));
+        assert_eq!(
+            ids(&index.search("retainer", &options)),
+            vec!["person.jane"]
+        );
+
+        let options = SearchOptions::default().with_limit(1);
+        assert_eq!(index.search("retainer", &options).len(), 1);
+    }
+
+    #[test]
+    fn test_snippets() {
+        let (index, entities) = sample_index();
+        let hit = &index.search("retainer", &SearchOptions::default())[0];
+
+        let snippets = snippets(&entities[0], &hit.fields, "retainer");
+        assert_eq!(snippets.len(), 1);
+
+        let snippet = &snippets[0];
+        assert_eq!(snippet.field_id, FieldId::new("notes"));
+        assert_eq!(
+            snippet.text,
+            "Signed a monthly retainer in March. Renewal is due in…"
+        );
+        assert_eq!(&snippet.text[snippet.highlights[0].clone()], "retainer");
+    }
+
+    #[test]
+    fn test_index_round_trips_through_json() {
+        let (index, _) = sample_index();
+        let json = serde_json::to_string(&index).unwrap();
+        let restored: SearchIndex = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, index);
+        assert_eq!(restored.len(), 3);
+    }
+
+    #[test]
+    fn test_index_is_current_only_for_the_graph_it_was_built_from() {
+        let (index, entities) = sample_index();
+        let mut graph = EntityGraph::new();
+        graph.add_entities(entities.clone()).unwrap();
+
+        let built = SearchIndex::from_graph(&graph);
+        assert!(built.is_current(&graph));
+        assert!(!index.is_current(&graph));
+
+        let json = serde_json::to_string(&built).unwrap();
+        let restored: SearchIndex = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_current(&graph));
+
+        let mut changed = EntityGraph::new();
+        let mut changed_entities = entities;
+        changed_entities[2] = changed_entities[2]
+            .clone()
+            .with_field(FieldId::new("name"), "Send renewal proposal");
+        changed.add_entities(changed_entities).unwrap();
+        assert!(!built.is_current(&changed));
+    }
+}
@@ -0,0 +1,144 @@
+//! Helpers for writing `chrono` values as date and datetime fields with `#[serde(with = ...)]`.
+//!
+//! Strings are always written as string fields, so dates need to be marked to be written as dates.
+//! Other serializers see the plain string, so the helpers are safe to use on structs that are
+//! serialized elsewhere too.
+
+/// The newtype name which marks a string as a datetime for [`to_entity`](crate::to_entity).
+pub(super) const DATETIME_NAME: &str = "FirmDateTime";
+
+/// The newtype name which marks a string as a date for [`to_entity`](crate::to_entity).
+pub(super) const DATE_NAME: &str = "FirmDate";
+
+/// Writes a `DateTime` as a datetime field, and reads it from one.
+///
+/// ```text
+/// #[serde(with = "firm_core::serde_bridge::datetime")]
+/// due_date: DateTime<FixedOffset>,
+/// ```
+pub mod datetime {
+    use chrono::{DateTime, TimeZone};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt::Display;
+
+    use super::DATETIME_NAME;
+
+    /// Serializes a datetime as an RFC 3339 string, marked as a datetime.
+    pub fn serialize<Tz, S>(value: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(DATETIME_NAME, &value.to_rfc3339())
+    }
+
+    /// Deserializes a datetime from an RFC 3339 string.
+    pub fn deserialize<'de, Tz, D>(deserializer: D) -> Result<DateTime<Tz>, D::Error>
+    where
+        Tz: TimeZone,
+        DateTime<Tz>: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        DateTime::<Tz>::deserialize(deserializer)
+    }
+
+    /// Writes an optional `DateTime` as a datetime field, leaving it out if it's `None`.
+    /// Add `#[serde(default)]` as well, so the field can be missing when reading.
+    pub mod option {
+        use super::*;
+
+        /// Serializes a datetime if there is one.
+        pub fn serialize<Tz, S>(
+            value: &Option<DateTime<Tz>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            Tz: TimeZone,
+            Tz::Offset: Display,
+            S: Serializer,
+        {
+            struct Marked<'a, Tz: TimeZone>(&'a DateTime<Tz>);
+
+            impl<Tz> Serialize for Marked<'_, Tz>
+            where
+                Tz: TimeZone,
+                Tz::Offset: Display,
+            {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, serializer)
+                }
+            }
+
+            match value {
+                Some(value) => serializer.serialize_some(&Marked(value)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserializes a datetime if there is one.
+        pub fn deserialize<'de, Tz, D>(deserializer: D) -> Result<Option<DateTime<Tz>>, D::Error>
+        where
+            Tz: TimeZone,
+            DateTime<Tz>: Deserialize<'de>,
+            D: Deserializer<'de>,
+        {
+            Option::<DateTime<Tz>>::deserialize(deserializer)
+        }
+    }
+}
+
+/// Writes a `NaiveDate` as a date field, and reads it from one.
+///
+/// ```text
+/// #[serde(with = "firm_core::serde_bridge::date")]
+/// start_date: NaiveDate,
+/// ```
+pub mod date {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::DATE_NAME;
+
+    /// Serializes a date as a `YYYY-MM-DD` string, marked as a date.
+    pub fn serialize<S: Serializer>(value: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(DATE_NAME, &value.to_string())
+    }
+
+    /// Deserializes a date from a `YYYY-MM-DD` string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        NaiveDate::deserialize(deserializer)
+    }
+
+    /// Writes an optional `NaiveDate` as a date field, leaving it out if it's `None`.
+    /// Add `#[serde(default)]` as well, so the field can be missing when reading.
+    pub mod option {
+        use super::*;
+
+        /// Serializes a date if there is one.
+        pub fn serialize<S: Serializer>(
+            value: &Option<NaiveDate>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            struct Marked<'a>(&'a NaiveDate);
+
+            impl Serialize for Marked<'_> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, serializer)
+                }
+            }
+
+            match value {
+                Some(value) => serializer.serialize_some(&Marked(value)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserializes a date if there is one.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<NaiveDate>, D::Error> {
+            Option::<NaiveDate>::deserialize(deserializer)
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::slice;
+
+use super::SerdeError;
+use crate::{Entity, FieldId, FieldValue};
+
+/// Deserializes an entity as a map of its fields.
+pub(super) struct EntityDeserializer<'a> {
+    entity: &'a Entity,
+}
+
+impl<'a> EntityDeserializer<'a> {
+    pub(super) fn new(entity: &'a Entity) -> Self {
+        Self { entity }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for EntityDeserializer<'a> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(FieldsAccess {
+            fields: self.entity.fields.iter(),
+            current: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Walks the fields of an entity, remembering the current field to name it in errors.
+struct FieldsAccess<'a> {
+    fields: slice::Iter<'a, (FieldId, FieldValue)>,
+    current: Option<&'a (FieldId, FieldValue)>,
+}
+
+impl<'de, 'a> MapAccess<'de> for FieldsAccess<'a> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.current = self.fields.next();
+
+        match self.current {
+            Some((field_id, _)) => seed
+                .deserialize(field_id.as_str().into_deserializer())
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (field_id, value) = self
+            .current
+            .ok_or_else(|| de::Error::custom("value requested before its field"))?;
+
+        seed.deserialize(ValueDeserializer { value })
+            .map_err(|e| e.in_field(field_id))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+/// Deserializes a field value, mapping it to the closest plain Rust type.
+struct ValueDeserializer<'a> {
+    value: &'a FieldValue,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    /// Re-reads the value through its own serde encoding, for targets which expect it, like `FieldValue`.
+    fn deserialize_encoded<'de, V: Visitor<'de>>(
+        &self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let encoded = match name {
+            "ReferenceValue" => match self.value {
+                FieldValue::Reference(reference) => serde_json::to_value(reference),
+                _ => return Err(self.invalid_type(&"a reference")),
+            },
+            _ => serde_json::to_value(self.value),
+        }
+        .map_err(de::Error::custom)?;
+
+        de::Deserializer::deserialize_enum(encoded, name, variants, visitor)
+            .map_err(de::Error::custom)
+    }
+
+    fn invalid_type(&self, expected: &dyn de::Expected) -> SerdeError {
+        let unexpected = match self.value {
+            FieldValue::Boolean(b) => de::Unexpected::Bool(*b),
+            FieldValue::String(s) => de::Unexpected::Str(s),
+            FieldValue::Integer(i) => de::Unexpected::Signed(*i),
+            FieldValue::Float(f) => de::Unexpected::Float(*f),
+            FieldValue::List(_) => de::Unexpected::Seq,
+            FieldValue::Currency { .. } => de::Unexpected::Other("currency value"),
+            FieldValue::Reference(_) => de::Unexpected::Other("reference"),
+            FieldValue::DateTime(_) => de::Unexpected::Other("datetime"),
+            FieldValue::Date(_) => de::Unexpected::Other("date"),
+            FieldValue::Duration(_) => de::Unexpected::Other("duration"),
+            FieldValue::Recurrence(_) => de::Unexpected::Other("recurrence"),
+            FieldValue::Quantity(_) => de::Unexpected::Other("quantity"),
+            FieldValue::Record(_) => de::Unexpected::Map,
+            FieldValue::Path(_) => de::Unexpected::Other("path"),
+        };
+
+        de::Error::invalid_type(unexpected, expected)
+    }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, SerdeError> for ValueDeserializer<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            FieldValue::Boolean(b) => visitor.visit_bool(*b),
+            FieldValue::String(s) => visitor.visit_str(s),
+            FieldValue::Integer(i) => visitor.visit_i64(*i),
+            FieldValue::Float(f) => visitor.visit_f64(*f),
+            FieldValue::Currency { amount, currency } => {
+                let mut map = MapDeserializer::<_, SerdeError>::new(
+                    [
+                        ("amount", amount.to_string()),
+                        ("currency", currency.code().to_string()),
+                    ]
+                    .into_iter(),
+                );
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            FieldValue::Reference(reference) => visitor.visit_string(reference.to_string()),
+            FieldValue::List(items) => {
+                let mut seq = SeqDeserializer::<_, SerdeError>::new(
+                    items.iter().map(|value| ValueDeserializer { value }),
+                );
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            FieldValue::DateTime(date) => visitor.visit_string(date.to_rfc3339()),
+            FieldValue::Date(date) => visitor.visit_string(date.to_string()),
+            FieldValue::Duration(duration) => visitor.visit_string(duration.to_string()),
+            FieldValue::Recurrence(recurrence) => visitor.visit_string(recurrence.to_string()),
+            FieldValue::Quantity(quantity) => visitor.visit_string(quantity.to_string()),
+            FieldValue::Record(fields) => {
+                let mut map = MapDeserializer::<_, SerdeError>::new(
+                    fields
+                        .iter()
+                        .map(|(field_id, value)| (field_id.as_str(), ValueDeserializer { value })),
+                );
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            FieldValue::Path(path) => visitor.visit_string(path.to_string_lossy().into_owned()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            FieldValue::Currency { amount, currency } => {
+                visitor.visit_string(format!("{} {}", amount, currency.code()))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            FieldValue::Currency { amount, currency } => {
+                let mut seq = SeqDeserializer::<_, SerdeError>::new(
+                    [amount.to_string(), currency.code().to_string()].into_iter(),
+                );
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match (name, self.value) {
+            ("FieldValue" | "ReferenceValue", _) => {
+                self.deserialize_encoded(name, variants, visitor)
+            }
+            (_, FieldValue::String(s)) => visitor.visit_enum(s.as_str().into_deserializer()),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct tuple_struct map struct identifier ignored_any
+    }
+}
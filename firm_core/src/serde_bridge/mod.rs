@@ -0,0 +1,504 @@
+//! Serde support for reading entities into user structs and writing them back.
+//!
+//! [`from_entity`] deserializes an entity's fields as a map from field ID to value, so any struct
+//! deriving `Deserialize` with matching field names can be read from it. [`to_entity`] does the
+//! reverse for structs deriving `Serialize`.
+//!
+//! Field values map to plain Rust types where they can:
+//! - Currency values are a map of `amount` and `currency`, which fits a struct of a
+//!   [`Decimal`](crate::field::Decimal) and a [`Currency`](crate::field::Currency).
+//!   They can also be read as a `(Decimal, Currency)` tuple or a string like `"12.50 EUR"`.
+//! - Datetimes are RFC 3339 strings, which `chrono` reads into its datetime types.
+//!   Dates are `YYYY-MM-DD` strings, which read into a [`NaiveDate`](crate::field::NaiveDate).
+//! - Durations and recurrences are strings in their DSL format, like `"2h30m"` or
+//!   `"every 2 weeks"`, which read into a [`Duration`](crate::temporal::Duration) or a
+//!   [`Recurrence`](crate::temporal::Recurrence).
+//! - Quantities are strings like `"120 h"`, which read into a [`Quantity`](crate::quantity::Quantity).
+//! - Paths are strings, which read into a `PathBuf`.
+//! - Records are maps of their fields, which read into nested structs or maps.
+//! - References are their ID as a string, like `"person.john_doe"`, which read into an
+//!   [`EntityId`] or a `String`. They can also be read into a [`ReferenceValue`](crate::ReferenceValue).
+//! - Strings can be read into unit-only enums, matching the string to the variant name.
+//!
+//! Writing back, strings stay strings, [`EntityId`] and [`ReferenceValue`](crate::ReferenceValue)
+//! become references, durations, recurrences and quantities keep their types and
+//! an `amount` and `currency` map becomes a currency value. Other nested structs and maps become
+//! records. Fields set to `None` are left out. Since `chrono` writes dates as plain strings, mark
+//! them with `#[serde(with = ...)]` and the [`datetime`] or [`date`] helpers to write them as
+//! datetime or date values.
+
+mod dates;
+mod de;
+mod ser;
+
+pub use dates::{date, datetime};
+
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+use crate::{Entity, EntityId, EntityType, FieldId};
+
+/// An error reading an entity into a struct, or writing a struct to an entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerdeError {
+    pub entity_id: Option<EntityId>,
+    pub field_id: Option<FieldId>,
+    pub message: String,
+}
+
+impl SerdeError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            entity_id: None,
+            field_id: None,
+            message: message.into(),
+        }
+    }
+
+    /// Names the field the error happened in, unless a nested field was already named.
+    fn in_field(mut self, field_id: &FieldId) -> Self {
+        self.field_id.get_or_insert_with(|| field_id.clone());
+        self
+    }
+
+    /// Names the entity the error happened in.
+    fn in_entity(mut self, entity_id: &EntityId) -> Self {
+        self.entity_id = Some(entity_id.clone());
+        self
+    }
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.entity_id, &self.field_id) {
+            (Some(entity_id), Some(field_id)) => write!(
+                f,
+                "Entity '{}' field '{}': {}",
+                entity_id, field_id, self.message
+            ),
+            (Some(entity_id), None) => write!(f, "Entity '{}': {}", entity_id, self.message),
+            (None, Some(field_id)) => write!(f, "Field '{}': {}", field_id, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SerdeError {}
+
+impl serde::de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::new(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        SerdeError::new("missing required field").in_field(&FieldId::from(field))
+    }
+}
+
+impl serde::ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::new(msg.to_string())
+    }
+}
+
+/// Reads an entity's fields into a struct deriving `Deserialize`.
+pub fn from_entity<T: DeserializeOwned>(entity: &Entity) -> Result<T, SerdeError> {
+    T::deserialize(de::EntityDeserializer::new(entity)).map_err(|e| e.in_entity(&entity.id))
+}
+
+/// Writes a struct deriving `Serialize` to an entity with the given ID and type.
+pub fn to_entity<T: Serialize + ?Sized>(
+    value: &T,
+    id: EntityId,
+    entity_type: EntityType,
+) -> Result<Entity, SerdeError> {
+    let fields = ser::to_fields(value).map_err(|e| e.in_entity(&id))?;
+
+    Ok(Entity {
+        id,
+        entity_type,
+        fields: fields.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Currency, DateTime, Decimal, FixedOffset, NaiveDate};
+    use crate::quantity::Quantity;
+    use crate::temporal::{Duration, Recurrence, RecurrenceUnit};
+    use crate::typed::TypedValue;
+    use crate::{FieldValue, ReferenceValue};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Money {
+        amount: Decimal,
+        currency: Currency,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Open,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Task {
+        name: String,
+        is_completed: bool,
+        estimate_hours: Option<i64>,
+        budget: Money,
+        #[serde(with = "datetime")]
+        due_date: DateTime<FixedOffset>,
+        assignee_ref: EntityId,
+        attachment: Option<PathBuf>,
+        status: Status,
+        tags: Vec<String>,
+    }
+
+    fn due_date() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2025-03-01T09:00:00+01:00").unwrap()
+    }
+
+    fn task_entity() -> Entity {
+        Entity::new(EntityId::new("task.write_docs"), EntityType::new("task"))
+            .with_field(FieldId::new("name"), "Write docs")
+            .with_field(FieldId::new("is_completed"), false)
+            .with_field(
+                FieldId::new("budget"),
+                FieldValue::Currency {
+                    amount: Decimal::new(1250, 2),
+                    currency: Currency::EUR,
+                },
+            )
+            .with_field(FieldId::new("due_date"), FieldValue::DateTime(due_date()))
+            .with_field(
+                FieldId::new("assignee_ref"),
+                FieldValue::Reference(ReferenceValue::Entity(EntityId::new("person.john_doe"))),
+            )
+            .with_field(
+                FieldId::new("attachment"),
+                FieldValue::Path(PathBuf::from("docs/outline.md")),
+            )
+            .with_field(FieldId::new("status"), "open")
+            .with_field(
+                FieldId::new("tags"),
+                FieldValue::List(vec![
+                    FieldValue::String(String::from("docs")),
+                    FieldValue::String(String::from("writing")),
+                ]),
+            )
+    }
+
+    fn task() -> Task {
+        Task {
+            name: String::from("Write docs"),
+            is_completed: false,
+            estimate_hours: None,
+            budget: Money {
+                amount: Decimal::new(1250, 2),
+                currency: Currency::EUR,
+            },
+            due_date: due_date(),
+            assignee_ref: EntityId::new("person.john_doe"),
+            attachment: Some(PathBuf::from("docs/outline.md")),
+            status: Status::Open,
+            tags: vec![String::from("docs"), String::from("writing")],
+        }
+    }
+
+    #[test]
+    fn test_from_entity() {
+        let result: Task = from_entity(&task_entity()).unwrap();
+
+        assert_eq!(result, task());
+    }
+
+    #[test]
+    fn test_from_entity_alternative_mappings() {
+        #[derive(Debug, Deserialize)]
+        struct Summary {
+            budget: (Decimal, Currency),
+            assignee_ref: ReferenceValue,
+            due_date: String,
+            all_fields: Option<FieldValue>,
+        }
+
+        let summary: Summary = from_entity(&task_entity()).unwrap();
+
+        assert_eq!(summary.budget, (Decimal::new(1250, 2), Currency::EUR));
+        assert_eq!(
+            summary.assignee_ref,
+            ReferenceValue::Entity(EntityId::new("person.john_doe"))
+        );
+        assert_eq!(summary.due_date, "2025-03-01T09:00:00+01:00");
+        assert_eq!(summary.all_fields, None);
+
+        #[derive(Debug, Deserialize)]
+        struct Labels {
+            budget: String,
+            name: FieldValue,
+        }
+
+        let labels: Labels = from_entity(&task_entity()).unwrap();
+        assert_eq!(labels.budget, "12.50 EUR");
+        assert_eq!(labels.name, FieldValue::String(String::from("Write docs")));
+    }
+
+    #[test]
+    fn test_from_entity_errors_name_entity_and_field() {
+        #[derive(Debug, Deserialize)]
+        struct Wrong {
+            name: i64,
+        }
+
+        let error = from_entity::<Wrong>(&task_entity())
+            .map(|wrong| wrong.name)
+            .unwrap_err();
+        assert_eq!(error.entity_id, Some(EntityId::new("task.write_docs")));
+        assert_eq!(error.field_id, Some(FieldId::new("name")));
+        assert!(
+            error
+                .to_string()
+                .starts_with("Entity 'task.write_docs' field 'name': invalid type")
+        );
+
+        #[derive(Debug, Deserialize)]
+        struct Missing {
+            owner_ref: EntityId,
+        }
+
+        let error = from_entity::<Missing>(&task_entity())
+            .map(|missing| missing.owner_ref)
+            .unwrap_err();
+        assert_eq!(error.field_id, Some(FieldId::new("owner_ref")));
+        assert_eq!(
+            error.to_string(),
+            "Entity 'task.write_docs' field 'owner_ref': missing required field"
+        );
+    }
+
+    #[test]
+    fn test_to_entity_round_trip() {
+        let entity = to_entity(
+            &task(),
+            EntityId::new("task.write_docs"),
+            EntityType::new("task"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            entity.get_field(&FieldId::new("due_date")),
+            Some(&FieldValue::DateTime(due_date()))
+        );
+        assert_eq!(
+            entity.get_field(&FieldId::new("assignee_ref")),
+            Some(&FieldValue::Reference(ReferenceValue::Entity(
+                EntityId::new("person.john_doe")
+            )))
+        );
+        assert_eq!(
+            entity.get_field(&FieldId::new("budget")),
+            Some(&FieldValue::Currency {
+                amount: Decimal::new(1250, 2),
+                currency: Currency::EUR,
+            })
+        );
+        assert_eq!(entity.get_field(&FieldId::new("estimate_hours")), None);
+
+        let round_trip: Task = from_entity(&entity).unwrap();
+        assert_eq!(round_trip, task());
+    }
+
+    #[test]
+    fn test_strings_and_marked_dates_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Note {
+            title: String,
+            #[serde(with = "date")]
+            written_on: NaiveDate,
+            #[serde(default, with = "datetime::option")]
+            reviewed_at: Option<DateTime<FixedOffset>>,
+            #[serde(default, with = "date::option")]
+            archived_on: Option<NaiveDate>,
+        }
+
+        let note = Note {
+            title: String::from("2025-01-01"),
+            written_on: NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+            reviewed_at: Some(due_date()),
+            archived_on: None,
+        };
+        let entity = to_entity(
+            &note,
+            EntityId::new("note.new_year"),
+            EntityType::new("note"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            entity.fields,
+            vec![
+                (FieldId::new("title"), FieldValue::from("2025-01-01")),
+                (
+                    FieldId::new("written_on"),
+                    FieldValue::Date(note.written_on)
+                ),
+                (
+                    FieldId::new("reviewed_at"),
+                    FieldValue::DateTime(due_date())
+                ),
+            ]
+        );
+        assert_eq!(from_entity::<Note>(&entity).unwrap(), note);
+
+        // Field values keep their own type, whether they hold dates or strings that look like them
+        let values = to_entity(
+            &BTreeMap::from([
+                ("due", FieldValue::DateTime(due_date())),
+                ("label", FieldValue::from("2025-03-01")),
+            ]),
+            EntityId::new("note.values"),
+            EntityType::new("note"),
+        )
+        .unwrap();
+        assert_eq!(
+            values.get_field(&FieldId::new("due")),
+            Some(&FieldValue::DateTime(due_date()))
+        );
+        assert_eq!(
+            values.get_field(&FieldId::new("label")),
+            Some(&FieldValue::from("2025-03-01"))
+        );
+    }
+
+    #[test]
+    fn test_durations_and_recurrences_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Retainer {
+            term: Duration,
+            invoicing: Recurrence,
+        }
+
+        let retainer = Retainer {
+            term: Duration::from_weeks(12),
+            invoicing: Recurrence::new(1, RecurrenceUnit::Month),
+        };
+        let entity = to_entity(
+            &retainer,
+            EntityId::new("retainer.acme"),
+            EntityType::new("retainer"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            entity.fields,
+            vec![
+                (FieldId::new("term"), FieldValue::Duration(retainer.term)),
+                (
+                    FieldId::new("invoicing"),
+                    FieldValue::Recurrence(retainer.invoicing)
+                ),
+            ]
+        );
+        assert_eq!(from_entity::<Retainer>(&entity).unwrap(), retainer);
+    }
+
+    #[test]
+    fn test_quantities_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Plan {
+            storage: Quantity,
+        }
+
+        let plan = Plan {
+            storage: Quantity::new(5.0, "TB"),
+        };
+        let entity = to_entity(&plan, EntityId::new("plan.pro"), EntityType::new("plan")).unwrap();
+
+        assert_eq!(
+            entity.fields,
+            vec![(
+                FieldId::new("storage"),
+                FieldValue::Quantity(plan.storage.clone())
+            )]
+        );
+        assert_eq!(from_entity::<Plan>(&entity).unwrap(), plan);
+    }
+
+    #[test]
+    fn test_nested_structs_round_trip_as_records() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Address {
+            street: String,
+            city: String,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Organization {
+            name: String,
+            address: Address,
+        }
+
+        let organization = Organization {
+            name: String::from("Acme"),
+            address: Address {
+                street: String::from("Main Street 1"),
+                city: String::from("Oslo"),
+            },
+        };
+        let entity = to_entity(
+            &organization,
+            EntityId::new("organization.acme"),
+            EntityType::new("organization"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            entity.get_field(&FieldId::new("address")),
+            Some(&FieldValue::Record(vec![
+                (FieldId::new("street"), FieldValue::from("Main Street 1")),
+                (FieldId::new("city"), FieldValue::from("Oslo")),
+            ]))
+        );
+        assert_eq!(from_entity::<Organization>(&entity).unwrap(), organization);
+    }
+
+    #[test]
+    fn test_to_entity_keeps_field_values() {
+        #[derive(Serialize)]
+        struct Link {
+            target: ReferenceValue,
+            location: FieldValue,
+            note: FieldValue,
+        }
+
+        let link = Link {
+            target: ReferenceValue::Field(EntityId::new("person.john_doe"), FieldId::new("email")),
+            location: FieldValue::Path(PathBuf::from("notes/john.md")),
+            note: FieldValue::String(String::from("2025-03-01T09:00:00+01:00")),
+        };
+        let entity = to_entity(&link, EntityId::new("link.john"), EntityType::new("link")).unwrap();
+
+        assert_eq!(
+            entity.fields,
+            vec![
+                (FieldId::new("target"), link.target.to_value()),
+                (FieldId::new("location"), link.location.clone()),
+                (FieldId::new("note"), link.note.clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_entity_requires_struct() {
+        let error =
+            to_entity(&42, EntityId::new("task.answer"), EntityType::new("task")).unwrap_err();
+
+        assert_eq!(error.entity_id, Some(EntityId::new("task.answer")));
+        assert_eq!(error.field_id, None);
+    }
+}
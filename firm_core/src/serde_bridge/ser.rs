@@ -0,0 +1,491 @@
+use serde::ser::{self, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::SerdeError;
+use super::dates::{DATE_NAME, DATETIME_NAME};
+use crate::field::{Currency, DateTime, Decimal, NaiveDate};
+use crate::{EntityId, FieldId, FieldValue, ReferenceValue};
+
+/// Serializes a struct or map to entity fields.
+pub(super) fn to_fields<T: Serialize + ?Sized>(
+    value: &T,
+) -> Result<Vec<(FieldId, FieldValue)>, SerdeError> {
+    match value.serialize(ValueSerializer)? {
+        Serialized::Fields(fields) => Ok(fields),
+        _ => Err(ser::Error::custom("expected a struct or map")),
+    }
+}
+
+/// The result of serializing a value: nothing, a field value or the fields of a struct.
+enum Serialized {
+    Empty,
+    Value(FieldValue),
+    Fields(Vec<(FieldId, FieldValue)>),
+}
+
+impl Serialized {
+    /// Gets the field value, treating nested fields as a currency value if they're an
+    /// `amount` and `currency`, or as a record otherwise.
+    fn into_value(self) -> Result<Option<FieldValue>, SerdeError> {
+        match self {
+            Serialized::Empty => Ok(None),
+            Serialized::Value(value) => Ok(Some(value)),
+            Serialized::Fields(fields) if is_currency(&fields) => {
+                currency_from_fields(fields).map(Some)
+            }
+            Serialized::Fields(fields) => Ok(Some(FieldValue::Record(fields))),
+        }
+    }
+}
+
+/// Checks if nested fields are the `amount` and `currency` of a currency value.
+fn is_currency(fields: &[(FieldId, FieldValue)]) -> bool {
+    let mut names: Vec<&str> = fields
+        .iter()
+        .map(|(field_id, _)| field_id.as_str())
+        .collect();
+    names.sort();
+    names == ["amount", "currency"]
+}
+
+/// Builds a currency value from the fields of an `amount` and `currency` struct.
+fn currency_from_fields(fields: Vec<(FieldId, FieldValue)>) -> Result<FieldValue, SerdeError> {
+    let mut amount = None;
+    let mut currency = None;
+
+    for (field_id, value) in fields {
+        match (field_id.as_str(), value) {
+            ("amount", FieldValue::String(s)) => amount = Decimal::from_str(&s).ok(),
+            ("amount", FieldValue::Integer(i)) => amount = Some(Decimal::from(i)),
+            ("amount", FieldValue::Float(f)) => amount = Decimal::try_from(f).ok(),
+            ("currency", FieldValue::String(code)) => currency = Currency::from_code(&code),
+            _ => {}
+        }
+    }
+
+    match (amount, currency) {
+        (Some(amount), Some(currency)) => Ok(FieldValue::Currency { amount, currency }),
+        _ => Err(ser::Error::custom(
+            "expected a decimal amount and a currency code",
+        )),
+    }
+}
+
+/// Reads a datetime value from an RFC 3339 string.
+fn datetime_value(raw: &str) -> Result<FieldValue, SerdeError> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(FieldValue::DateTime)
+        .map_err(ser::Error::custom)
+}
+
+/// Reads a date value from a `YYYY-MM-DD` string.
+fn date_value(raw: &str) -> Result<FieldValue, SerdeError> {
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map(FieldValue::Date)
+        .map_err(ser::Error::custom)
+}
+
+/// Serializes a value to the closest field value.
+#[derive(Clone, Copy)]
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = FieldsSerializer;
+    type SerializeStruct = FieldsSerializer;
+    type SerializeStructVariant = FieldsSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::Boolean(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::Integer(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        let v = i64::try_from(v).map_err(|_| ser::Error::custom("integer is too large"))?;
+        self.serialize_i64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::Float(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::String(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::String(v.to_string())))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(ser::Error::custom("bytes are not supported"))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Empty)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Empty)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Empty)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Value(FieldValue::String(variant.to_string())))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        // Entity IDs, durations, recurrences, quantities and marked dates serialize as strings
+        // wrapped in a named newtype
+        if !matches!(
+            name,
+            "EntityId" | "Duration" | "Recurrence" | "Quantity" | DATETIME_NAME | DATE_NAME
+        ) {
+            return value.serialize(self);
+        }
+
+        let Serialized::Value(FieldValue::String(raw)) = value.serialize(self)? else {
+            return Err(ser::Error::custom(format!("expected a {} string", name)));
+        };
+
+        let value = match name {
+            "EntityId" => FieldValue::Reference(ReferenceValue::Entity(EntityId::from(raw))),
+            "Duration" => FieldValue::Duration(raw.parse().map_err(ser::Error::custom)?),
+            "Quantity" => FieldValue::Quantity(raw.parse().map_err(ser::Error::custom)?),
+            DATETIME_NAME => datetime_value(&raw)?,
+            DATE_NAME => date_value(&raw)?,
+            _ => FieldValue::Recurrence(raw.parse().map_err(ser::Error::custom)?),
+        };
+
+        Ok(Serialized::Value(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        match (name, variant) {
+            ("FieldValue", "Record") => match value.serialize(self)? {
+                Serialized::Fields(fields) => Ok(Serialized::Value(FieldValue::Record(fields))),
+                _ => Err(ser::Error::custom("expected record fields")),
+            },
+            ("FieldValue", "Path") => match value.serialize(self)? {
+                Serialized::Value(FieldValue::String(path)) => {
+                    Ok(Serialized::Value(FieldValue::Path(PathBuf::from(path))))
+                }
+                _ => Err(ser::Error::custom("expected a path")),
+            },
+            ("FieldValue", "DateTime") => match value.serialize(self)? {
+                Serialized::Value(FieldValue::String(raw)) => {
+                    datetime_value(&raw).map(Serialized::Value)
+                }
+                _ => Err(ser::Error::custom("expected a datetime")),
+            },
+            ("FieldValue", "Date") => match value.serialize(self)? {
+                Serialized::Value(FieldValue::String(raw)) => {
+                    date_value(&raw).map(Serialized::Value)
+                }
+                _ => Err(ser::Error::custom("expected a date")),
+            },
+            ("FieldValue", _) | ("ReferenceValue", "Entity") => value.serialize(self),
+            _ => Err(ser::Error::custom(format!(
+                "enum variant '{}' holds data, which is not supported",
+                variant
+            ))),
+        }
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ListSerializer::default())
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        match (name, variant) {
+            ("ReferenceValue", "Field") => Ok(ListSerializer {
+                items: Vec::new(),
+                field_reference: true,
+            }),
+            _ => Err(ser::Error::custom(format!(
+                "enum variant '{}' holds data, which is not supported",
+                variant
+            ))),
+        }
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(FieldsSerializer::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(FieldsSerializer::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        match (name, variant) {
+            ("FieldValue", "Currency") => Ok(FieldsSerializer::default()),
+            _ => Err(ser::Error::custom(format!(
+                "enum variant '{}' holds data, which is not supported",
+                variant
+            ))),
+        }
+    }
+}
+
+/// Collects the items of a sequence into a list value, or the parts of a field reference.
+#[derive(Default)]
+struct ListSerializer {
+    items: Vec<FieldValue>,
+    field_reference: bool,
+}
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        if let Some(item) = value.serialize(ValueSerializer)?.into_value()? {
+            self.items.push(item);
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Serialized, SerdeError> {
+        if !self.field_reference {
+            return Ok(Serialized::Value(FieldValue::List(self.items)));
+        }
+
+        match <[FieldValue; 2]>::try_from(self.items) {
+            Ok(
+                [
+                    FieldValue::Reference(ReferenceValue::Entity(entity_id)),
+                    FieldValue::String(field_id),
+                ],
+            ) => Ok(Serialized::Value(FieldValue::Reference(
+                ReferenceValue::Field(entity_id, FieldId::from(field_id)),
+            ))),
+            _ => Err(ser::Error::custom("expected an entity ID and a field ID")),
+        }
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+/// Collects the fields of a struct or map, naming the field in errors.
+#[derive(Default)]
+struct FieldsSerializer {
+    fields: Vec<(FieldId, FieldValue)>,
+    next_field: Option<FieldId>,
+}
+
+impl FieldsSerializer {
+    fn push<T: Serialize + ?Sized>(
+        &mut self,
+        field_id: FieldId,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        let value = value
+            .serialize(ValueSerializer)
+            .and_then(Serialized::into_value)
+            .map_err(|e| e.in_field(&field_id))?;
+
+        if let Some(value) = value {
+            self.fields.push((field_id, value));
+        }
+
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for FieldsSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        match key.serialize(ValueSerializer)? {
+            Serialized::Value(FieldValue::String(field_id)) => {
+                self.next_field = Some(FieldId::from(field_id));
+                Ok(())
+            }
+            _ => Err(ser::Error::custom("field names must be strings")),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let field_id = self
+            .next_field
+            .take()
+            .ok_or_else(|| ser::Error::custom("value serialized before its field name"))?;
+
+        self.push(field_id, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Fields(self.fields))
+    }
+}
+
+impl ser::SerializeStruct for FieldsSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(FieldId::from(key), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Fields(self.fields))
+    }
+}
+
+impl ser::SerializeStructVariant for FieldsSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(FieldId::from(key), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Serialized::Fields(self.fields))
+    }
+}
@@ -0,0 +1,94 @@
+//! Suggestions for misspelled names, based on edit distance.
+
+/// Counts the single-character insertions, deletions and substitutions needed to turn one string into another.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = (previous[j] + substitution_cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
+}
+
+/// Finds the candidate closest to the input, if any is close enough to be a likely typo.
+///
+/// A candidate is close enough if it's within a third of the input's length in edits (and at least one edit).
+/// Ties are resolved in favour of the first candidate.
+pub fn closest_match<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    ranked_matches(input, candidates, 1).into_iter().next()
+}
+
+/// Finds up to `limit` candidates close enough to the input to be likely typos, closest first.
+///
+/// Uses the same notion of close enough as [`closest_match`], and keeps the candidate order for ties.
+pub fn ranked_matches<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(1);
+
+    let mut matches: Vec<(&str, usize)> = candidates
+        .into_iter()
+        .map(|candidate| (candidate, edit_distance(input, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect();
+    matches.sort_by_key(|(_, distance)| *distance);
+
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(candidate, _)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("asignee_ref", "assignee_ref"), 1);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["assignee_ref", "source_ref", "due_date"];
+
+        assert_eq!(
+            closest_match("asignee_ref", candidates),
+            Some("assignee_ref")
+        );
+        assert_eq!(closest_match("priority", candidates), None);
+    }
+
+    #[test]
+    fn test_ranked_matches() {
+        let candidates = ["john_doe", "jane_doe", "jon_dow", "priority"];
+
+        assert_eq!(
+            ranked_matches("jon_doe", candidates, 5),
+            vec!["john_doe", "jon_dow", "jane_doe"]
+        );
+        assert_eq!(ranked_matches("jon_doe", candidates, 1), vec!["john_doe"]);
+        assert!(ranked_matches("invoice", candidates, 5).is_empty());
+    }
+}
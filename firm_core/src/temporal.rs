@@ -0,0 +1,399 @@
+//! Durations and recurrence rules, with the date arithmetic that goes with them.
+//!
+//! Durations are written as a sequence of amounts and units, like `3d` or `2h30m`.
+//! Recurrence rules are written as `every week` or `every 2 months`.
+
+use chrono::{DateTime, Days, FixedOffset, Months, NaiveDate, TimeDelta};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+const MINUTES_PER_HOUR: i64 = 60;
+const MINUTES_PER_DAY: i64 = 24 * MINUTES_PER_HOUR;
+const MINUTES_PER_WEEK: i64 = 7 * MINUTES_PER_DAY;
+
+/// The units a duration can be written in, from largest to smallest.
+const DURATION_UNITS: [(char, i64); 4] = [
+    ('w', MINUTES_PER_WEEK),
+    ('d', MINUTES_PER_DAY),
+    ('h', MINUTES_PER_HOUR),
+    ('m', 1),
+];
+
+/// A length of time, with minute precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    minutes: i64,
+}
+
+impl Duration {
+    /// Creates a duration from a number of minutes.
+    pub fn from_minutes(minutes: i64) -> Self {
+        Self { minutes }
+    }
+
+    /// Creates a duration from a number of hours.
+    pub fn from_hours(hours: i64) -> Self {
+        Self::from_minutes(hours * MINUTES_PER_HOUR)
+    }
+
+    /// Creates a duration from a number of days.
+    pub fn from_days(days: i64) -> Self {
+        Self::from_minutes(days * MINUTES_PER_DAY)
+    }
+
+    /// Creates a duration from a number of weeks.
+    pub fn from_weeks(weeks: i64) -> Self {
+        Self::from_minutes(weeks * MINUTES_PER_WEEK)
+    }
+
+    /// Gets the total number of minutes in the duration.
+    pub fn minutes(&self) -> i64 {
+        self.minutes
+    }
+
+    /// Gets the number of whole days in the duration.
+    pub fn whole_days(&self) -> i64 {
+        self.minutes / MINUTES_PER_DAY
+    }
+
+    /// Converts the duration to a chrono `TimeDelta`.
+    pub fn to_time_delta(&self) -> TimeDelta {
+        TimeDelta::minutes(self.minutes)
+    }
+
+    /// Adds the duration to a datetime.
+    pub fn add_to_datetime(
+        &self,
+        datetime: DateTime<FixedOffset>,
+    ) -> Option<DateTime<FixedOffset>> {
+        datetime.checked_add_signed(self.to_time_delta())
+    }
+
+    /// Adds the whole days of the duration to a date. Hours and minutes are ignored.
+    pub fn add_to_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        date.checked_add_signed(TimeDelta::days(self.whole_days()))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "0m");
+        }
+
+        let mut remaining = self.minutes;
+        for (unit, minutes) in DURATION_UNITS {
+            let amount = remaining / minutes;
+            if amount != 0 {
+                write!(f, "{}{}", amount, unit)?;
+                remaining -= amount * minutes;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Duration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid duration: '{}' (expected e.g. '3d' or '2h30m')", s);
+
+        let mut minutes = 0i64;
+        let mut amount = String::new();
+        let mut last_unit = None;
+        for c in s.trim().chars() {
+            if c.is_ascii_digit() {
+                amount.push(c);
+                continue;
+            }
+
+            // Units must come in order from largest to smallest, each at most once
+            let unit_index = DURATION_UNITS
+                .iter()
+                .position(|(unit, _)| *unit == c)
+                .ok_or_else(error)?;
+            if amount.is_empty() || last_unit.is_some_and(|last| unit_index <= last) {
+                return Err(error());
+            }
+
+            let value: i64 = amount.parse().map_err(|_| error())?;
+            minutes = value
+                .checked_mul(DURATION_UNITS[unit_index].1)
+                .and_then(|unit_minutes| minutes.checked_add(unit_minutes))
+                .ok_or_else(error)?;
+            amount.clear();
+            last_unit = Some(unit_index);
+        }
+
+        if !amount.is_empty() || last_unit.is_none() {
+            return Err(error());
+        }
+
+        Ok(Duration::from_minutes(minutes))
+    }
+}
+
+/// Durations are serialized in their DSL format, like `2h30m`.
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Duration", &self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The unit of time a recurrence repeats in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl RecurrenceUnit {
+    fn name(&self) -> &'static str {
+        match self {
+            RecurrenceUnit::Day => "day",
+            RecurrenceUnit::Week => "week",
+            RecurrenceUnit::Month => "month",
+            RecurrenceUnit::Year => "year",
+        }
+    }
+}
+
+/// A rule for something which repeats at a regular interval, like `every 2 weeks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Recurrence {
+    pub interval: u32,
+    pub unit: RecurrenceUnit,
+}
+
+impl Recurrence {
+    /// Creates a recurrence which repeats every `interval` units.
+    pub fn new(interval: u32, unit: RecurrenceUnit) -> Self {
+        Self { interval, unit }
+    }
+
+    /// Gets the date `count` intervals after the start date.
+    pub fn nth_occurrence(&self, start: NaiveDate, count: u32) -> Option<NaiveDate> {
+        let steps = self.interval.checked_mul(count)?;
+        match self.unit {
+            RecurrenceUnit::Day => start.checked_add_days(Days::new(steps.into())),
+            RecurrenceUnit::Week => start.checked_add_days(Days::new(u64::from(steps) * 7)),
+            RecurrenceUnit::Month => start.checked_add_months(Months::new(steps)),
+            RecurrenceUnit::Year => start.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// Gets the first occurrence after a date, for a recurrence which started on `start`.
+    ///
+    /// Occurrences are counted from the start date, so monthly recurrences starting on the 31st
+    /// fall on the last day of shorter months without drifting.
+    pub fn next_occurrence_after(&self, start: NaiveDate, after: NaiveDate) -> Option<NaiveDate> {
+        if start > after {
+            return Some(start);
+        }
+        if self.interval == 0 {
+            return None;
+        }
+
+        // Estimate how many intervals have passed, then step forward from there
+        let days = (after - start).num_days();
+        let approximate_days = match self.unit {
+            RecurrenceUnit::Day => 1,
+            RecurrenceUnit::Week => 7,
+            RecurrenceUnit::Month => 31,
+            RecurrenceUnit::Year => 366,
+        } * i64::from(self.interval);
+        let mut count = u32::try_from(days / approximate_days).ok()?;
+
+        loop {
+            let occurrence = self.nth_occurrence(start, count)?;
+            if occurrence > after {
+                return Some(occurrence);
+            }
+            count = count.checked_add(1)?;
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.interval {
+            1 => write!(f, "every {}", self.unit.name()),
+            interval => write!(f, "every {} {}s", interval, self.unit.name()),
+        }
+    }
+}
+
+impl std::str::FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Invalid recurrence: '{}' (expected e.g. 'every week' or 'every 2 months')",
+                s
+            )
+        };
+
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let (interval, unit) = match parts.as_slice() {
+            ["every", unit] => (1, *unit),
+            ["every", interval, unit] => (interval.parse().map_err(|_| error())?, *unit),
+            _ => return Err(error()),
+        };
+
+        let unit = match unit {
+            "day" | "days" => RecurrenceUnit::Day,
+            "week" | "weeks" => RecurrenceUnit::Week,
+            "month" | "months" => RecurrenceUnit::Month,
+            "year" | "years" => RecurrenceUnit::Year,
+            _ => return Err(error()),
+        };
+
+        if interval == 0 {
+            return Err(error());
+        }
+
+        Ok(Recurrence::new(interval, unit))
+    }
+}
+
+/// Recurrences are serialized in their DSL format, like `every 2 weeks`.
+impl Serialize for Recurrence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Recurrence", &self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!("3d".parse(), Ok(Duration::from_days(3)));
+        assert_eq!("2h30m".parse(), Ok(Duration::from_minutes(2 * 60 + 30)));
+        assert_eq!("1w2d".parse(), Ok(Duration::from_days(9)));
+
+        assert!("".parse::<Duration>().is_err());
+        assert!("3".parse::<Duration>().is_err());
+        assert!("30m2h".parse::<Duration>().is_err());
+        assert!("2x".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    fn test_display_duration() {
+        assert_eq!(Duration::from_minutes(150).to_string(), "2h30m");
+        assert_eq!(Duration::from_days(9).to_string(), "1w2d");
+        assert_eq!(Duration::from_minutes(0).to_string(), "0m");
+    }
+
+    #[test]
+    fn test_add_duration() {
+        let duration: Duration = "1d12h".parse().unwrap();
+        assert_eq!(
+            duration.add_to_date(date(2025, 2, 28)),
+            Some(date(2025, 3, 1))
+        );
+
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let start = offset.with_ymd_and_hms(2025, 2, 28, 18, 0, 0).unwrap();
+        assert_eq!(
+            duration.add_to_datetime(start),
+            Some(offset.with_ymd_and_hms(2025, 3, 2, 6, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_recurrence() {
+        assert_eq!(
+            "every week".parse(),
+            Ok(Recurrence::new(1, RecurrenceUnit::Week))
+        );
+        assert_eq!(
+            "every 2 months".parse(),
+            Ok(Recurrence::new(2, RecurrenceUnit::Month))
+        );
+        assert_eq!(
+            Recurrence::new(2, RecurrenceUnit::Week).to_string(),
+            "every 2 weeks"
+        );
+
+        assert!("every".parse::<Recurrence>().is_err());
+        assert!("every 0 days".parse::<Recurrence>().is_err());
+        assert!("each week".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn test_next_occurrence_after() {
+        let biweekly = Recurrence::new(2, RecurrenceUnit::Week);
+        let start = date(2025, 1, 6);
+        assert_eq!(
+            biweekly.next_occurrence_after(start, date(2025, 1, 1)),
+            Some(start)
+        );
+        assert_eq!(
+            biweekly.next_occurrence_after(start, start),
+            Some(date(2025, 1, 20))
+        );
+        assert_eq!(
+            biweekly.next_occurrence_after(start, date(2025, 3, 4)),
+            Some(date(2025, 3, 17))
+        );
+
+        // Months are counted from the start date, so the day doesn't drift
+        let monthly = Recurrence::new(1, RecurrenceUnit::Month);
+        let end_of_month = date(2025, 1, 31);
+        assert_eq!(
+            monthly.next_occurrence_after(end_of_month, date(2025, 2, 1)),
+            Some(date(2025, 2, 28))
+        );
+        assert_eq!(
+            monthly.next_occurrence_after(end_of_month, date(2025, 3, 1)),
+            Some(date(2025, 3, 31))
+        );
+    }
+
+    #[test]
+    fn test_temporal_serialization() {
+        let duration: Duration = "2h30m".parse().unwrap();
+        let serialized = serde_json::to_string(&duration).unwrap();
+        assert_eq!(serialized, r#""2h30m""#);
+        assert_eq!(
+            serde_json::from_str::<Duration>(&serialized).unwrap(),
+            duration
+        );
+
+        let recurrence = Recurrence::new(2, RecurrenceUnit::Week);
+        let serialized = serde_json::to_string(&recurrence).unwrap();
+        assert_eq!(serialized, r#""every 2 weeks""#);
+        assert_eq!(
+            serde_json::from_str::<Recurrence>(&serialized).unwrap(),
+            recurrence
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! Conversions between field values and plain Rust types.
+//!
+//! Typed entity structs, like those generated from workspace schemas, use these to read fields
+//! from an [`Entity`] and write them back, without matching on [`FieldValue`] themselves.
+
+// The helpers hand back the same `ValidationError` that schema validation reports.
+#![allow(clippy::result_large_err)]
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use iso_currency::Currency;
+use rust_decimal::Decimal;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use crate::quantity::Quantity;
+use crate::schema::{FieldConstraints, ValidationError};
+use crate::temporal::{Duration, Recurrence};
+use crate::{
+    Entity, EntityId, EntitySchema, EntityType, FieldId, FieldType, FieldValue, ReferenceValue,
+};
+
+/// A Rust type which holds the value of fields of one field type.
+pub trait TypedValue: Sized {
+    /// The field type this Rust type holds.
+    const FIELD_TYPE: FieldType;
+
+    /// Gets the Rust value from a field value, if it has the right type.
+    fn from_value(value: &FieldValue) -> Option<Self>;
+
+    /// Converts the Rust value to a field value.
+    fn to_value(&self) -> FieldValue;
+}
+
+impl TypedValue for bool {
+    const FIELD_TYPE: FieldType = FieldType::Boolean;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Boolean(*self)
+    }
+}
+
+impl TypedValue for String {
+    const FIELD_TYPE: FieldType = FieldType::String;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::String(self.clone())
+    }
+}
+
+impl TypedValue for i64 {
+    const FIELD_TYPE: FieldType = FieldType::Integer;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Integer(*self)
+    }
+}
+
+impl TypedValue for f64 {
+    const FIELD_TYPE: FieldType = FieldType::Float;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Float(*self)
+    }
+}
+
+/// Currency values are held as their amount and currency.
+impl TypedValue for (Decimal, Currency) {
+    const FIELD_TYPE: FieldType = FieldType::Currency;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Currency { amount, currency } => Some((*amount, *currency)),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Currency {
+            amount: self.0,
+            currency: self.1,
+        }
+    }
+}
+
+impl TypedValue for ReferenceValue {
+    const FIELD_TYPE: FieldType = FieldType::Reference;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Reference(reference) => Some(reference.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Reference(self.clone())
+    }
+}
+
+/// Entity IDs hold references to whole entities.
+impl TypedValue for EntityId {
+    const FIELD_TYPE: FieldType = FieldType::Reference;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Reference(ReferenceValue::Entity(entity_id)) => Some(entity_id.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Reference(ReferenceValue::Entity(self.clone()))
+    }
+}
+
+/// A reference to an entity held by the typed struct `T`, like `EntityRef<Person>`.
+///
+/// The target type only exists at compile time, so references to different types can't be mixed up.
+/// Converting from a field doesn't check the type of the referenced entity, which is left to
+/// [`check_reference_target`].
+pub struct EntityRef<T> {
+    pub id: EntityId,
+    target: PhantomData<fn() -> T>,
+}
+
+impl<T> EntityRef<T> {
+    /// Creates a reference to the entity with an ID.
+    pub fn new(id: EntityId) -> Self {
+        Self {
+            id,
+            target: PhantomData,
+        }
+    }
+}
+
+// Implemented by hand, so they don't require the target struct to implement them too
+impl<T> Clone for EntityRef<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for EntityRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for EntityRef<T> {}
+
+impl<T> Hash for EntityRef<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for EntityRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EntityRef").field(&self.id).finish()
+    }
+}
+
+impl<T> TypedValue for EntityRef<T> {
+    const FIELD_TYPE: FieldType = FieldType::Reference;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        EntityId::from_value(value).map(Self::new)
+    }
+
+    fn to_value(&self) -> FieldValue {
+        self.id.to_value()
+    }
+}
+
+impl TypedValue for Vec<FieldValue> {
+    const FIELD_TYPE: FieldType = FieldType::List;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::List(items) => Some(items.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::List(self.clone())
+    }
+}
+
+impl TypedValue for DateTime<FixedOffset> {
+    const FIELD_TYPE: FieldType = FieldType::DateTime;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::DateTime(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::DateTime(*self)
+    }
+}
+
+impl TypedValue for NaiveDate {
+    const FIELD_TYPE: FieldType = FieldType::Date;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Date(date) => Some(*date),
+            FieldValue::DateTime(datetime) => Some(datetime.date_naive()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Date(*self)
+    }
+}
+
+impl TypedValue for Vec<(FieldId, FieldValue)> {
+    const FIELD_TYPE: FieldType = FieldType::Record;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Record(fields) => Some(fields.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Record(self.clone())
+    }
+}
+
+impl TypedValue for Duration {
+    const FIELD_TYPE: FieldType = FieldType::Duration;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Duration(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Duration(*self)
+    }
+}
+
+impl TypedValue for Quantity {
+    const FIELD_TYPE: FieldType = FieldType::Quantity;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Quantity(quantity) => Some(quantity.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Quantity(self.clone())
+    }
+}
+
+impl TypedValue for Recurrence {
+    const FIELD_TYPE: FieldType = FieldType::Recurrence;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Recurrence(recurrence) => Some(*recurrence),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Recurrence(*self)
+    }
+}
+
+impl TypedValue for PathBuf {
+    const FIELD_TYPE: FieldType = FieldType::Path;
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Path(path) => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Path(self.clone())
+    }
+}
+
+/// A Rust struct which holds entities of one type, like those deriving `FirmEntity`.
+pub trait TypedEntity {
+    /// The entity type this struct holds.
+    const ENTITY_TYPE: &'static str;
+
+    /// Gets the schema of entities this struct holds.
+    fn schema() -> EntitySchema;
+}
+
+/// Checks that an entity has the expected type before converting it to a typed struct.
+pub fn check_entity_type(entity: &Entity, expected: &EntityType) -> Result<(), ValidationError> {
+    if &entity.entity_type != expected {
+        return Err(ValidationError::mismatched_entity_type(
+            &entity.id,
+            expected,
+            &entity.entity_type,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a reference field, if the entity has it, points to an entity of the target type.
+pub fn check_reference_target(
+    entity: &Entity,
+    field_id: &FieldId,
+    target_type: &EntityType,
+) -> Result<(), ValidationError> {
+    let Some(value) = entity.get_field(field_id) else {
+        return Ok(());
+    };
+
+    let constraints = FieldConstraints {
+        references: Some(target_type.clone()),
+        ..Default::default()
+    };
+
+    match constraints
+        .check(&entity.id, field_id, value)
+        .into_iter()
+        .next()
+    {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Gets the value of a field the entity must have.
+pub fn required_field<T: TypedValue>(
+    entity: &Entity,
+    field_id: &FieldId,
+) -> Result<T, ValidationError> {
+    optional_field(entity, field_id)?
+        .ok_or_else(|| ValidationError::missing_field(&entity.id, field_id))
+}
+
+/// Gets the value of a field the entity may have.
+pub fn optional_field<T: TypedValue>(
+    entity: &Entity,
+    field_id: &FieldId,
+) -> Result<Option<T>, ValidationError> {
+    let Some(value) = entity.get_field(field_id) else {
+        return Ok(None);
+    };
+
+    T::from_value(value).map(Some).ok_or_else(|| {
+        ValidationError::mismatched_field_type(
+            &entity.id,
+            field_id,
+            &T::FIELD_TYPE,
+            &value.get_type(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::ValidationErrorType;
+    use assert_matches::assert_matches;
+
+    fn task() -> Entity {
+        Entity::new(EntityId::new("task.write_docs"), EntityType::new("task"))
+            .with_field(FieldId::new("name"), "Write docs")
+            .with_field(FieldId::new("is_completed"), false)
+    }
+
+    #[test]
+    fn test_required_and_optional_fields() {
+        let entity = task();
+
+        let name: String = required_field(&entity, &FieldId::new("name")).unwrap();
+        assert_eq!(name, "Write docs");
+
+        let due_date: Option<DateTime<FixedOffset>> =
+            optional_field(&entity, &FieldId::new("due_date")).unwrap();
+        assert_eq!(due_date, None);
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        let result: Result<String, _> = required_field(&task(), &FieldId::new("assignee"));
+
+        assert_matches!(
+            result.unwrap_err().error_type,
+            ValidationErrorType::MissingRequiredField { .. }
+        );
+    }
+
+    #[test]
+    fn test_mismatched_field_type() {
+        let result: Result<Option<i64>, _> = optional_field(&task(), &FieldId::new("name"));
+
+        assert_matches!(
+            result.unwrap_err().error_type,
+            ValidationErrorType::MismatchedFieldType {
+                expected: FieldType::Integer,
+                actual: FieldType::String,
+            }
+        );
+    }
+
+    #[test]
+    fn test_typed_value_round_trip() {
+        let money = (Decimal::new(1250, 2), Currency::EUR);
+        assert_eq!(
+            <(Decimal, Currency)>::from_value(&money.to_value()),
+            Some(money)
+        );
+
+        let reference = ReferenceValue::Entity(EntityId::new("person.john_doe"));
+        assert_eq!(
+            ReferenceValue::from_value(&reference.to_value()),
+            Some(reference)
+        );
+
+        let entity_id = EntityId::new("person.john_doe");
+        assert_eq!(EntityId::from_value(&entity_id.to_value()), Some(entity_id));
+    }
+
+    #[test]
+    fn test_entity_ref_round_trip() {
+        struct Person;
+
+        let person: EntityRef<Person> = EntityRef::new(EntityId::new("person.john_doe"));
+        assert_eq!(
+            EntityRef::<Person>::from_value(&person.to_value()),
+            Some(person.clone())
+        );
+
+        let field = FieldValue::Reference(ReferenceValue::Field(
+            EntityId::new("person.john_doe"),
+            FieldId::new("email"),
+        ));
+        assert_eq!(EntityRef::<Person>::from_value(&field), None);
+    }
+
+    #[test]
+    fn test_check_reference_target() {
+        let entity = task().with_field(
+            FieldId::new("assignee_ref"),
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::new("person.john_doe"))),
+        );
+        let assignee = FieldId::new("assignee_ref");
+
+        assert!(check_reference_target(&entity, &assignee, &EntityType::new("person")).is_ok());
+        assert_matches!(
+            check_reference_target(&entity, &assignee, &EntityType::new("organization"))
+                .unwrap_err()
+                .error_type,
+            ValidationErrorType::MismatchedReferenceType { .. }
+        );
+    }
+
+    #[test]
+    fn test_check_entity_type() {
+        assert!(check_entity_type(&task(), &EntityType::new("task")).is_ok());
+        assert!(check_entity_type(&task(), &EntityType::new("project")).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! Derive macros for defining Firm schemas and entities from Rust types.
+//!
+//! `#[derive(FirmEntity)]` on a struct with named fields generates its `EntitySchema` and
+//! conversions to and from `firm_core::Entity`, so the struct and the schema can't drift apart.
+//! The struct needs an `id: firm_core::EntityId` field, which holds the entity ID. Every other
+//! field becomes a schema field, in declaration order, and is required unless it's an `Option`.
+//! Field types are those implementing `firm_core::typed::TypedValue`.
+//!
+//! ```ignore
+//! use firm_core::{EntityId, typed::TypedEntity};
+//! use firm_derive::FirmEntity;
+//!
+//! #[derive(FirmEntity)]
+//! #[firm(entity_type = "task")]
+//! struct Task {
+//!     id: EntityId,
+//!     name: String,
+//!     #[firm(rename = "is_completed")]
+//!     done: bool,
+//!     #[firm(references = "person")]
+//!     assignee_ref: Option<EntityId>,
+//! }
+//!
+//! let schema = Task::schema();
+//! let task = Task::try_from(&entity)?;
+//! let entity = firm_core::Entity::from(task);
+//! ```
+//!
+//! The entity type defaults to the struct name in snake case. Fields can be renamed with
+//! `#[firm(rename = "...")]`, and reference fields can require the entity type they point to
+//! with `#[firm(references = "...")]`.
+
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericArgument, LitStr, PathArguments, Type,
+    ext::IdentExt, parse_macro_input,
+};
+
+const ATTRIBUTE: &str = "firm";
+
+/// Derives `TypedEntity`, `TryFrom<Entity>` and `From<T> for Entity` for a struct.
+#[proc_macro_derive(FirmEntity, attributes(firm))]
+pub fn derive_firm_entity(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A struct field which holds an entity field.
+struct EntityField {
+    ident: syn::Ident,
+    field_id: String,
+    value_type: Type,
+    required: bool,
+    references: Option<String>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let entity_type = entity_type(input)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "FirmEntity can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named_fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "FirmEntity needs a struct with named fields",
+        ));
+    };
+
+    let mut has_id = false;
+    let mut fields = Vec::new();
+    for field in &named_fields.named {
+        match &field.ident {
+            Some(ident) if ident == "id" => has_id = true,
+            _ => fields.push(entity_field(field)?),
+        }
+    }
+
+    if !has_id {
+        return Err(syn::Error::new_spanned(
+            name,
+            "FirmEntity needs an `id: firm_core::EntityId` field",
+        ));
+    }
+
+    let schema_fields = fields.iter().map(|field| {
+        let field_id = &field.field_id;
+        let value_type = &field.value_type;
+        let add_field = match field.required {
+            true => quote!(with_required_field),
+            false => quote!(with_optional_field),
+        };
+
+        quote! {
+            let schema = schema.#add_field(
+                firm_core::FieldId::new(#field_id),
+                <#value_type as firm_core::typed::TypedValue>::FIELD_TYPE,
+            );
+        }
+    });
+
+    let schema_references = fields.iter().filter_map(|field| {
+        let field_id = &field.field_id;
+        field.references.as_ref().map(|target| {
+            quote! {
+                let schema = schema.with_constraints(
+                    &firm_core::FieldId::new(#field_id),
+                    firm_core::schema::FieldConstraints {
+                        references: Some(firm_core::EntityType::new(#target)),
+                        ..Default::default()
+                    },
+                );
+            }
+        })
+    });
+
+    let reference_checks = fields.iter().filter_map(|field| {
+        let field_id = &field.field_id;
+        field.references.as_ref().map(|target| {
+            quote! {
+                firm_core::typed::check_reference_target(
+                    entity,
+                    &firm_core::FieldId::new(#field_id),
+                    &firm_core::EntityType::new(#target),
+                )?;
+            }
+        })
+    });
+
+    let read_fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let field_id = &field.field_id;
+        let getter = match field.required {
+            true => quote!(required_field),
+            false => quote!(optional_field),
+        };
+
+        quote! {
+            #ident: firm_core::typed::#getter(entity, &firm_core::FieldId::new(#field_id))?,
+        }
+    });
+
+    // Optional fields without a value are left out of the entity
+    let write_fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let field_id = &field.field_id;
+
+        match field.required {
+            true => quote! {
+                entity.fields.push((
+                    firm_core::FieldId::new(#field_id),
+                    firm_core::typed::TypedValue::to_value(&typed.#ident),
+                ));
+            },
+            false => quote! {
+                if let Some(value) = &typed.#ident {
+                    entity.fields.push((
+                        firm_core::FieldId::new(#field_id),
+                        firm_core::typed::TypedValue::to_value(value),
+                    ));
+                }
+            },
+        }
+    });
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics firm_core::typed::TypedEntity for #name #type_generics #where_clause {
+            const ENTITY_TYPE: &'static str = #entity_type;
+
+            fn schema() -> firm_core::EntitySchema {
+                let schema = firm_core::EntitySchema::new(firm_core::EntityType::new(#entity_type));
+                #(#schema_fields)*
+                #(#schema_references)*
+                schema
+            }
+        }
+
+        impl #impl_generics TryFrom<&firm_core::Entity> for #name #type_generics #where_clause {
+            type Error = firm_core::schema::ValidationError;
+
+            fn try_from(entity: &firm_core::Entity) -> Result<Self, Self::Error> {
+                firm_core::typed::check_entity_type(entity, &firm_core::EntityType::new(#entity_type))?;
+                #(#reference_checks)*
+
+                Ok(Self {
+                    id: entity.id.clone(),
+                    #(#read_fields)*
+                })
+            }
+        }
+
+        impl #impl_generics TryFrom<firm_core::Entity> for #name #type_generics #where_clause {
+            type Error = firm_core::schema::ValidationError;
+
+            fn try_from(entity: firm_core::Entity) -> Result<Self, Self::Error> {
+                Self::try_from(&entity)
+            }
+        }
+
+        impl #impl_generics From<&#name #type_generics> for firm_core::Entity #where_clause {
+            fn from(typed: &#name #type_generics) -> Self {
+                let mut entity = firm_core::Entity::new(
+                    typed.id.clone(),
+                    firm_core::EntityType::new(#entity_type),
+                );
+                #(#write_fields)*
+                entity
+            }
+        }
+
+        impl #impl_generics From<#name #type_generics> for firm_core::Entity #where_clause {
+            fn from(typed: #name #type_generics) -> Self {
+                Self::from(&typed)
+            }
+        }
+    })
+}
+
+/// Gets the entity type from the struct's attributes, or its name in snake case.
+fn entity_type(input: &DeriveInput) -> syn::Result<String> {
+    let mut entity_type = None;
+
+    for attr in firm_attributes(&input.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("entity_type") {
+                let value: LitStr = meta.value()?.parse()?;
+                entity_type = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `entity_type = \"...\"`"))
+            }
+        })?;
+    }
+
+    Ok(entity_type.unwrap_or_else(|| input.ident.to_string().to_case(Case::Snake)))
+}
+
+/// Reads a struct field and its attributes.
+fn entity_field(field: &Field) -> syn::Result<EntityField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new_spanned(field, "expected a named field"))?;
+
+    let mut field_id = ident.unraw().to_string();
+    let mut references = None;
+
+    for attr in firm_attributes(&field.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let value: LitStr = meta.value()?.parse()?;
+                field_id = value.value();
+                Ok(())
+            } else if meta.path.is_ident("references") {
+                let value: LitStr = meta.value()?.parse()?;
+                references = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename = \"...\"` or `references = \"...\"`"))
+            }
+        })?;
+    }
+
+    let (value_type, required) = match option_inner_type(&field.ty) {
+        Some(inner) => (inner.clone(), false),
+        None => (field.ty.clone(), true),
+    };
+
+    Ok(EntityField {
+        ident,
+        field_id,
+        value_type,
+        required,
+        references,
+    })
+}
+
+/// Gets the `#[firm(...)]` attributes.
+fn firm_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident(ATTRIBUTE))
+}
+
+/// Gets the type inside an `Option`, if the type is one.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+            match arguments.args.first()? {
+                GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,128 @@
+use firm_core::{
+    Entity, EntityId, EntityType, FieldId, FieldType, FieldValue, ReferenceValue,
+    field::{Currency, Decimal},
+    schema::ValidationErrorType,
+    typed::TypedEntity,
+};
+use firm_derive::FirmEntity;
+
+#[derive(Debug, Clone, PartialEq, FirmEntity)]
+#[firm(entity_type = "task")]
+struct Task {
+    id: EntityId,
+    name: String,
+    #[firm(rename = "is_completed")]
+    done: bool,
+    #[firm(references = "person")]
+    assignee_ref: Option<EntityId>,
+    budget: Option<(Decimal, Currency)>,
+    r#type: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FirmEntity)]
+struct ProjectMilestone {
+    id: EntityId,
+    title: String,
+}
+
+fn task() -> Task {
+    Task {
+        id: EntityId::new("task.write_docs"),
+        name: String::from("Write docs"),
+        done: false,
+        assignee_ref: Some(EntityId::new("person.john_doe")),
+        budget: None,
+        r#type: Some(String::from("writing")),
+    }
+}
+
+#[test]
+fn test_derived_schema_fields() {
+    let schema = Task::schema();
+    let fields: Vec<(&str, &FieldType, bool)> = schema
+        .ordered_fields()
+        .into_iter()
+        .map(|(id, field)| (id.as_str(), &field.field_type, field.is_required()))
+        .collect();
+
+    assert_eq!(schema.entity_type, EntityType::new("task"));
+    assert_eq!(
+        fields,
+        vec![
+            ("name", &FieldType::String, true),
+            ("is_completed", &FieldType::Boolean, true),
+            ("assignee_ref", &FieldType::Reference, false),
+            ("budget", &FieldType::Currency, false),
+            ("type", &FieldType::String, false),
+        ]
+    );
+    assert_eq!(
+        schema.fields[&FieldId::new("assignee_ref")]
+            .constraints
+            .references,
+        Some(EntityType::new("person"))
+    );
+}
+
+#[test]
+fn test_derived_entity_type_defaults_to_struct_name() {
+    assert_eq!(ProjectMilestone::ENTITY_TYPE, "project_milestone");
+    assert_eq!(
+        ProjectMilestone::schema().entity_type,
+        EntityType::new("project_milestone")
+    );
+}
+
+#[test]
+fn test_derived_conversions_round_trip() {
+    let entity = Entity::from(task());
+
+    assert_eq!(entity.entity_type, EntityType::new("task"));
+    assert_eq!(
+        entity.get_field(&FieldId::new("is_completed")),
+        Some(&FieldValue::Boolean(false))
+    );
+    assert_eq!(
+        entity.get_field(&FieldId::new("assignee_ref")),
+        Some(&FieldValue::Reference(ReferenceValue::Entity(
+            EntityId::new("person.john_doe")
+        )))
+    );
+    assert_eq!(entity.get_field(&FieldId::new("budget")), None);
+    assert!(Task::schema().validate(&entity).is_ok());
+
+    assert_eq!(Task::try_from(entity).unwrap(), task());
+}
+
+#[test]
+fn test_derived_conversion_errors() {
+    let wrong_type = Entity::new(EntityId::new("task.broken"), EntityType::new("task"))
+        .with_field(FieldId::new("name"), "Broken")
+        .with_field(FieldId::new("is_completed"), "no");
+    assert_eq!(
+        Task::try_from(&wrong_type).unwrap_err().error_type,
+        ValidationErrorType::MismatchedFieldType {
+            expected: FieldType::Boolean,
+            actual: FieldType::String,
+        }
+    );
+
+    let mut wrong_reference = Entity::from(task());
+    wrong_reference.fields.insert(
+        FieldId::new("assignee_ref"),
+        FieldValue::Reference(ReferenceValue::Entity(EntityId::new("organization.acme"))),
+    );
+    assert!(matches!(
+        Task::try_from(&wrong_reference).unwrap_err().error_type,
+        ValidationErrorType::MismatchedReferenceType { .. }
+    ));
+
+    let wrong_entity = Entity::from(ProjectMilestone {
+        id: EntityId::new("project_milestone.launch"),
+        title: String::from("Launch"),
+    });
+    assert!(matches!(
+        Task::try_from(&wrong_entity).unwrap_err().error_type,
+        ValidationErrorType::MismatchedEntityType { .. }
+    ));
+}
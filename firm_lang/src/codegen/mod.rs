@@ -0,0 +1,233 @@
+//! Generates typed Rust structs from entity schemas.
+//!
+//! Each schema becomes a struct with a field per schema field, so code using the entities
+//! gets compile-time field names instead of looking fields up by string. The generated code
+//! only depends on `firm_core`, and converts to and from `Entity` with validation errors on mismatch.
+
+use convert_case::{Case, Casing};
+use firm_core::schema::FieldSchema;
+use firm_core::{EntitySchema, EntityType, FieldType};
+use std::{fs, path::Path};
+
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Generates a Rust module with a typed struct for each schema.
+pub fn generate_rust(schemas: &[EntitySchema]) -> String {
+    let mut schemas: Vec<&EntitySchema> = schemas.iter().collect();
+    schemas.sort_by(|a, b| a.entity_type.cmp(&b.entity_type));
+    let entity_types: Vec<&EntityType> = schemas.iter().map(|s| &s.entity_type).collect();
+
+    let mut output =
+        String::from("// Typed entities generated from Firm schemas. Do not edit by hand.\n");
+
+    for schema in &schemas {
+        output.push('\n');
+        output.push_str(&generate_struct(schema, &entity_types));
+    }
+
+    output
+}
+
+/// Generates Rust structs for the schemas of a workspace from a build script.
+///
+/// Loads the workspace directory, writes the generated module to the output path,
+/// and tells Cargo to run the build script again when the workspace changes.
+/// Include the module with `include!(concat!(env!("OUT_DIR"), "/firm_entities.rs"))`.
+pub fn generate_rust_for_build(
+    workspace_path: &Path,
+    output_path: &Path,
+) -> Result<(), WorkspaceError> {
+    println!("cargo:rerun-if-changed={}", workspace_path.display());
+
+    let mut workspace = Workspace::new();
+    workspace.load_directory(&workspace_path.to_path_buf())?;
+    let schemas = workspace.schemas()?;
+
+    fs::write(output_path, generate_rust(&schemas)).map_err(WorkspaceError::IoError)
+}
+
+/// Generates the struct for a schema with its conversions to and from entities.
+/// References to the other entity types are typed with the struct of the target type.
+fn generate_struct(schema: &EntitySchema, entity_types: &[&EntityType]) -> String {
+    let entity_type = schema.entity_type.as_str();
+    let struct_name = entity_type.to_case(Case::Pascal);
+    let fields = schema.ordered_fields();
+
+    let mut output = format!(
+        "/// An entity of type `{}`.\n#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n    pub id: firm_core::EntityId,\n",
+        entity_type, struct_name
+    );
+    for (field_id, field_schema) in &fields {
+        let rust_type = field_rust_type(field_schema, entity_types);
+        let rust_type = match field_schema.is_required() {
+            true => rust_type,
+            false => format!("Option<{}>", rust_type),
+        };
+        output.push_str(&format!(
+            "    pub {}: {},\n",
+            rust_identifier(field_id.as_str()),
+            rust_type
+        ));
+    }
+    output.push_str("}\n\n");
+
+    output.push_str(&format!(
+        "impl {} {{\n    /// The entity type this struct holds.\n    pub const ENTITY_TYPE: &str = \"{}\";\n}}\n\n",
+        struct_name, entity_type
+    ));
+
+    // Converting from an entity checks its type and the type of each field
+    output.push_str(&format!(
+        "impl TryFrom<&firm_core::Entity> for {} {{\n    type Error = firm_core::schema::ValidationError;\n\n    fn try_from(entity: &firm_core::Entity) -> Result<Self, Self::Error> {{\n        firm_core::typed::check_entity_type(entity, &firm_core::EntityType::new(Self::ENTITY_TYPE))?;\n",
+        struct_name
+    ));
+    for (field_id, field_schema) in &fields {
+        if let Some(target_type) = reference_target(field_schema) {
+            output.push_str(&format!(
+                "        firm_core::typed::check_reference_target(entity, &firm_core::FieldId::new(\"{}\"), &firm_core::EntityType::new(\"{}\"))?;\n",
+                field_id, target_type
+            ));
+        }
+    }
+    output.push_str("\n        Ok(Self {\n            id: entity.id.clone(),\n");
+    for (field_id, field_schema) in &fields {
+        let getter = match field_schema.is_required() {
+            true => "required_field",
+            false => "optional_field",
+        };
+        output.push_str(&format!(
+            "            {}: firm_core::typed::{}(entity, &firm_core::FieldId::new(\"{}\"))?,\n",
+            rust_identifier(field_id.as_str()),
+            getter,
+            field_id
+        ));
+    }
+    output.push_str("        })\n    }\n}\n\n");
+
+    // Converting to an entity leaves out optional fields without a value
+    output.push_str(&format!(
+        "impl From<&{}> for firm_core::Entity {{\n    fn from(typed: &{}) -> Self {{\n        let mut entity = firm_core::Entity::new(typed.id.clone(), firm_core::EntityType::new({}::ENTITY_TYPE));\n",
+        struct_name, struct_name, struct_name
+    ));
+    for (field_id, field_schema) in &fields {
+        let identifier = rust_identifier(field_id.as_str());
+        let line = match field_schema.is_required() {
+            true => format!(
+                "        entity.fields.push((firm_core::FieldId::new(\"{}\"), firm_core::typed::TypedValue::to_value(&typed.{})));\n",
+                field_id, identifier
+            ),
+            false => format!(
+                "        if let Some(value) = &typed.{} {{\n            entity.fields.push((firm_core::FieldId::new(\"{}\"), firm_core::typed::TypedValue::to_value(value)));\n        }}\n",
+                identifier, field_id
+            ),
+        };
+        output.push_str(&line);
+    }
+    output.push_str("        entity\n    }\n}\n");
+
+    output
+}
+
+/// Gets the Rust type which holds the values of a field.
+/// References to a generated entity type use its struct, and other typed references use the ID.
+fn field_rust_type(field_schema: &FieldSchema, entity_types: &[&EntityType]) -> String {
+    match reference_target(field_schema) {
+        Some(target_type) if entity_types.contains(&target_type) => format!(
+            "firm_core::typed::EntityRef<{}>",
+            target_type.as_str().to_case(Case::Pascal)
+        ),
+        Some(_) => String::from("firm_core::EntityId"),
+        None => rust_type(&field_schema.field_type).to_string(),
+    }
+}
+
+/// Gets the entity type a reference field must point to, if the schema sets one.
+fn reference_target(field_schema: &FieldSchema) -> Option<&EntityType> {
+    match field_schema.field_type {
+        FieldType::Reference => field_schema.constraints.references.as_ref(),
+        _ => None,
+    }
+}
+
+/// Gets the Rust type which holds values of a field type.
+fn rust_type(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::Boolean => "bool",
+        FieldType::String => "String",
+        FieldType::Integer => "i64",
+        FieldType::Float => "f64",
+        FieldType::Currency => "(firm_core::field::Decimal, firm_core::field::Currency)",
+        FieldType::Reference => "firm_core::ReferenceValue",
+        FieldType::List => "Vec<firm_core::FieldValue>",
+        FieldType::DateTime => "firm_core::field::DateTime<firm_core::field::FixedOffset>",
+        FieldType::Date => "firm_core::field::NaiveDate",
+        FieldType::Duration => "firm_core::temporal::Duration",
+        FieldType::Recurrence => "firm_core::temporal::Recurrence",
+        FieldType::Quantity => "firm_core::quantity::Quantity",
+        FieldType::Record => "Vec<(firm_core::FieldId, firm_core::FieldValue)>",
+        FieldType::Path => "std::path::PathBuf",
+    }
+}
+
+/// Makes a field name usable as a Rust identifier, escaping keywords.
+fn rust_identifier(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+        "priv", "try", "typeof", "unsized", "virtual", "yield",
+    ];
+
+    match name {
+        // These can't be raw identifiers, and structs already have an id
+        "self" | "Self" | "super" | "crate" | "id" => format!("{}_", name),
+        _ if KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use firm_core::{EntityType, FieldId};
+
+    fn task_schema() -> EntitySchema {
+        EntitySchema::new(EntityType::new("project_task"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("assignee_ref"), FieldType::Reference)
+            .with_optional_field(FieldId::new("type"), FieldType::String)
+    }
+
+    #[test]
+    fn test_generate_struct_fields() {
+        let code = generate_rust(&[task_schema()]);
+
+        assert!(code.contains("pub struct ProjectTask {"));
+        assert!(code.contains("    pub id: firm_core::EntityId,\n"));
+        assert!(code.contains("    pub name: String,\n"));
+        assert!(code.contains("    pub assignee_ref: Option<firm_core::ReferenceValue>,\n"));
+        assert!(code.contains("    pub r#type: Option<String>,\n"));
+        assert!(code.contains("pub const ENTITY_TYPE: &str = \"project_task\";"));
+    }
+
+    #[test]
+    fn test_generate_conversions() {
+        let code = generate_rust(&[task_schema()]);
+
+        assert!(code.contains("impl TryFrom<&firm_core::Entity> for ProjectTask {"));
+        assert!(code.contains(
+            "name: firm_core::typed::required_field(entity, &firm_core::FieldId::new(\"name\"))?,"
+        ));
+        assert!(code.contains("impl From<&ProjectTask> for firm_core::Entity {"));
+        assert!(code.contains("if let Some(value) = &typed.assignee_ref {"));
+    }
+
+    #[test]
+    fn test_rust_identifier() {
+        assert_eq!(rust_identifier("name"), "name");
+        assert_eq!(rust_identifier("type"), "r#type");
+        assert_eq!(rust_identifier("self"), "self_");
+        assert_eq!(rust_identifier("id"), "id_");
+    }
+}
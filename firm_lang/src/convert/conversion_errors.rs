@@ -1,3 +1,4 @@
+use firm_core::schema::FieldConflict;
 use std::fmt;
 
 /// Errors that can occur when converting a parsed entity.
@@ -35,6 +36,18 @@ pub enum SchemaConversionError {
     MissingFieldName,
     MissingFieldType,
     UnknownFieldType(String),
+    UnknownSchemaMode(String),
+    UnknownFieldPolicy(String),
+    UnknownSchema(String),
+    SchemaCycle(Vec<String>),
+    ConflictingFieldTypes(String, Vec<FieldConflict>),
+    ConflictingExtension(String, usize, Vec<FieldConflict>),
+    InvalidFieldDefault(String, String),
+    InvalidFieldConstraint(String, String),
+    InvalidFieldStates(String, String),
+    InvalidRecordFields(String, String),
+    InvalidRule(String),
+    InvalidMigration(String),
     InvalidFieldDefinition,
 }
 
@@ -53,6 +66,100 @@ impl fmt::Display for SchemaConversionError {
             SchemaConversionError::UnknownFieldType(field_type) => {
                 write!(f, "Unknown field type: '{}'", field_type)
             }
+            SchemaConversionError::UnknownSchemaMode(mode) => {
+                write!(
+                    f,
+                    "Unknown schema mode: '{}' (expected 'extend' or 'override')",
+                    mode
+                )
+            }
+            SchemaConversionError::UnknownFieldPolicy(policy) => {
+                write!(
+                    f,
+                    "Unknown policy for unknown fields: '{}' (expected 'allow', 'warn' or 'error')",
+                    policy
+                )
+            }
+            SchemaConversionError::UnknownSchema(schema_name) => {
+                write!(f, "Schema builds on unknown schema: '{}'", schema_name)
+            }
+            SchemaConversionError::SchemaCycle(cycle) => {
+                write!(
+                    f,
+                    "Schemas build on each other in a cycle: {}",
+                    cycle.join(" -> ")
+                )
+            }
+            SchemaConversionError::ConflictingFieldTypes(schema_name, conflicts) => {
+                let details: Vec<String> = conflicts
+                    .iter()
+                    .map(|conflict| {
+                        format!(
+                            "'{}' is both '{}' and '{}'",
+                            conflict.field, conflict.existing, conflict.incoming
+                        )
+                    })
+                    .collect();
+
+                write!(
+                    f,
+                    "Schema '{}' has conflicting field types: {}",
+                    schema_name,
+                    details.join(", ")
+                )
+            }
+            SchemaConversionError::ConflictingExtension(schema_name, _, conflicts) => {
+                let details: Vec<String> = conflicts
+                    .iter()
+                    .map(|conflict| {
+                        format!(
+                            "field '{}' is '{}' but the extension declares it as '{}'",
+                            conflict.field, conflict.existing, conflict.incoming
+                        )
+                    })
+                    .collect();
+
+                write!(
+                    f,
+                    "Schema '{}' cannot be extended: {}",
+                    schema_name,
+                    details.join(", ")
+                )
+            }
+            SchemaConversionError::InvalidFieldDefault(field_name, error) => {
+                write!(
+                    f,
+                    "Schema field '{}' has an invalid default: {}",
+                    field_name, error
+                )
+            }
+            SchemaConversionError::InvalidFieldConstraint(field_name, error) => {
+                write!(
+                    f,
+                    "Schema field '{}' has an invalid constraint: {}",
+                    field_name, error
+                )
+            }
+            SchemaConversionError::InvalidFieldStates(field_name, error) => {
+                write!(
+                    f,
+                    "Schema field '{}' has invalid states: {}",
+                    field_name, error
+                )
+            }
+            SchemaConversionError::InvalidRecordFields(field_name, error) => {
+                write!(
+                    f,
+                    "Schema field '{}' has invalid nested fields: {}",
+                    field_name, error
+                )
+            }
+            SchemaConversionError::InvalidRule(error) => {
+                write!(f, "Schema rule is invalid: {}", error)
+            }
+            SchemaConversionError::InvalidMigration(error) => {
+                write!(f, "Schema migration is invalid: {}", error)
+            }
             SchemaConversionError::InvalidFieldDefinition => {
                 write!(f, "Schema field definition is invalid")
             }
@@ -3,3 +3,4 @@ pub mod to_entity;
 pub mod to_schema;
 
 pub use conversion_errors::{EntityConversionError, SchemaConversionError};
+pub use to_schema::{ComposableSchema, SchemaMode, SchemaResolver, convert_field_type};
@@ -28,7 +28,7 @@ impl TryFrom<&ParsedEntity<'_>> for Entity {
 
             entity
                 .fields
-                .push((FieldId(field_id.to_string()), field_value));
+                .push((FieldId::from(field_id.to_string()), field_value));
         }
 
         Ok(entity)
@@ -63,7 +63,7 @@ impl TryFrom<ParsedValue> for FieldValue {
                 let composite_id = compose_entity_id(&entity_type, &entity_id);
                 Ok(FieldValue::Reference(ReferenceValue::Field(
                     composite_id,
-                    FieldId(field_id),
+                    FieldId::from(field_id),
                 )))
             }
             ParsedValue::List(values) => {
@@ -73,6 +73,7 @@ impl TryFrom<ParsedValue> for FieldValue {
                 Ok(FieldValue::List(converted_values?))
             }
             ParsedValue::DateTime(value) => Ok(FieldValue::DateTime(value)),
+            ParsedValue::Date(value) => Ok(FieldValue::Date(value)),
             ParsedValue::Path(value) => Ok(FieldValue::Path(value)),
         }
     }
@@ -1,11 +1,43 @@
 use firm_core::{
-    EntityType, FieldId,
+    EntityType, FieldId, FieldValue,
     field::FieldType,
-    schema::{EntitySchema, FieldMode, FieldSchema},
+    migration::{Migration, MigrationStep},
+    schema::{
+        Comparison, EntitySchema, FieldConstraints, FieldDefault, FieldMode, FieldSchema,
+        FieldStates, RuleSeverity, SchemaRule, UnknownFieldPolicy,
+    },
 };
+use std::collections::HashMap;
 
-use super::SchemaConversionError;
-use crate::parser::ParsedSchema;
+use super::{EntityConversionError, SchemaConversionError};
+use crate::parser::{
+    ParsedSchema, ParsedSchemaField, ParsedSchemaMigration, ParsedSchemaRule, ValueParseError,
+};
+
+/// Defines how a workspace schema relates to a built-in schema with the same name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaMode {
+    /// Defines a new schema. This is the default.
+    Define,
+    /// Adds fields to an existing schema.
+    Extend,
+    /// Replaces an existing schema entirely.
+    Override,
+}
+
+/// Converts the mode of a ParsedSchema to a SchemaMode.
+impl TryFrom<&ParsedSchema<'_>> for SchemaMode {
+    type Error = SchemaConversionError;
+
+    fn try_from(parsed: &ParsedSchema) -> Result<Self, SchemaConversionError> {
+        match parsed.mode().as_deref() {
+            None => Ok(SchemaMode::Define),
+            Some("extend") => Ok(SchemaMode::Extend),
+            Some("override") => Ok(SchemaMode::Override),
+            Some(mode) => Err(SchemaConversionError::UnknownSchemaMode(mode.to_string())),
+        }
+    }
+}
 
 /// Converts a ParsedSchema to an EntitySchema.
 impl TryFrom<&ParsedSchema<'_>> for EntitySchema {
@@ -18,33 +50,535 @@ impl TryFrom<&ParsedSchema<'_>> for EntitySchema {
 
         let entity_type = EntityType::new(schema_name.to_string());
         let mut schema = EntitySchema::new(entity_type);
+        schema.unknown_fields = convert_unknown_field_policy(parsed)?;
 
         for (order, field) in parsed.fields().iter().enumerate() {
-            let field_name = field
-                .name()
-                .map_err(|_| SchemaConversionError::MissingFieldName)?;
+            let (field_id, field_schema) = convert_schema_field(field, order)?;
+            schema.fields.insert(field_id, field_schema);
+        }
+
+        for rule in &parsed.rules() {
+            schema.rules.push(convert_schema_rule(rule)?);
+        }
+
+        Ok(schema)
+    }
+}
+
+/// Converts a single schema field definition, including the nested fields of records.
+fn convert_schema_field(
+    field: &ParsedSchemaField,
+    order: usize,
+) -> Result<(FieldId, FieldSchema), SchemaConversionError> {
+    let field_name = field
+        .name()
+        .map_err(|_| SchemaConversionError::MissingFieldName)?;
+
+    let field_type_str = field
+        .field_type()
+        .map_err(|_| SchemaConversionError::MissingFieldType)?;
+
+    let field_type = convert_field_type(&field_type_str)?;
+    let default = convert_field_default(field, &field_name, &field_type)?;
+    let constraints = convert_field_constraints(field, &field_name, &field_type)?;
+    let states = convert_field_states(field, &field_name, &field_type)?;
+    let record_fields = convert_record_fields(field, &field_name, &field_type)?;
+
+    let field_schema = if field.required() {
+        FieldSchema::new(field_type, FieldMode::Required, order)
+    } else {
+        FieldSchema::new(field_type, FieldMode::Optional, order)
+    };
+
+    Ok((
+        FieldId::from(field_name),
+        FieldSchema {
+            default,
+            materialize_default: field.materialize(),
+            constraints,
+            states,
+            record_fields,
+            ..field_schema
+        },
+    ))
+}
+
+/// Converts the nested field definitions of a record field (or a list of records).
+fn convert_record_fields(
+    field: &ParsedSchemaField,
+    field_name: &str,
+    field_type: &FieldType,
+) -> Result<Option<HashMap<FieldId, FieldSchema>>, SchemaConversionError> {
+    let nested_fields = field.fields();
+    if nested_fields.is_empty() {
+        return Ok(None);
+    }
+
+    if !matches!(field_type, FieldType::Record | FieldType::List) {
+        return Err(SchemaConversionError::InvalidRecordFields(
+            field_name.to_string(),
+            format!("nested fields are only allowed on record and list fields, not {field_type}"),
+        ));
+    }
+
+    let mut record_fields = HashMap::new();
+    for (order, nested_field) in nested_fields.iter().enumerate() {
+        let (field_id, field_schema) = convert_schema_field(nested_field, order)?;
+        record_fields.insert(field_id, field_schema);
+    }
+
+    Ok(Some(record_fields))
+}
+
+/// A schema converted from DSL, along with the schemas it is composed from
+/// and the migration it declares for existing entities.
+#[derive(Debug, Clone)]
+pub struct ComposableSchema {
+    pub schema: EntitySchema,
+    pub extends: Option<EntityType>,
+    pub includes: Vec<EntityType>,
+    pub is_abstract: bool,
+    pub migration: Migration,
+}
+
+/// Converts a ParsedSchema to a ComposableSchema, keeping its `extends` and `includes` unresolved.
+impl TryFrom<&ParsedSchema<'_>> for ComposableSchema {
+    type Error = SchemaConversionError;
+
+    fn try_from(parsed: &ParsedSchema) -> Result<Self, SchemaConversionError> {
+        let schema = EntitySchema::try_from(parsed)?;
+
+        let mut migration = Migration::new(schema.entity_type.clone());
+        for step in &parsed.migrations() {
+            migration.steps.push(convert_migration_step(step)?);
+        }
+
+        Ok(Self {
+            schema,
+            extends: parsed.extends().map(EntityType::new),
+            includes: parsed.includes().into_iter().map(EntityType::new).collect(),
+            is_abstract: parsed.is_abstract(),
+            migration,
+        })
+    }
+}
+
+/// Resolves `extends` and `includes` between schemas.
+///
+/// A composed schema starts with the fields of the schema it extends, followed by its own fields
+/// and then the fields of each included group in order. Its own fields decide whether a field is required.
+/// Extensions of a schema are applied when it is resolved, so schemas building on it see the extended fields.
+pub struct SchemaResolver<'a> {
+    available: &'a HashMap<EntityType, EntitySchema>,
+    pending: HashMap<EntityType, &'a ComposableSchema>,
+    extensions: Vec<&'a ComposableSchema>,
+    resolved: HashMap<EntityType, EntitySchema>,
+}
+
+impl<'a> SchemaResolver<'a> {
+    /// Creates a resolver for a set of schemas that can also build on already available schemas.
+    /// Schemas in the set take precedence over available schemas with the same name.
+    pub fn new(
+        schemas: impl IntoIterator<Item = &'a ComposableSchema>,
+        available: &'a HashMap<EntityType, EntitySchema>,
+    ) -> Self {
+        Self {
+            available,
+            pending: schemas
+                .into_iter()
+                .map(|schema| (schema.schema.entity_type.clone(), schema))
+                .collect(),
+            extensions: Vec::new(),
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Builder method to add extensions, which are applied in order to the schemas they name.
+    /// A conflicting extension is reported by its position among the extensions.
+    pub fn with_extensions(
+        mut self,
+        extensions: impl IntoIterator<Item = &'a ComposableSchema>,
+    ) -> Self {
+        self.extensions.extend(extensions);
+        self
+    }
+
+    /// Resolves one of the schemas given to the resolver by name.
+    pub fn resolve(
+        &mut self,
+        entity_type: &EntityType,
+    ) -> Result<EntitySchema, SchemaConversionError> {
+        self.resolve_with_stack(entity_type, &mut Vec::new())
+    }
+
+    /// Resolves a schema by name, tracking the schemas being resolved to detect cycles.
+    fn resolve_with_stack(
+        &mut self,
+        entity_type: &EntityType,
+        stack: &mut Vec<EntityType>,
+    ) -> Result<EntitySchema, SchemaConversionError> {
+        if let Some(start) = stack.iter().position(|t| t == entity_type) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|t| t.to_string()).collect();
+            cycle.push(entity_type.to_string());
+            return Err(SchemaConversionError::SchemaCycle(cycle));
+        }
+
+        if let Some(schema) = self.resolved.get(entity_type) {
+            return Ok(schema.clone());
+        }
+
+        let extensions: Vec<(usize, &'a ComposableSchema)> = self
+            .extensions
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, extension)| &extension.schema.entity_type == entity_type)
+            .collect();
+
+        stack.push(entity_type.clone());
+        let (mut composed, is_abstract) = match self.pending.get(entity_type).copied() {
+            Some(pending) => (
+                self.compose_with_stack(pending, stack)?,
+                pending.is_abstract,
+            ),
+            None => {
+                let available =
+                    self.available.get(entity_type).cloned().ok_or_else(|| {
+                        SchemaConversionError::UnknownSchema(entity_type.to_string())
+                    })?;
+
+                if extensions.is_empty() {
+                    stack.pop();
+                    return Ok(available);
+                }
+                (available, false)
+            }
+        };
+
+        for (index, extension) in extensions {
+            let extension_schema = self.compose_with_stack(extension, stack)?;
+            composed = composed.extend(&extension_schema).map_err(|conflicts| {
+                SchemaConversionError::ConflictingExtension(
+                    entity_type.to_string(),
+                    index,
+                    conflicts,
+                )
+            })?;
+        }
+        stack.pop();
+
+        // Field groups may have rules and stamps about fields they don't declare themselves
+        if !is_abstract {
+            composed
+                .check_rules()
+                .map_err(SchemaConversionError::InvalidRule)?;
+            composed.check_states().map_err(|(field_id, message)| {
+                SchemaConversionError::InvalidFieldStates(field_id.to_string(), message)
+            })?;
+        }
+
+        self.resolved.insert(entity_type.clone(), composed.clone());
+        Ok(composed)
+    }
 
-            let field_type_str = field
-                .field_type()
-                .map_err(|_| SchemaConversionError::MissingFieldType)?;
+    /// Composes a schema from its base, its own fields and its included groups.
+    fn compose_with_stack(
+        &mut self,
+        schema: &ComposableSchema,
+        stack: &mut Vec<EntityType>,
+    ) -> Result<EntitySchema, SchemaConversionError> {
+        let entity_type = &schema.schema.entity_type;
+        let conflict_error = |conflicts| {
+            SchemaConversionError::ConflictingFieldTypes(entity_type.to_string(), conflicts)
+        };
 
-            let field_type = convert_field_type(&field_type_str)?;
+        let mut composed = EntitySchema::new(entity_type.clone());
 
-            let field_schema = if field.required() {
-                FieldSchema::new(field_type, FieldMode::Required, order)
-            } else {
-                FieldSchema::new(field_type, FieldMode::Optional, order)
+        if let Some(base) = &schema.extends {
+            let base_schema = self.resolve_with_stack(base, stack)?;
+            composed = composed.extend(&base_schema).map_err(conflict_error)?;
+        }
+        let base_policy = composed.unknown_fields;
+
+        composed = composed.extend(&schema.schema).map_err(conflict_error)?;
+
+        for group in &schema.includes {
+            let group_schema = self.resolve_with_stack(group, stack)?;
+            composed = composed.extend(&group_schema).map_err(conflict_error)?;
+        }
+
+        // Included groups should not change whether the schema's own fields are required
+        for (field_id, field_schema) in &schema.schema.fields {
+            if let Some(composed_field) = composed.fields.get_mut(field_id) {
+                composed_field.field_mode = field_schema.field_mode.clone();
+            }
+        }
+
+        // Likewise, strictness comes from the schema itself or the schema it extends
+        composed.unknown_fields = schema.schema.unknown_fields.or(base_policy);
+
+        Ok(composed)
+    }
+}
+
+/// Converts the default or auto-population rule of a schema field to a FieldDefault.
+fn convert_field_default(
+    field: &ParsedSchemaField,
+    field_name: &str,
+    field_type: &FieldType,
+) -> Result<Option<FieldDefault>, SchemaConversionError> {
+    let invalid_default = |message: String| {
+        SchemaConversionError::InvalidFieldDefault(field_name.to_string(), message)
+    };
+
+    let default_value = field
+        .default_value()
+        .map_err(|err| invalid_default(err.to_string()))?;
+
+    let default = match (default_value, field.auto()) {
+        (None, None) => return Ok(None),
+        (Some(_), Some(_)) => {
+            return Err(invalid_default(
+                "a field can't have both a default and an auto rule".to_string(),
+            ));
+        }
+        (Some(parsed_value), None) => {
+            let value: FieldValue = parsed_value
+                .try_into()
+                .map_err(|err: EntityConversionError| invalid_default(err.to_string()))?;
+            FieldDefault::Value(value.coerce_to(field_type).unwrap_or(value))
+        }
+        (None, Some(auto)) => match auto.as_str() {
+            "now" => FieldDefault::Now,
+            "today" => FieldDefault::Today,
+            _ => return Err(invalid_default(format!("unknown auto rule '{}'", auto))),
+        },
+    };
+
+    if &default.get_type() != field_type {
+        return Err(invalid_default(format!(
+            "expected a default of type '{}' but it was '{}'",
+            field_type,
+            default.get_type()
+        )));
+    }
+
+    Ok(Some(default))
+}
+
+/// Converts the constraints of a schema field to FieldConstraints.
+fn convert_field_constraints(
+    field: &ParsedSchemaField,
+    field_name: &str,
+    field_type: &FieldType,
+) -> Result<FieldConstraints, SchemaConversionError> {
+    let invalid_constraint = |message: String| {
+        SchemaConversionError::InvalidFieldConstraint(field_name.to_string(), message)
+    };
+    let parse_error = |err: ValueParseError| invalid_constraint(err.to_string());
+
+    let constraints = FieldConstraints {
+        min: field.min().map_err(parse_error)?,
+        max: field.max().map_err(parse_error)?,
+        pattern: field.pattern().map_err(parse_error)?,
+        min_length: field.min_length().map_err(parse_error)?,
+        max_length: field.max_length().map_err(parse_error)?,
+        min_items: field.min_items().map_err(parse_error)?,
+        max_items: field.max_items().map_err(parse_error)?,
+        unique: field.unique(),
+        references: field
+            .references()
+            .map_err(parse_error)?
+            .map(EntityType::new),
+        dimension: field
+            .dimension()
+            .map_err(parse_error)?
+            .map(|dimension| dimension.parse())
+            .transpose()
+            .map_err(invalid_constraint)?,
+        severity: RuleSeverity::Error,
+    };
+
+    constraints
+        .check_definition(field_type)
+        .map_err(invalid_constraint)?;
+
+    Ok(constraints)
+}
+
+/// Converts the states and transitions of a schema field to FieldStates, if set.
+fn convert_field_states(
+    field: &ParsedSchemaField,
+    field_name: &str,
+    field_type: &FieldType,
+) -> Result<Option<FieldStates>, SchemaConversionError> {
+    let invalid_states = |message: String| {
+        SchemaConversionError::InvalidFieldStates(field_name.to_string(), message)
+    };
+    let parse_error = |err: ValueParseError| invalid_states(err.to_string());
+
+    let transitions = field.transitions().map_err(parse_error)?;
+    let stamp = field.stamp().map_err(parse_error)?;
+    let Some(states) = field.states().map_err(parse_error)? else {
+        if transitions.is_some() || stamp.is_some() {
+            return Err(invalid_states(
+                "transitions and stamp require states to be declared".to_string(),
+            ));
+        }
+
+        return Ok(None);
+    };
+
+    let mut field_states = FieldStates::new(states);
+    for transition in transitions.unwrap_or_default() {
+        let Some((from, to)) = transition.split_once("->") else {
+            return Err(invalid_states(format!(
+                "expected a transition like \"open -> won\" but got \"{}\"",
+                transition
+            )));
+        };
+
+        field_states = field_states.with_transition(from.trim(), to.trim());
+    }
+
+    if let Some(stamp) = stamp {
+        field_states = field_states.with_stamp(FieldId::new(stamp));
+    }
+
+    field_states
+        .check_definition(field_type)
+        .map_err(invalid_states)?;
+
+    Ok(Some(field_states))
+}
+
+/// Converts a rule of a schema to a SchemaRule.
+fn convert_schema_rule(rule: &ParsedSchemaRule) -> Result<SchemaRule, SchemaConversionError> {
+    let parse_error = |err: ValueParseError| SchemaConversionError::InvalidRule(err.to_string());
+
+    let require = rule.require().map_err(parse_error)?;
+    let when = rule.when().map_err(parse_error)?;
+    let equals = rule.equals().map_err(parse_error)?;
+
+    let schema_rule = match (rule.compare().map_err(parse_error)?, require, when) {
+        (Some(expression), None, None) if equals.is_none() => {
+            let parts: Vec<&str> = expression.split_whitespace().collect();
+            let [field, comparison, other] = parts[..] else {
+                return Err(SchemaConversionError::InvalidRule(format!(
+                    "expected a comparison like \"end_date > start_date\" but got \"{}\"",
+                    expression
+                )));
+            };
+
+            let comparison: Comparison = comparison
+                .parse()
+                .map_err(SchemaConversionError::InvalidRule)?;
+            SchemaRule::compare(FieldId::new(field), comparison, FieldId::new(other))
+        }
+        (None, Some(require), Some(when)) => {
+            let equals = match equals {
+                Some(parsed_value) => Some(FieldValue::try_from(parsed_value).map_err(
+                    |err: EntityConversionError| {
+                        SchemaConversionError::InvalidRule(err.to_string())
+                    },
+                )?),
+                None => None,
             };
 
-            schema.fields.insert(FieldId(field_name), field_schema);
+            SchemaRule::required_if(FieldId::new(require), FieldId::new(when), equals)
         }
+        _ => {
+            return Err(SchemaConversionError::InvalidRule(
+                "a rule must either set compare, or set require and when".to_string(),
+            ));
+        }
+    };
 
-        Ok(schema)
+    match rule.message().map_err(parse_error)? {
+        Some(message) => Ok(schema_rule.with_message(message)),
+        None => Ok(schema_rule),
+    }
+}
+
+/// Converts a migration step of a schema to a MigrationStep.
+fn convert_migration_step(
+    step: &ParsedSchemaMigration,
+) -> Result<MigrationStep, SchemaConversionError> {
+    let parse_error =
+        |err: ValueParseError| SchemaConversionError::InvalidMigration(err.to_string());
+
+    let to = step.to().map_err(parse_error)?;
+    let value = step.value().map_err(parse_error)?;
+
+    let (rename, convert, fill) = (
+        step.rename().map_err(parse_error)?,
+        step.convert().map_err(parse_error)?,
+        step.fill().map_err(parse_error)?,
+    );
+
+    match (rename, convert, fill) {
+        (Some(from), None, None) if value.is_none() => {
+            let to = to.ok_or_else(|| {
+                SchemaConversionError::InvalidMigration(format!(
+                    "renaming '{}' requires the new name in 'to'",
+                    from
+                ))
+            })?;
+
+            Ok(MigrationStep::Rename {
+                from: FieldId::new(from),
+                to: FieldId::new(to),
+            })
+        }
+        (None, Some(field), None) if value.is_none() => {
+            let to = match to {
+                Some(type_str) => Some(convert_field_type(&type_str)?),
+                None => None,
+            };
+
+            Ok(MigrationStep::Convert {
+                field: FieldId::new(field),
+                to,
+            })
+        }
+        (None, None, Some(field)) if to.is_none() => {
+            let value = match value {
+                Some(parsed_value) => Some(FieldValue::try_from(parsed_value).map_err(
+                    |err: EntityConversionError| {
+                        SchemaConversionError::InvalidMigration(err.to_string())
+                    },
+                )?),
+                None => None,
+            };
+
+            Ok(MigrationStep::Fill {
+                field: FieldId::new(field),
+                value,
+            })
+        }
+        _ => Err(SchemaConversionError::InvalidMigration(
+            "a migration must set one of rename and to, convert, or fill".to_string(),
+        )),
+    }
+}
+
+/// Converts how a schema treats undeclared fields to an UnknownFieldPolicy, if set.
+fn convert_unknown_field_policy(
+    parsed: &ParsedSchema,
+) -> Result<Option<UnknownFieldPolicy>, SchemaConversionError> {
+    match parsed.unknown_fields().as_deref() {
+        None => Ok(None),
+        Some("allow") => Ok(Some(UnknownFieldPolicy::Allow)),
+        Some("warn") => Ok(Some(UnknownFieldPolicy::Warn)),
+        Some("error") => Ok(Some(UnknownFieldPolicy::Error)),
+        Some(policy) => Err(SchemaConversionError::UnknownFieldPolicy(
+            policy.to_string(),
+        )),
     }
 }
 
 /// Converts a field type string to a FieldType enum.
-fn convert_field_type(type_str: &str) -> Result<FieldType, SchemaConversionError> {
+pub fn convert_field_type(type_str: &str) -> Result<FieldType, SchemaConversionError> {
     match type_str {
         "boolean" => Ok(FieldType::Boolean),
         "string" => Ok(FieldType::String),
@@ -54,6 +588,11 @@ fn convert_field_type(type_str: &str) -> Result<FieldType, SchemaConversionError
         "reference" => Ok(FieldType::Reference),
         "list" => Ok(FieldType::List),
         "datetime" => Ok(FieldType::DateTime),
+        "date" => Ok(FieldType::Date),
+        "duration" => Ok(FieldType::Duration),
+        "recurrence" => Ok(FieldType::Recurrence),
+        "quantity" => Ok(FieldType::Quantity),
+        "record" => Ok(FieldType::Record),
         _ => Err(SchemaConversionError::UnknownFieldType(
             type_str.to_string(),
         )),
@@ -0,0 +1,250 @@
+//! Edits entities in Firm DSL source while leaving everything else as it was written.
+//!
+//! Unlike generating DSL from scratch, edits keep comments, formatting and the order of fields.
+
+use firm_core::migration::FieldChange;
+use firm_core::{EntityId, FieldId, FieldValue};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::generate::generator_options::GeneratorOptions;
+use crate::generate::{from_field::generate_field, from_value::generate_value};
+use crate::parser::ParsedSource;
+
+/// Sets fields of an entity in the source, returning the edited source text.
+///
+/// Fields the entity already has get their value replaced in place.
+/// New fields are added at the end of the entity block, with the same indentation as the other fields.
+/// Returns None if the source doesn't define the entity.
+pub fn set_entity_fields(
+    parsed: &ParsedSource,
+    entity_id: &EntityId,
+    fields: &[(FieldId, FieldValue)],
+) -> Option<String> {
+    let changes: Vec<FieldChange> = fields
+        .iter()
+        .map(|(field_id, value)| FieldChange::Set(field_id.clone(), value.clone()))
+        .collect();
+
+    let replacements = entity_replacements(parsed, entity_id, &changes)?;
+    Some(apply_replacements(&parsed.source, replacements))
+}
+
+/// Applies field changes to several entities in the source, returning the edited source text.
+///
+/// Changes are applied in order for each entity, so a field can be renamed and then given a new value.
+/// Entities the source doesn't define are left out.
+pub fn edit_entities(parsed: &ParsedSource, edits: &[(EntityId, Vec<FieldChange>)]) -> String {
+    let replacements = edits
+        .iter()
+        .filter_map(|(entity_id, changes)| entity_replacements(parsed, entity_id, changes))
+        .flatten()
+        .collect();
+
+    apply_replacements(&parsed.source, replacements)
+}
+
+/// Gets the replacements in the source which apply field changes to an entity.
+fn entity_replacements(
+    parsed: &ParsedSource,
+    entity_id: &EntityId,
+    changes: &[FieldChange],
+) -> Option<Vec<(Range<usize>, String)>> {
+    let options = GeneratorOptions::default();
+    let entity = parsed.find_entity(entity_id)?;
+    let existing_fields = entity.fields();
+
+    // Track the name each field has in the source, as earlier changes may have renamed it
+    let mut source_names: HashMap<FieldId, Option<FieldId>> = HashMap::new();
+    let source_field = |source_names: &HashMap<FieldId, Option<FieldId>>, field_id: &FieldId| {
+        let source_name = match source_names.get(field_id) {
+            Some(source_name) => source_name.clone()?,
+            None => field_id.clone(),
+        };
+
+        existing_fields
+            .iter()
+            .find(|field| field.id() == Some(source_name.as_str()))
+    };
+
+    let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+    let mut new_fields: Vec<(FieldId, FieldValue)> = Vec::new();
+
+    for change in changes {
+        match change {
+            FieldChange::Rename(from, to) => {
+                if let Some(new_field) = new_fields.iter_mut().find(|(id, _)| id == from) {
+                    new_field.0 = to.clone();
+                } else if let Some(range) =
+                    source_field(&source_names, from).and_then(|field| field.id_byte_range())
+                {
+                    set_replacement(&mut replacements, range, to.to_string());
+                }
+
+                let source_name = source_names.remove(from).unwrap_or(Some(from.clone()));
+                source_names.insert(to.clone(), source_name);
+                source_names.insert(from.clone(), None);
+            }
+            FieldChange::Set(field_id, value) => {
+                if let Some(new_field) = new_fields.iter_mut().find(|(id, _)| id == field_id) {
+                    new_field.1 = value.clone();
+                } else if let Some(range) =
+                    source_field(&source_names, field_id).and_then(|field| field.value_byte_range())
+                {
+                    set_replacement(&mut replacements, range, generate_value(value, &options));
+                } else {
+                    new_fields.push((field_id.clone(), value.clone()));
+                }
+            }
+        }
+    }
+
+    if !new_fields.is_empty() {
+        let source = parsed.source.as_str();
+        let indent = existing_fields
+            .last()
+            .map(|field| line_indent(source, field.byte_range().start))
+            .unwrap_or_else(|| options.indent_style.indent_string(1));
+
+        // Insert before the closing brace, keeping it on its own line
+        let closing_brace = entity.byte_range().end.saturating_sub(1);
+        let line_start = source[..closing_brace].rfind('\n').map_or(0, |i| i + 1);
+        let brace_on_own_line = source[line_start..closing_brace].trim().is_empty();
+
+        let lines: String = new_fields
+            .iter()
+            .map(|(field_id, value)| {
+                format!(
+                    "{}{}\n",
+                    indent,
+                    generate_field(field_id.as_str(), value, &options)
+                )
+            })
+            .collect();
+
+        if brace_on_own_line {
+            replacements.push((line_start..line_start, lines));
+        } else {
+            replacements.push((closing_brace..closing_brace, format!("\n{}", lines)));
+        }
+    }
+
+    Some(replacements)
+}
+
+/// Adds a replacement, replacing any earlier one for the same range.
+fn set_replacement(
+    replacements: &mut Vec<(Range<usize>, String)>,
+    range: Range<usize>,
+    text: String,
+) {
+    replacements.retain(|(existing, _)| *existing != range);
+    replacements.push((range, text));
+}
+
+/// Gets the whitespace at the start of the line containing a byte position.
+fn line_indent(source: &str, position: usize) -> String {
+    let line_start = source[..position].rfind('\n').map_or(0, |i| i + 1);
+    source[line_start..position]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect()
+}
+
+/// Applies replacements of byte ranges in the source, which must not overlap.
+fn apply_replacements(source: &str, mut replacements: Vec<(Range<usize>, String)>) -> String {
+    let mut output = source.to_string();
+
+    // Apply from the end so earlier ranges stay valid
+    replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, text) in replacements {
+        output.replace_range(range, &text);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn test_set_existing_and_new_fields() {
+        let source = r#"// Our biggest deal
+opportunity big_deal {
+    name = "Big deal" // Keep this name
+    status = "open"
+}
+"#;
+
+        let parsed = parse_source(String::from(source), None).unwrap();
+        let edited = set_entity_fields(
+            &parsed,
+            &EntityId::new("opportunity.big_deal"),
+            &[
+                (
+                    FieldId::new("status"),
+                    FieldValue::String(String::from("won")),
+                ),
+                (FieldId::new("probability"), FieldValue::Integer(100)),
+            ],
+        )
+        .unwrap();
+
+        let expected = r#"// Our biggest deal
+opportunity big_deal {
+    name = "Big deal" // Keep this name
+    status = "won"
+    probability = 100
+}
+"#;
+        assert_eq!(edited, expected);
+    }
+
+    #[test]
+    fn test_set_fields_missing_entity() {
+        let parsed = parse_source(String::from("task a {\n}\n"), None).unwrap();
+        let edited = set_entity_fields(&parsed, &EntityId::new("task.b"), &[]);
+        assert!(edited.is_none());
+    }
+
+    #[test]
+    fn test_edit_entities_with_renames() {
+        let source = r#"task first {
+    title = "Write docs" // Short title
+    priority = "2"
+}
+
+task second { title = "Review docs" }
+"#;
+
+        let parsed = parse_source(String::from(source), None).unwrap();
+        let changes = vec![
+            FieldChange::Rename(FieldId::new("title"), FieldId::new("name")),
+            FieldChange::Set(FieldId::new("priority"), FieldValue::Integer(2)),
+        ];
+        let edited = edit_entities(
+            &parsed,
+            &[
+                (EntityId::new("task.first"), changes),
+                (
+                    EntityId::new("task.second"),
+                    vec![
+                        FieldChange::Rename(FieldId::new("title"), FieldId::new("name")),
+                        FieldChange::Set(FieldId::new("name"), FieldValue::from("Review all docs")),
+                    ],
+                ),
+            ],
+        );
+
+        let expected = r#"task first {
+    name = "Write docs" // Short title
+    priority = 2
+}
+
+task second { name = "Review all docs" }
+"#;
+        assert_eq!(edited, expected);
+    }
+}
@@ -5,7 +5,7 @@ use super::{GeneratorOptions, from_field};
 /// Generate DSL for a single entity.
 pub fn generate_entity(entity: &Entity, options: &GeneratorOptions) -> String {
     let mut output = String::new();
-    let (_, entity_id) = decompose_entity_id(&entity.id.0);
+    let (_, entity_id) = decompose_entity_id(entity.id.as_str());
 
     // Entity declaration and open block
     output.push_str(&format!(
@@ -31,7 +31,7 @@ fn generate_entity_fields(entity: &Entity, options: &GeneratorOptions) -> Vec<St
     let fields: Vec<(String, &firm_core::FieldValue)> = entity
         .fields
         .iter()
-        .map(|(field_id, field_value)| (field_id.0.clone(), field_value))
+        .map(|(field_id, field_value)| (field_id.to_string(), field_value))
         .collect();
 
     // Generate each field
@@ -48,19 +48,19 @@ fn generate_entity_fields(entity: &Entity, options: &GeneratorOptions) -> Vec<St
 mod tests {
     use super::*;
     use crate::generate::generator_options::IndentStyle;
-    use firm_core::{Entity, EntityId, EntityType, FieldId, FieldValue, ReferenceValue};
+    use firm_core::{Entity, EntityId, EntityType, FieldId, FieldValue, Fields, ReferenceValue};
 
     #[test]
     fn test_generate_simple_person_entity() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("name".to_string()),
+            FieldId::from("name"),
             FieldValue::String("John Doe".to_string()),
         ));
-        fields.push((FieldId("age".to_string()), FieldValue::Integer(42)));
+        fields.push((FieldId::from("age"), FieldValue::Integer(42)));
 
         let entity = Entity {
-            id: EntityId("person.john_doe".to_string()),
+            id: EntityId::from("person.john_doe"),
             entity_type: EntityType::new("person"),
             fields,
         };
@@ -77,23 +77,20 @@ mod tests {
 
     #[test]
     fn test_generate_organization_with_multiple_fields() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("name".to_string()),
+            FieldId::from("name"),
             FieldValue::String("ACME Corp".to_string()),
         ));
         fields.push((
-            FieldId("primary_email".to_string()),
+            FieldId::from("primary_email"),
             FieldValue::String("contact@acme.com".to_string()),
         ));
-        fields.push((FieldId("active".to_string()), FieldValue::Boolean(true)));
-        fields.push((
-            FieldId("employee_count".to_string()),
-            FieldValue::Integer(150),
-        ));
+        fields.push((FieldId::from("active"), FieldValue::Boolean(true)));
+        fields.push((FieldId::from("employee_count"), FieldValue::Integer(150)));
 
         let entity = Entity {
-            id: EntityId("organization.acme_corp".to_string()),
+            id: EntityId::from("organization.acme_corp"),
             entity_type: EntityType::new("organization"),
             fields,
         };
@@ -112,27 +109,27 @@ mod tests {
 
     #[test]
     fn test_generate_entity_with_references() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("name".to_string()),
+            FieldId::from("name"),
             FieldValue::String("Jane Smith".to_string()),
         ));
         fields.push((
-            FieldId("manager".to_string()),
-            FieldValue::Reference(ReferenceValue::Entity(EntityId(
+            FieldId::from("manager"),
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::from(
                 "person.john_doe".to_string(),
             ))),
         ));
         fields.push((
-            FieldId("manager_email".to_string()),
+            FieldId::from("manager_email"),
             FieldValue::Reference(ReferenceValue::Field(
-                EntityId("person.john_doe".to_string()),
-                FieldId("email".to_string()),
+                EntityId::from("person.john_doe"),
+                FieldId::from("email"),
             )),
         ));
 
         let entity = Entity {
-            id: EntityId("person.jane_smith".to_string()),
+            id: EntityId::from("person.jane_smith"),
             entity_type: EntityType::new("person"),
             fields,
         };
@@ -150,13 +147,13 @@ mod tests {
 
     #[test]
     fn test_generate_entity_with_multiline_string() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("title".to_string()),
+            FieldId::from("title"),
             FieldValue::String("Code Review".to_string()),
         ));
         fields.push((
-            FieldId("description".to_string()),
+            FieldId::from("description"),
             FieldValue::String(
                 "Review the pull request:\n- Check logic\n- Verify tests\n- Approve changes"
                     .to_string(),
@@ -164,7 +161,7 @@ mod tests {
         ));
 
         let entity = Entity {
-            id: EntityId("task.code_review".to_string()),
+            id: EntityId::from("task.code_review"),
             entity_type: EntityType::new("task"),
             fields,
         };
@@ -186,14 +183,14 @@ mod tests {
 
     #[test]
     fn test_generate_with_custom_indent() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("name".to_string()),
+            FieldId::from("name"),
             FieldValue::String("Test".to_string()),
         ));
 
         let entity = Entity {
-            id: EntityId("person.test".to_string()),
+            id: EntityId::from("person.test"),
             entity_type: EntityType::new("person"),
             fields,
         };
@@ -214,14 +211,14 @@ mod tests {
 
     #[test]
     fn test_generate_with_tab_indent() {
-        let mut fields = Vec::new();
+        let mut fields = Fields::new();
         fields.push((
-            FieldId("name".to_string()),
+            FieldId::from("name"),
             FieldValue::String("Test".to_string()),
         ));
 
         let entity = Entity {
-            id: EntityId("person.test".to_string()),
+            id: EntityId::from("person.test"),
             entity_type: EntityType::new("person"),
             fields,
         };
@@ -1,12 +1,17 @@
 use chrono::{DateTime, FixedOffset};
 use std::path::PathBuf;
 
-use firm_core::{FieldValue, ReferenceValue};
+use firm_core::{FieldId, FieldValue, ReferenceValue};
 
 use super::GeneratorOptions;
 
 /// Generate DSL for en entity field value.
 pub fn generate_value(value: &FieldValue, options: &GeneratorOptions) -> String {
+    generate_nested_value(value, options, 1)
+}
+
+/// Generate DSL for a field value at a given indentation level, so that records nest cleanly.
+fn generate_nested_value(value: &FieldValue, options: &GeneratorOptions, level: usize) -> String {
     match value {
         FieldValue::Boolean(b) => b.to_string(),
         FieldValue::String(s) => generate_string(s, options),
@@ -16,8 +21,14 @@ pub fn generate_value(value: &FieldValue, options: &GeneratorOptions) -> String
             format!("{} {}", amount, currency.code())
         }
         FieldValue::Reference(reference) => generate_reference(reference),
-        FieldValue::List(values) => generate_list(values, options),
+        FieldValue::List(values) => generate_list(values, options, level),
+        FieldValue::Record(fields) => generate_record(fields, options, level),
         FieldValue::DateTime(dt) => generate_datetime(dt),
+        FieldValue::Date(date) => date.format("%Y-%m-%d").to_string(),
+        // Durations, recurrences and quantities are written as strings, and read back by their field's type
+        FieldValue::Duration(duration) => format!("\"{}\"", duration),
+        FieldValue::Recurrence(recurrence) => format!("\"{}\"", recurrence),
+        FieldValue::Quantity(quantity) => format!("\"{}\"", quantity),
         FieldValue::Path(path) => generate_path(path),
     }
 }
@@ -56,24 +67,49 @@ fn generate_float(f: &f64) -> String {
 /// Generate entity/field reference value.
 fn generate_reference(reference: &ReferenceValue) -> String {
     match reference {
-        ReferenceValue::Entity(entity_id) => entity_id.0.clone(),
+        ReferenceValue::Entity(entity_id) => entity_id.to_string(),
         ReferenceValue::Field(entity_id, field_id) => {
-            format!("{}.{}", entity_id.0, field_id.0)
+            format!("{}.{}", entity_id, field_id)
         }
     }
 }
 
 /// Generate list value.
-fn generate_list(values: &[FieldValue], options: &GeneratorOptions) -> String {
+fn generate_list(values: &[FieldValue], options: &GeneratorOptions, level: usize) -> String {
     if values.is_empty() {
         return "[]".to_string();
     }
 
-    let value_strings: Vec<String> = values.iter().map(|v| generate_value(v, options)).collect();
+    let value_strings: Vec<String> = values
+        .iter()
+        .map(|v| generate_nested_value(v, options, level))
+        .collect();
 
     format!("[{}]", value_strings.join(", "))
 }
 
+/// Generate record value with one field per line.
+fn generate_record(
+    fields: &[(FieldId, FieldValue)],
+    options: &GeneratorOptions,
+    level: usize,
+) -> String {
+    if fields.is_empty() {
+        return "{}".to_string();
+    }
+
+    let field_indent = options.indent_style.indent_string(level + 1);
+    let closing_indent = options.indent_style.indent_string(level);
+
+    let mut result = String::from("{\n");
+    for (field_id, value) in fields {
+        let value_str = generate_nested_value(value, options, level + 1);
+        result.push_str(&format!("{}{} = {}\n", field_indent, field_id, value_str));
+    }
+    result.push_str(&format!("{}}}", closing_indent));
+    result
+}
+
 /// Generate datetime value.
 fn generate_datetime(dt: &DateTime<FixedOffset>) -> String {
     let date_str = dt.format("%Y-%m-%d").to_string();
@@ -104,6 +140,8 @@ fn generate_path(path: &PathBuf) -> String {
 mod tests {
     use super::*;
     use chrono::TimeZone;
+    use firm_core::quantity::Quantity;
+    use firm_core::temporal::{Duration, Recurrence, RecurrenceUnit};
     use firm_core::{EntityId, FieldId, FieldValue, ReferenceValue};
     use iso_currency::Currency;
     use rust_decimal::Decimal;
@@ -236,17 +274,14 @@ mod tests {
 
     #[test]
     fn test_generate_reference_entity() {
-        let reference = ReferenceValue::Entity(EntityId("person.john".to_string()));
+        let reference = ReferenceValue::Entity(EntityId::from("person.john"));
         let result = generate_reference(&reference);
         assert_eq!(result, "person.john");
     }
 
     #[test]
     fn test_generate_reference_field() {
-        let reference = ReferenceValue::Field(
-            EntityId("person.john".to_string()),
-            FieldId("name".to_string()),
-        );
+        let reference = ReferenceValue::Field(EntityId::from("person.john"), FieldId::from("name"));
         let result = generate_reference(&reference);
         assert_eq!(result, "person.john.name");
     }
@@ -254,7 +289,7 @@ mod tests {
     #[test]
     fn test_generate_empty_list() {
         let options = GeneratorOptions::default();
-        let result = generate_list(&[], &options);
+        let result = generate_list(&[], &options, 1);
         assert_eq!(result, "[]");
     }
 
@@ -265,7 +300,7 @@ mod tests {
             FieldValue::String("first".to_string()),
             FieldValue::String("second".to_string()),
         ];
-        let result = generate_list(&values, &options);
+        let result = generate_list(&values, &options, 1);
         assert_eq!(result, "[\"first\", \"second\"]");
     }
 
@@ -277,7 +312,7 @@ mod tests {
             FieldValue::Integer(2),
             FieldValue::Integer(3),
         ];
-        let result = generate_list(&values, &options);
+        let result = generate_list(&values, &options, 1);
         assert_eq!(result, "[1, 2, 3]");
     }
 
@@ -289,7 +324,7 @@ mod tests {
             FieldValue::Boolean(false),
             FieldValue::Boolean(true),
         ];
-        let result = generate_list(&values, &options);
+        let result = generate_list(&values, &options, 1);
         assert_eq!(result, "[true, false, true]");
     }
 
@@ -309,10 +344,58 @@ mod tests {
 
         let nested_list = vec![FieldValue::List(inner_list1), FieldValue::List(inner_list2)];
 
-        let result = generate_list(&nested_list, &options);
+        let result = generate_list(&nested_list, &options, 1);
         assert_eq!(result, "[[\"a\", \"b\"], [\"c\", \"d\"]]");
     }
 
+    #[test]
+    fn test_generate_date() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let result = generate_value(&FieldValue::Date(date), &GeneratorOptions::default());
+        assert_eq!(result, "2024-03-05");
+    }
+
+    #[test]
+    fn test_generate_duration_and_recurrence() {
+        let options = GeneratorOptions::default();
+        let duration = FieldValue::Duration(Duration::from_minutes(150));
+        assert_eq!(generate_value(&duration, &options), "\"2h30m\"");
+
+        let recurrence = FieldValue::Recurrence(Recurrence::new(2, RecurrenceUnit::Week));
+        assert_eq!(generate_value(&recurrence, &options), "\"every 2 weeks\"");
+    }
+
+    #[test]
+    fn test_generate_quantity() {
+        let options = GeneratorOptions::default();
+        let quantity = FieldValue::Quantity(Quantity::new(1.5, "TB"));
+        assert_eq!(generate_value(&quantity, &options), "\"1.5 TB\"");
+    }
+
+    #[test]
+    fn test_generate_record() {
+        let options = GeneratorOptions::default();
+        let record = FieldValue::Record(vec![
+            (
+                FieldId::from("street"),
+                FieldValue::String("Main Street 1".to_string()),
+            ),
+            (
+                FieldId::from("location"),
+                FieldValue::Record(vec![(FieldId::from("floor"), FieldValue::Integer(3))]),
+            ),
+        ]);
+
+        let expected = "{\n        street = \"Main Street 1\"\n        location = {\n            floor = 3\n        }\n    }";
+        assert_eq!(generate_value(&record, &options), expected);
+    }
+
+    #[test]
+    fn test_generate_empty_record() {
+        let options = GeneratorOptions::default();
+        assert_eq!(generate_value(&FieldValue::Record(vec![]), &options), "{}");
+    }
+
     #[test]
     fn test_generate_datetime_utc() {
         let dt = FixedOffset::east_opt(0)
@@ -46,19 +46,19 @@ mod tests {
     fn test_generate_multiple_entities() {
         // Create a person
         let person = Entity {
-            id: EntityId("person.daniel_rothmann".to_string()),
+            id: EntityId::from("person.daniel_rothmann"),
             entity_type: EntityType::new("person"),
             fields: [
                 (
-                    FieldId("first_name".to_string()),
+                    FieldId::from("first_name"),
                     FieldValue::String("Daniel".to_string()),
                 ),
                 (
-                    FieldId("last_name".to_string()),
+                    FieldId::from("last_name"),
                     FieldValue::String("Rothmann".to_string()),
                 ),
                 (
-                    FieldId("primary_email".to_string()),
+                    FieldId::from("primary_email"),
                     FieldValue::String("daniel@42futures.com".to_string()),
                 ),
             ]
@@ -67,15 +67,15 @@ mod tests {
 
         // Create an organization
         let organization = Entity {
-            id: EntityId("organization.main".to_string()),
+            id: EntityId::from("organization.main"),
             entity_type: EntityType::new("organization"),
             fields: [
                 (
-                    FieldId("name".to_string()),
+                    FieldId::from("name"),
                     FieldValue::String("42futures".to_string()),
                 ),
                 (
-                    FieldId("primary_email".to_string()),
+                    FieldId::from("primary_email"),
                     FieldValue::String("hello@42futures.com".to_string()),
                 ),
             ]
@@ -84,34 +84,34 @@ mod tests {
 
         // Create a project with references
         let project = Entity {
-            id: EntityId("project.firm_language".to_string()),
+            id: EntityId::from("project.firm_language"),
             entity_type: EntityType::new("project"),
             fields: [
                 (
-                    FieldId("name".to_string()),
+                    FieldId::from("name"),
                     FieldValue::String("Firm Language Development".to_string()),
                 ),
                 (
-                    FieldId("owner_ref".to_string()),
-                    FieldValue::Reference(ReferenceValue::Entity(EntityId(
+                    FieldId::from("owner_ref"),
+                    FieldValue::Reference(ReferenceValue::Entity(EntityId::from(
                         "person.daniel_rothmann".to_string(),
                     ))),
                 ),
                 (
-                    FieldId("organization_ref".to_string()),
-                    FieldValue::Reference(ReferenceValue::Entity(EntityId(
+                    FieldId::from("organization_ref"),
+                    FieldValue::Reference(ReferenceValue::Entity(EntityId::from(
                         "organization.main".to_string(),
                     ))),
                 ),
                 (
-                    FieldId("budget".to_string()),
+                    FieldId::from("budget"),
                     FieldValue::Currency {
                         amount: Decimal::from_str_exact("150000").unwrap(),
                         currency: Currency::EUR,
                     },
                 ),
                 (
-                    FieldId("technologies".to_string()),
+                    FieldId::from("technologies"),
                     FieldValue::List(vec![
                         FieldValue::String("Rust".to_string()),
                         FieldValue::String("Tree-sitter".to_string()),
@@ -150,22 +150,18 @@ project firm_language {
     fn test_generate_with_custom_options() {
         let entities = vec![
             Entity {
-                id: EntityId("person.alice".to_string()),
+                id: EntityId::from("person.alice"),
                 entity_type: EntityType::new("person"),
                 fields: [(
-                    FieldId("name".to_string()),
+                    FieldId::from("name"),
                     FieldValue::String("Alice".to_string()),
                 )]
                 .into(),
             },
             Entity {
-                id: EntityId("person.bob".to_string()),
+                id: EntityId::from("person.bob"),
                 entity_type: EntityType::new("person"),
-                fields: [(
-                    FieldId("name".to_string()),
-                    FieldValue::String("Bob".to_string()),
-                )]
-                .into(),
+                fields: [(FieldId::from("name"), FieldValue::String("Bob".to_string()))].into(),
             },
         ];
 
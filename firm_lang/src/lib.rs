@@ -3,7 +3,9 @@
 //! This crate handles loading `.firm` files, parsing them into an abstract
 //! representation, and converting them to Firm's core data structures.
 
+pub mod codegen;
 pub mod convert;
+pub mod edit;
 pub mod generate;
 pub mod parser;
 pub mod workspace;
@@ -2,6 +2,8 @@ mod parsed_entity;
 mod parsed_field;
 mod parsed_schema;
 mod parsed_schema_field;
+mod parsed_schema_migration;
+mod parsed_schema_rule;
 mod parsed_source;
 mod parsed_value;
 mod parser_errors;
@@ -12,6 +14,8 @@ pub use parsed_entity::ParsedEntity;
 pub use parsed_field::ParsedField;
 pub use parsed_schema::ParsedSchema;
 pub use parsed_schema_field::ParsedSchemaField;
+pub use parsed_schema_migration::ParsedSchemaMigration;
+pub use parsed_schema_rule::ParsedSchemaRule;
 pub use parsed_source::ParsedSource;
 pub use parsed_value::ParsedValue;
 pub use parser_errors::{LanguageError, ValueParseError};
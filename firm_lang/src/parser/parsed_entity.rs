@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{ops::Range, path::PathBuf};
 
 use tree_sitter::Node;
 
@@ -63,4 +63,9 @@ impl<'a> ParsedEntity<'a> {
 
         fields
     }
+
+    /// Gets the byte range of the whole entity block in the source.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.node.byte_range()
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{ops::Range, path::PathBuf};
 
 use tree_sitter::Node;
 
@@ -40,4 +40,19 @@ impl<'a> ParsedField<'a> {
 
         ParsedValue::from_node(value_node, self.source, self.path)
     }
+
+    /// Gets the byte range of the whole field assignment in the source.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.node.byte_range()
+    }
+
+    /// Gets the byte range of the field name in the source.
+    pub fn id_byte_range(&self) -> Option<Range<usize>> {
+        find_child_of_kind(&self.node, FIELD_ID_KIND).map(|id_node| id_node.byte_range())
+    }
+
+    /// Gets the byte range of the field's value in the source.
+    pub fn value_byte_range(&self) -> Option<Range<usize>> {
+        find_child_of_kind(&self.node, VALUE_KIND).map(|value_node| value_node.byte_range())
+    }
 }
@@ -3,17 +3,20 @@ use std::path::PathBuf;
 use tree_sitter::Node;
 
 use super::{
-    ParsedSchemaField,
+    ParsedField, ParsedSchemaField, ParsedSchemaMigration, ParsedSchemaRule, ParsedValue,
     parser_utils::{find_child_of_kind, get_node_text},
 };
 
 const SCHEMA_NAME_KIND: &str = "schema_name";
 const NESTED_BLOCK_KIND: &str = "nested_block";
+const BLOCK_KIND: &str = "block";
+const BLOCK_TYPE_KIND: &str = "block_type";
+const FIELD_KIND: &str = "field";
 
 /// A parsed schema definition from Firm DSL.
 ///
 /// Represents a schema block like `schema project { ... }` with
-/// access to the schema name and contained field definitions.
+/// access to the schema name and contained field, rule and migration definitions.
 #[derive(Debug)]
 pub struct ParsedSchema<'a> {
     node: Node<'a>,
@@ -33,37 +36,131 @@ impl<'a> ParsedSchema<'a> {
         Some(get_node_text(&name_node, self.source))
     }
 
+    /// Gets the schema mode from the "mode" field (e.g., "extend", "override"), if set.
+    pub fn mode(&self) -> Option<String> {
+        let mode_field = self.find_field_by_name("mode")?;
+
+        match mode_field.value() {
+            Ok(ParsedValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets how the schema treats undeclared fields from the "unknown_fields" field (e.g., "warn", "error"), if set.
+    pub fn unknown_fields(&self) -> Option<String> {
+        let unknown_fields_field = self.find_field_by_name("unknown_fields")?;
+
+        match unknown_fields_field.value() {
+            Ok(ParsedValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets the name of the schema this one extends from the "extends" field, if set.
+    pub fn extends(&self) -> Option<String> {
+        let extends_field = self.find_field_by_name("extends")?;
+
+        match extends_field.value() {
+            Ok(ParsedValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets the names of the field groups this schema includes from the "includes" field.
+    /// Defaults to no groups if not specified.
+    pub fn includes(&self) -> Vec<String> {
+        let Some(includes_field) = self.find_field_by_name("includes") else {
+            return Vec::new();
+        };
+
+        match includes_field.value() {
+            Ok(ParsedValue::List(items)) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    ParsedValue::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks whether the schema is abstract, meaning it only exists to be extended or included.
+    /// Defaults to false if not specified.
+    pub fn is_abstract(&self) -> bool {
+        if let Some(abstract_field) = self.find_field_by_name("abstract") {
+            if let Ok(ParsedValue::Boolean(b)) = abstract_field.value() {
+                return b;
+            }
+        }
+
+        false
+    }
+
     /// Extracts all field definitions from the schema block.
-    pub fn fields(&self) -> Vec<ParsedSchemaField> {
-        let mut fields = Vec::new();
-        let mut cursor = self.node.walk();
-
-        // First find the block node
-        if let Some(block_node) = self
-            .node
-            .children(&mut cursor)
-            .find(|child| child.kind() == "block")
-        {
-            let mut block_cursor = block_node.walk();
-
-            // Then find nested_block nodes within the block (these are the field definitions)
-            for child in block_node.children(&mut block_cursor) {
-                if child.kind() == NESTED_BLOCK_KIND {
-                    // Verify this is a "field" block by checking the block_type
-                    let mut nested_cursor = child.walk();
-                    if let Some(block_type_node) = child
-                        .children(&mut nested_cursor)
-                        .find(|c| c.kind() == "block_type")
-                    {
-                        let block_type = get_node_text(&block_type_node, self.source);
-                        if block_type == "field" {
-                            fields.push(ParsedSchemaField::new(child, self.source, self.path));
-                        }
+    pub fn fields(&self) -> Vec<ParsedSchemaField<'a>> {
+        self.find_nested_blocks("field")
+            .into_iter()
+            .map(|node| ParsedSchemaField::new(node, self.source, self.path))
+            .collect()
+    }
+
+    /// Extracts all rule definitions from the schema block.
+    pub fn rules(&self) -> Vec<ParsedSchemaRule<'a>> {
+        self.find_nested_blocks("rule")
+            .into_iter()
+            .map(|node| ParsedSchemaRule::new(node, self.source, self.path))
+            .collect()
+    }
+
+    /// Extracts all migration steps from the schema block, in the order they are declared.
+    pub fn migrations(&self) -> Vec<ParsedSchemaMigration<'a>> {
+        self.find_nested_blocks("migration")
+            .into_iter()
+            .map(|node| ParsedSchemaMigration::new(node, self.source, self.path))
+            .collect()
+    }
+
+    /// Helper method to find nested blocks of a given type (e.g., "field") within the schema block.
+    fn find_nested_blocks(&self, block_type: &str) -> Vec<Node<'a>> {
+        let mut blocks = Vec::new();
+        let Some(block_node) = find_child_of_kind(&self.node, BLOCK_KIND) else {
+            return blocks;
+        };
+
+        let mut block_cursor = block_node.walk();
+        for child in block_node.children(&mut block_cursor) {
+            if child.kind() == NESTED_BLOCK_KIND {
+                // Check the type of the nested block, like "field" or "rule"
+                let mut nested_cursor = child.walk();
+                if let Some(block_type_node) = child
+                    .children(&mut nested_cursor)
+                    .find(|c| c.kind() == BLOCK_TYPE_KIND)
+                {
+                    if get_node_text(&block_type_node, self.source) == block_type {
+                        blocks.push(child);
                     }
                 }
             }
         }
 
-        fields
+        blocks
+    }
+
+    /// Helper method to find a top-level field assignment by name within the schema block.
+    fn find_field_by_name(&self, field_name: &str) -> Option<ParsedField<'a>> {
+        let block_node = find_child_of_kind(&self.node, BLOCK_KIND)?;
+        let mut cursor = block_node.walk();
+
+        for child in block_node.children(&mut cursor) {
+            if child.kind() == FIELD_KIND {
+                let field = ParsedField::new(child, self.source, self.path);
+                if field.id() == Some(field_name) {
+                    return Some(field);
+                }
+            }
+        }
+
+        None
     }
 }
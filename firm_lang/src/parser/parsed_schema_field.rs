@@ -2,11 +2,15 @@ use std::path::PathBuf;
 use tree_sitter::Node;
 
 use super::{
-    parsed_value::ParsedValue, parser_errors::ValueParseError, parser_utils::find_child_of_kind,
+    parsed_value::ParsedValue,
+    parser_errors::ValueParseError,
+    parser_utils::{find_child_of_kind, get_node_text},
 };
 
 const FIELD_KIND: &str = "field";
 const BLOCK_KIND: &str = "block";
+const NESTED_BLOCK_KIND: &str = "nested_block";
+const BLOCK_TYPE_KIND: &str = "block_type";
 
 /// A parsed schema field definition from a schema block.
 ///
@@ -16,6 +20,31 @@ const BLOCK_KIND: &str = "block";
 ///     name = "title"
 ///     type = "string"
 ///     required = true
+///     default = "Untitled"
+/// }
+/// ```
+///
+/// String fields can also declare states and the transitions between them:
+/// ```text
+/// field {
+///     name = "status"
+///     type = "string"
+///     states = ["open", "won", "lost"]
+///     transitions = ["open -> won", "open -> lost"]
+///     stamp = "status_changed_at"
+/// }
+/// ```
+///
+/// Record fields (and lists of records) declare their inner fields as nested field blocks:
+/// ```text
+/// field {
+///     name = "address"
+///     type = "record"
+///     field {
+///         name = "city"
+///         type = "string"
+///         required = true
+///     }
 /// }
 /// ```
 #[derive(Debug)]
@@ -67,6 +96,202 @@ impl<'a> ParsedSchemaField<'a> {
         false // Default to false if not specified or invalid
     }
 
+    /// Gets the literal default value from the "default" field, if set.
+    pub fn default_value(&self) -> Result<Option<ParsedValue>, ValueParseError> {
+        match self.find_field_by_name("default") {
+            Some(default_field) => Ok(Some(default_field.value()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the auto-population rule from the "auto" field (e.g., "now"), if set.
+    pub fn auto(&self) -> Option<String> {
+        let auto_field = self.find_field_by_name("auto")?;
+
+        match auto_field.value() {
+            Ok(ParsedValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the default should be written to the DSL when an entity is created.
+    /// Defaults to false if not specified.
+    pub fn materialize(&self) -> bool {
+        if let Some(materialize_field) = self.find_field_by_name("materialize") {
+            if let Ok(ParsedValue::Boolean(b)) = materialize_field.value() {
+                return b;
+            }
+        }
+
+        false
+    }
+
+    /// Gets the minimum numeric value from the "min" field, if set.
+    pub fn min(&self) -> Result<Option<f64>, ValueParseError> {
+        self.find_number("min")
+    }
+
+    /// Gets the maximum numeric value from the "max" field, if set.
+    pub fn max(&self) -> Result<Option<f64>, ValueParseError> {
+        self.find_number("max")
+    }
+
+    /// Gets the regular expression from the "pattern" field, if set.
+    pub fn pattern(&self) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name("pattern") {
+            Some(pattern_field) => match pattern_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the minimum string length from the "min_length" field, if set.
+    pub fn min_length(&self) -> Result<Option<usize>, ValueParseError> {
+        self.find_count("min_length")
+    }
+
+    /// Gets the maximum string length from the "max_length" field, if set.
+    pub fn max_length(&self) -> Result<Option<usize>, ValueParseError> {
+        self.find_count("max_length")
+    }
+
+    /// Gets the minimum number of list items from the "min_items" field, if set.
+    pub fn min_items(&self) -> Result<Option<usize>, ValueParseError> {
+        self.find_count("min_items")
+    }
+
+    /// Gets the maximum number of list items from the "max_items" field, if set.
+    pub fn max_items(&self) -> Result<Option<usize>, ValueParseError> {
+        self.find_count("max_items")
+    }
+
+    /// Gets the entity type references must point to from the "references" field, if set.
+    pub fn references(&self) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name("references") {
+            Some(references_field) => match references_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the dimension quantities must be measured in (e.g., "time") from the "dimension" field, if set.
+    pub fn dimension(&self) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name("dimension") {
+            Some(dimension_field) => match dimension_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Checks whether values of the field must be unique across entities.
+    /// Defaults to false if not specified.
+    pub fn unique(&self) -> bool {
+        if let Some(unique_field) = self.find_field_by_name("unique") {
+            if let Ok(ParsedValue::Boolean(b)) = unique_field.value() {
+                return b;
+            }
+        }
+
+        false
+    }
+
+    /// Gets the states the field can be in from the "states" field, if set.
+    pub fn states(&self) -> Result<Option<Vec<String>>, ValueParseError> {
+        self.find_string_list("states")
+    }
+
+    /// Gets the allowed transitions between states (e.g., "open -> won") from the "transitions" field, if set.
+    pub fn transitions(&self) -> Result<Option<Vec<String>>, ValueParseError> {
+        self.find_string_list("transitions")
+    }
+
+    /// Gets the name of the field to stamp with the time of each transition from the "stamp" field, if set.
+    pub fn stamp(&self) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name("stamp") {
+            Some(stamp_field) => match stamp_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the nested field definitions of a record field, in the order they are declared.
+    pub fn fields(&self) -> Vec<ParsedSchemaField<'a>> {
+        let mut fields = Vec::new();
+        let Some(block_node) = find_child_of_kind(&self.node, BLOCK_KIND) else {
+            return fields;
+        };
+
+        let mut cursor = block_node.walk();
+        for child in block_node.children(&mut cursor) {
+            if child.kind() == NESTED_BLOCK_KIND {
+                let mut nested_cursor = child.walk();
+                let is_field = child
+                    .children(&mut nested_cursor)
+                    .find(|c| c.kind() == BLOCK_TYPE_KIND)
+                    .is_some_and(|block_type| {
+                        get_node_text(&block_type, self.source) == FIELD_KIND
+                    });
+
+                if is_field {
+                    fields.push(ParsedSchemaField::new(child, self.source, self.path));
+                }
+            }
+        }
+
+        fields
+    }
+
+    /// Helper method to find a list of strings setting by name.
+    fn find_string_list(&self, field_name: &str) -> Result<Option<Vec<String>>, ValueParseError> {
+        match self.find_field_by_name(field_name) {
+            Some(list_field) => match list_field.value()? {
+                ParsedValue::List(items) => items
+                    .into_iter()
+                    .map(|item| match item {
+                        ParsedValue::String(s) => Ok(s),
+                        _ => Err(ValueParseError::UnknownValueKind),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Some),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Helper method to find an integer or float setting by name.
+    fn find_number(&self, field_name: &str) -> Result<Option<f64>, ValueParseError> {
+        match self.find_field_by_name(field_name) {
+            Some(number_field) => match number_field.value()? {
+                ParsedValue::Integer(i) => Ok(Some(i as f64)),
+                ParsedValue::Float(f) => Ok(Some(f)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Helper method to find a non-negative integer setting by name.
+    fn find_count(&self, field_name: &str) -> Result<Option<usize>, ValueParseError> {
+        match self.find_field_by_name(field_name) {
+            Some(count_field) => match count_field.value()? {
+                ParsedValue::Integer(i) => usize::try_from(i)
+                    .map(Some)
+                    .map_err(|_| ValueParseError::InvalidInteger(i.to_string())),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
     /// Helper method to find a field by name within this schema field block.
     fn find_field_by_name(&self, field_name: &str) -> Option<super::ParsedField> {
         // Find the block node within this field
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use tree_sitter::Node;
+
+use super::{
+    parsed_value::ParsedValue, parser_errors::ValueParseError, parser_utils::find_child_of_kind,
+};
+
+const FIELD_KIND: &str = "field";
+const BLOCK_KIND: &str = "block";
+
+/// A parsed migration step from a schema block.
+///
+/// Represents a nested migration block which renames a field:
+/// ```text
+/// migration {
+///     rename = "title"
+///     to = "name"
+/// }
+/// ```
+///
+/// Converts a field to another type, or to the type declared in the schema if "to" is left out:
+/// ```text
+/// migration {
+///     convert = "probability"
+///     to = "float"
+/// }
+/// ```
+///
+/// Or fills a field on entities which don't have it, with the schema default if "value" is left out:
+/// ```text
+/// migration {
+///     fill = "region"
+///     value = "EU"
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ParsedSchemaMigration<'a> {
+    node: Node<'a>,
+    source: &'a str,
+    path: &'a PathBuf,
+}
+
+impl<'a> ParsedSchemaMigration<'a> {
+    /// Creates a new ParsedSchemaMigration from a tree-sitter node and source text.
+    pub fn new(node: Node<'a>, source: &'a str, path: &'a PathBuf) -> Self {
+        Self { node, source, path }
+    }
+
+    /// Gets the name of the field to rename from the "rename" field, if set.
+    pub fn rename(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("rename")
+    }
+
+    /// Gets the name of the field to convert from the "convert" field, if set.
+    pub fn convert(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("convert")
+    }
+
+    /// Gets the name of the field to fill from the "fill" field, if set.
+    pub fn fill(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("fill")
+    }
+
+    /// Gets the new field name or target type from the "to" field, if set.
+    pub fn to(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("to")
+    }
+
+    /// Gets the value to fill in from the "value" field, if set.
+    pub fn value(&self) -> Result<Option<ParsedValue>, ValueParseError> {
+        match self.find_field_by_name("value") {
+            Some(value_field) => Ok(Some(value_field.value()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Helper method to find a string setting by name.
+    fn find_string(&self, field_name: &str) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name(field_name) {
+            Some(string_field) => match string_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Helper method to find a field by name within this migration block.
+    fn find_field_by_name(&self, field_name: &str) -> Option<super::ParsedField<'a>> {
+        let block_node = find_child_of_kind(&self.node, BLOCK_KIND)?;
+        let mut cursor = block_node.walk();
+
+        for child in block_node.children(&mut cursor) {
+            if child.kind() == FIELD_KIND {
+                let field = super::ParsedField::new(child, self.source, self.path);
+                if field.id() == Some(field_name) {
+                    return Some(field);
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+use tree_sitter::Node;
+
+use super::{
+    parsed_value::ParsedValue, parser_errors::ValueParseError, parser_utils::find_child_of_kind,
+};
+
+const FIELD_KIND: &str = "field";
+const BLOCK_KIND: &str = "block";
+
+/// A parsed validation rule from a schema block.
+///
+/// Represents a nested rule block which either requires a field under a condition:
+/// ```text
+/// rule {
+///     require = "completed_at"
+///     when = "is_completed"
+///     equals = true
+///     message = "Completed tasks need a completion date"
+/// }
+/// ```
+///
+/// Or compares the values of two fields:
+/// ```text
+/// rule {
+///     compare = "end_date > start_date"
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ParsedSchemaRule<'a> {
+    node: Node<'a>,
+    source: &'a str,
+    path: &'a PathBuf,
+}
+
+impl<'a> ParsedSchemaRule<'a> {
+    /// Creates a new ParsedSchemaRule from a tree-sitter node and source text.
+    pub fn new(node: Node<'a>, source: &'a str, path: &'a PathBuf) -> Self {
+        Self { node, source, path }
+    }
+
+    /// Gets the name of the field the rule requires from the "require" field, if set.
+    pub fn require(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("require")
+    }
+
+    /// Gets the name of the field that decides when the rule applies from the "when" field, if set.
+    pub fn when(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("when")
+    }
+
+    /// Gets the value the "when" field must have for the rule to apply from the "equals" field, if set.
+    pub fn equals(&self) -> Result<Option<ParsedValue>, ValueParseError> {
+        match self.find_field_by_name("equals") {
+            Some(equals_field) => Ok(Some(equals_field.value()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the comparison expression (e.g., "end_date > start_date") from the "compare" field, if set.
+    pub fn compare(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("compare")
+    }
+
+    /// Gets the custom message for violations of the rule from the "message" field, if set.
+    pub fn message(&self) -> Result<Option<String>, ValueParseError> {
+        self.find_string("message")
+    }
+
+    /// Helper method to find a string setting by name.
+    fn find_string(&self, field_name: &str) -> Result<Option<String>, ValueParseError> {
+        match self.find_field_by_name(field_name) {
+            Some(string_field) => match string_field.value()? {
+                ParsedValue::String(s) => Ok(Some(s)),
+                _ => Err(ValueParseError::UnknownValueKind),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Helper method to find a field by name within this rule block.
+    fn find_field_by_name(&self, field_name: &str) -> Option<super::ParsedField<'a>> {
+        let block_node = find_child_of_kind(&self.node, BLOCK_KIND)?;
+        let mut cursor = block_node.walk();
+
+        for child in block_node.children(&mut cursor) {
+            if child.kind() == FIELD_KIND {
+                let field = super::ParsedField::new(child, self.source, self.path);
+                if field.id() == Some(field_name) {
+                    return Some(field);
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -1,3 +1,4 @@
+use firm_core::{EntityId, compose_entity_id};
 use std::path::PathBuf;
 
 use tree_sitter::Tree;
@@ -49,6 +50,16 @@ impl ParsedSource {
         entities
     }
 
+    /// Finds the entity definition with the given ID, if the source has one.
+    pub fn find_entity(&self, entity_id: &EntityId) -> Option<ParsedEntity<'_>> {
+        self.entities()
+            .into_iter()
+            .find(|entity| match (entity.entity_type(), entity.id()) {
+                (Some(entity_type), Some(id)) => &compose_entity_id(entity_type, id) == entity_id,
+                _ => false,
+            })
+    }
+
     /// Extracts all schema definitions from the parsed source.
     pub fn schemas(&self) -> Vec<ParsedSchema> {
         let mut schemas = Vec::new();
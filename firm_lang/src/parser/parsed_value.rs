@@ -70,8 +70,10 @@ pub enum ParsedValue {
     },
     /// List of values (`["item1", "item2", 42]`)
     List(Vec<ParsedValue>),
-    /// Date or datetime value with timezone
+    /// Datetime value with timezone (`2024-03-20 at 14:30 UTC-5`)
     DateTime(DateTime<FixedOffset>),
+    /// Calendar date without a time (`2024-03-20`)
+    Date(NaiveDate),
     /// A path to a file or directory
     Path(PathBuf),
 }
@@ -89,6 +91,7 @@ impl ParsedValue {
             ParsedValue::FieldReference { .. } => "FieldReference",
             ParsedValue::List(_) => "List",
             ParsedValue::DateTime(_) => "DateTime",
+            ParsedValue::Date(_) => "Date",
             ParsedValue::Path(_) => "Path",
         }
     }
@@ -259,23 +262,12 @@ impl ParsedValue {
         Ok(ParsedValue::List(items))
     }
 
-    /// Parses date values (`2024-03-20`) as datetime at midnight local time.
+    /// Parses date values (`2024-03-20`) as calendar dates, independent of timezone.
     fn parse_date(raw: &str) -> Result<ParsedValue, ValueParseError> {
         // Parse "naive date" in year-month-day format (2025-07-31)
-        let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
-            .map_err(|_| ValueParseError::InvalidDate(raw.to_string()))?;
-
-        // Assume time is midnight local time
-        let datetime = date.and_hms_opt(0, 0, 0).unwrap();
-        let local_offset = Local::now().offset().fix();
-
-        // Convert from local datetime to timezoned datetime
-        let with_tz = local_offset
-            .from_local_datetime(&datetime)
-            .single()
-            .ok_or_else(|| ValueParseError::InvalidDate(raw.to_string()))?;
-
-        Ok(ParsedValue::DateTime(with_tz))
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(ParsedValue::Date)
+            .map_err(|_| ValueParseError::InvalidDate(raw.to_string()))
     }
 
     /// Parses datetime values with optional timezone (`2024-03-20 at 14:30 UTC-5`).
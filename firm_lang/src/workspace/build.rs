@@ -1,18 +1,38 @@
-use firm_core::{Entity, EntitySchema, EntityType};
-use std::collections::HashMap;
+use firm_core::{
+    Entity, EntitySchema, EntityType,
+    money::ExchangeRates,
+    schema::{UnknownFieldPolicy, ValidationError},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use super::{Workspace, WorkspaceError};
+use crate::convert::{ComposableSchema, SchemaConversionError, SchemaMode, SchemaResolver};
 
 /// Holds converted entities and schemas after the workspace is built.
 #[derive(Debug)]
 pub struct WorkspaceBuild {
     pub entities: Vec<Entity>,
     pub schemas: Vec<EntitySchema>,
+    pub warnings: Vec<ValidationError>,
 }
 
 impl WorkspaceBuild {
     pub fn new(entities: Vec<Entity>, schemas: Vec<EntitySchema>) -> Self {
-        WorkspaceBuild { entities, schemas }
+        WorkspaceBuild {
+            entities,
+            schemas,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Builder method to add warnings found while building the workspace.
+    pub fn with_warnings(mut self, warnings: Vec<ValidationError>) -> Self {
+        self.warnings = warnings;
+        self
     }
 }
 
@@ -32,37 +52,17 @@ impl Workspace {
     where
         F: FnMut(usize, usize, &str),
     {
-        // Get all built-in schemas
-        let builtin_schemas = EntitySchema::all_builtin();
-        let mut schemas: HashMap<EntityType, EntitySchema> = builtin_schemas
-            .into_iter()
-            .map(|schema| (schema.entity_type.clone(), schema))
-            .collect();
-
         let files_to_process = self.num_files();
         let mut files_processed = 0;
         progress(files_to_process, files_processed, "Building schemas");
 
-        // First pass: Walk through workspace files to add custom schemas
-        for (path, file) in &self.files {
-            let parsed_schemas = file.parsed.schemas();
-            for parsed_schema in &parsed_schemas {
-                let schema = EntitySchema::try_from(parsed_schema)
-                    .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
-
-                if schemas.contains_key(&schema.entity_type) {
-                    return Err(WorkspaceError::ValidationError(
-                        path.clone(),
-                        "Stuff".to_string(),
-                    ));
-                }
-
-                schemas.insert(schema.entity_type.clone(), schema);
-            }
-        }
+        // First pass: Combine built-in schemas with the custom schemas from workspace files
+        let schemas = self.build_schemas()?;
 
         // Second pass: Walk through workspace files to build and validate entities against schemas
         let mut entities = Vec::new();
+        let mut entity_paths = HashMap::new();
+        let mut warnings = Vec::new();
 
         files_processed = 0;
 
@@ -72,7 +72,7 @@ impl Workspace {
             let parsed_entities = file.parsed.entities();
             for parsed_entity in &parsed_entities {
                 // Build the entity
-                let entity = Entity::try_from(parsed_entity)
+                let mut entity = Entity::try_from(parsed_entity)
                     .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
 
                 // Find the appropriate schema for this entity
@@ -83,6 +83,12 @@ impl Workspace {
                     )
                 })?;
 
+                // Populate fields the entity leaves out with their defaults
+                schema.apply_defaults(&mut entity);
+
+                // Read values written in another form of their field's type, like durations written as strings
+                schema.coerce_values(&mut entity);
+
                 // Validate the entity against its schema
                 if let Err(validation_errors) = schema.validate(&entity) {
                     let error_msg = format!(
@@ -92,13 +98,247 @@ impl Workspace {
                     return Err(WorkspaceError::ValidationError(path.clone(), error_msg));
                 }
 
+                // Report undeclared fields without failing the build, if the schema asks for it
+                if schema.unknown_field_policy() == UnknownFieldPolicy::Warn {
+                    warnings.extend(schema.find_unknown_fields(&entity));
+                }
+
+                // Report broken rules and constraints which only warn, like those of built-in schemas
+                warnings.extend(schema.find_rule_warnings(&entity));
+                warnings.extend(schema.find_constraint_warnings(&entity));
+
+                entity_paths.insert(entity.id.clone(), path.clone());
                 entities.push(entity);
             }
 
             files_processed += 1;
         }
 
+        // Check uniqueness constraints, which span all entities of a type
+        for schema in schemas.values() {
+            if let Err(validation_errors) = schema.validate_uniqueness(&entities) {
+                let error = &validation_errors[0];
+                let path = error
+                    .entity_id
+                    .as_ref()
+                    .and_then(|entity_id| entity_paths.get(entity_id))
+                    .cloned()
+                    .unwrap_or_default();
+
+                let error_msg = format!(
+                    "Entities of type '{}' failed validation: {:?}",
+                    schema.entity_type, validation_errors
+                );
+                return Err(WorkspaceError::ValidationError(path, error_msg));
+            }
+
+            warnings.extend(schema.find_uniqueness_warnings(&entities));
+        }
+
+        // Report exchange rates which are left out of currency conversions
+        warnings.extend(ExchangeRates::find_warnings(&entities));
+
         let schemas_vec = schemas.into_values().collect();
-        Ok(WorkspaceBuild::new(entities, schemas_vec))
+        Ok(WorkspaceBuild::new(entities, schemas_vec).with_warnings(warnings))
+    }
+
+    /// Builds only the schemas of the workspace, without converting or validating entities.
+    pub fn schemas(&self) -> Result<Vec<EntitySchema>, WorkspaceError> {
+        Ok(self.build_schemas()?.into_values().collect())
+    }
+
+    /// Builds the schemas available to the workspace.
+    ///
+    /// Custom schemas are added to the enabled built-in schemas, after resolving which schemas they extend or include.
+    /// Extensions of existing schemas are applied before other schemas build on them, and abstract schemas are left out of the result.
+    /// Schemas that don't set how to treat undeclared fields use the workspace policy.
+    pub(super) fn build_schemas(
+        &self,
+    ) -> Result<HashMap<EntityType, EntitySchema>, WorkspaceError> {
+        let builtin_schemas = EntitySchema::builtin_groups(&self.options.builtin_groups);
+
+        let mut schemas: HashMap<EntityType, (EntitySchema, SchemaSource)> = builtin_schemas
+            .iter()
+            .map(|schema| {
+                (
+                    schema.entity_type.clone(),
+                    (schema.clone(), SchemaSource::BuiltIn),
+                )
+            })
+            .collect();
+
+        // Built-in schemas and field groups that custom schemas can extend or include
+        let mut available: HashMap<EntityType, EntitySchema> = builtin_schemas
+            .into_iter()
+            .map(|schema| (schema.entity_type.clone(), schema))
+            .collect();
+        let metadata_group = EntitySchema::metadata_group();
+        available.insert(metadata_group.entity_type.clone(), metadata_group);
+
+        // Sort files by path so that schemas are combined in a predictable order
+        let mut paths: Vec<&PathBuf> = self.files.keys().collect();
+        paths.sort();
+
+        let mut definitions = Vec::new();
+        let mut extensions = Vec::new();
+        for path in paths {
+            let file = &self.files[path];
+            for parsed_schema in &file.parsed.schemas() {
+                let mode = SchemaMode::try_from(parsed_schema)
+                    .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
+                let schema = ComposableSchema::try_from(parsed_schema)
+                    .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
+                let entity_type = &schema.schema.entity_type;
+
+                match (mode, schemas.get(entity_type)) {
+                    (SchemaMode::Extend, _) => {
+                        extensions.push((path, schema));
+                        continue;
+                    }
+                    (SchemaMode::Define, None)
+                    | (SchemaMode::Override, Some((_, SchemaSource::BuiltIn))) => {}
+                    (SchemaMode::Define, Some((_, SchemaSource::BuiltIn))) => {
+                        return Err(WorkspaceError::SchemaConflict(
+                            path.clone(),
+                            format!(
+                                "Schema '{}' is already defined by {}. Set mode = \"extend\" to add fields to it or mode = \"override\" to replace it",
+                                entity_type,
+                                SchemaSource::BuiltIn
+                            ),
+                        ));
+                    }
+                    (SchemaMode::Define | SchemaMode::Override, Some((_, existing))) => {
+                        return Err(WorkspaceError::SchemaConflict(
+                            path.clone(),
+                            format!(
+                                "Schema '{}' is already defined in {}",
+                                entity_type, existing
+                            ),
+                        ));
+                    }
+                    (SchemaMode::Override, None) => {
+                        return Err(WorkspaceError::SchemaConflict(
+                            path.clone(),
+                            format!(
+                                "Schema '{}' overrides a built-in schema, but no enabled built-in schema has that name",
+                                entity_type
+                            ),
+                        ));
+                    }
+                }
+
+                // Keep a placeholder until the schema is resolved, so later definitions are checked against it
+                schemas.insert(
+                    entity_type.clone(),
+                    (schema.schema.clone(), SchemaSource::File(path.clone())),
+                );
+                definitions.push((path, schema));
+            }
+        }
+
+        for (path, extension) in &extensions {
+            let entity_type = &extension.schema.entity_type;
+            if !schemas.contains_key(entity_type) {
+                return Err(WorkspaceError::SchemaConflict(
+                    (*path).clone(),
+                    format!(
+                        "Schema '{}' is set to extend an existing schema, but none is defined",
+                        entity_type
+                    ),
+                ));
+            }
+        }
+
+        // Resolve what each custom schema extends and includes.
+        // Extensions are applied as part of resolving, so schemas that build on an extended schema see its added fields.
+        let mut resolver = SchemaResolver::new(definitions.iter().map(|(_, s)| s), &available)
+            .with_extensions(extensions.iter().map(|(_, s)| s));
+        let resolve_order = extensions
+            .iter()
+            .chain(&definitions)
+            .map(|(path, schema)| (*path, &schema.schema.entity_type));
+
+        for (path, entity_type) in resolve_order {
+            let resolved = resolver
+                .resolve(entity_type)
+                .map_err(|err| resolve_error(err, path, &schemas, &extensions))?;
+
+            if let Some((schema, _)) = schemas.get_mut(entity_type) {
+                *schema = resolved;
+            }
+        }
+
+        for (_, definition) in &definitions {
+            if definition.is_abstract {
+                schemas.remove(&definition.schema.entity_type);
+            }
+        }
+
+        Ok(schemas
+            .into_iter()
+            .map(|(entity_type, (mut schema, _))| {
+                schema
+                    .unknown_fields
+                    .get_or_insert(self.options.unknown_fields);
+                (entity_type, schema)
+            })
+            .collect())
+    }
+}
+
+/// Converts an error from resolving a schema to a workspace error for the file being resolved.
+/// Conflicting extensions are reported against the extension's file, naming where the extended schema was defined.
+fn resolve_error(
+    err: SchemaConversionError,
+    path: &Path,
+    schemas: &HashMap<EntityType, (EntitySchema, SchemaSource)>,
+    extensions: &[(&PathBuf, ComposableSchema)],
+) -> WorkspaceError {
+    let SchemaConversionError::ConflictingExtension(schema_name, index, conflicts) = err else {
+        return WorkspaceError::ParseError(path.to_path_buf(), err.to_string());
+    };
+
+    let extension_path = extensions[index].0;
+    let source = schemas
+        .get(&extensions[index].1.schema.entity_type)
+        .map(|(_, source)| source.clone())
+        .unwrap_or(SchemaSource::BuiltIn);
+    let details: Vec<String> = conflicts
+        .iter()
+        .map(|conflict| {
+            format!(
+                "field '{}' is '{}' in {} but '{}' in {}",
+                conflict.field,
+                conflict.existing,
+                source,
+                conflict.incoming,
+                extension_path.display()
+            )
+        })
+        .collect();
+
+    WorkspaceError::SchemaConflict(
+        extension_path.clone(),
+        format!(
+            "Schema '{}' cannot be extended: {}",
+            schema_name,
+            details.join(", ")
+        ),
+    )
+}
+
+/// Where a schema in the workspace was originally defined.
+#[derive(Debug, Clone)]
+enum SchemaSource {
+    BuiltIn,
+    File(PathBuf),
+}
+
+impl fmt::Display for SchemaSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaSource::BuiltIn => write!(f, "the built-in schemas"),
+            SchemaSource::File(path) => write!(f, "{}", path.display()),
+        }
     }
 }
@@ -0,0 +1,107 @@
+use firm_core::{
+    Entity, EntityId, EntityType,
+    migration::{FieldChange, Migration},
+};
+use std::{collections::HashMap, path::PathBuf};
+
+use super::{Workspace, WorkspaceError};
+use crate::convert::ComposableSchema;
+use crate::edit::edit_entities;
+
+/// A workspace file changed by a migration.
+#[derive(Debug)]
+pub struct MigratedFile {
+    pub path: PathBuf,
+    pub original: String,
+    pub migrated: String,
+    pub entities: Vec<EntityId>,
+}
+
+impl Workspace {
+    /// Collects the migrations declared by schemas in the workspace files.
+    pub fn declared_migrations(&self) -> Result<Vec<Migration>, WorkspaceError> {
+        let mut paths: Vec<&PathBuf> = self.files.keys().collect();
+        paths.sort();
+
+        let mut migrations = Vec::new();
+        for path in paths {
+            for parsed_schema in &self.files[path].parsed.schemas() {
+                let schema = ComposableSchema::try_from(parsed_schema)
+                    .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
+
+                if !schema.migration.is_empty() {
+                    migrations.push(schema.migration);
+                }
+            }
+        }
+
+        Ok(migrations)
+    }
+
+    /// Migrates entities in the workspace files, returning the source of each file that changes.
+    ///
+    /// Entities are migrated as they are written, without defaults or validation,
+    /// so files can be migrated after their schema has changed. Migrations for the same
+    /// entity type are applied in order. The files themselves are not written.
+    pub fn migrate(&self, migrations: &[Migration]) -> Result<Vec<MigratedFile>, WorkspaceError> {
+        let schemas = self.build_schemas()?;
+
+        let mut combined: HashMap<&EntityType, Migration> = HashMap::new();
+        for migration in migrations {
+            combined
+                .entry(&migration.entity_type)
+                .or_insert_with(|| Migration::new(migration.entity_type.clone()))
+                .steps
+                .extend(migration.steps.iter().cloned());
+        }
+
+        let mut paths: Vec<&PathBuf> = self.files.keys().collect();
+        paths.sort();
+
+        let mut migrated_files = Vec::new();
+        for path in paths {
+            let parsed = &self.files[path].parsed;
+            let mut edits: Vec<(EntityId, Vec<FieldChange>)> = Vec::new();
+
+            for parsed_entity in &parsed.entities() {
+                let entity = Entity::try_from(parsed_entity)
+                    .map_err(|err| WorkspaceError::ParseError(path.clone(), err.to_string()))?;
+
+                let Some(migration) = combined.get(&entity.entity_type) else {
+                    continue;
+                };
+
+                let schema = schemas.get(&entity.entity_type).ok_or_else(|| {
+                    WorkspaceError::MigrationError(
+                        path.clone(),
+                        format!("No schema found for entity type: {:?}", entity.entity_type),
+                    )
+                })?;
+
+                let changes = migration.apply(&entity, schema).map_err(|err| {
+                    WorkspaceError::MigrationError(
+                        path.clone(),
+                        format!("Entity '{}' could not be migrated: {}", entity.id, err),
+                    )
+                })?;
+
+                if !changes.is_empty() {
+                    edits.push((entity.id, changes));
+                }
+            }
+
+            if edits.is_empty() {
+                continue;
+            }
+
+            migrated_files.push(MigratedFile {
+                path: path.clone(),
+                original: parsed.source.clone(),
+                migrated: edit_entities(parsed, &edits),
+                entities: edits.into_iter().map(|(entity_id, _)| entity_id).collect(),
+            });
+        }
+
+        Ok(migrated_files)
+    }
+}
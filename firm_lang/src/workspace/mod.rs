@@ -1,11 +1,16 @@
 mod build;
 mod io;
+mod migrate;
 mod workspace_errors;
+mod workspace_options;
 
+use firm_core::EntityId;
 use std::{collections::HashMap, path::PathBuf};
 
 pub use build::WorkspaceBuild;
+pub use migrate::MigratedFile;
 pub use workspace_errors::WorkspaceError;
+pub use workspace_options::WorkspaceOptions;
 
 use crate::parser::ParsedSource;
 
@@ -16,12 +21,19 @@ use crate::parser::ParsedSource;
 #[derive(Debug)]
 pub struct Workspace {
     files: HashMap<PathBuf, WorkspaceFile>,
+    options: WorkspaceOptions,
 }
 
 impl Workspace {
     pub fn new() -> Self {
+        Self::with_options(WorkspaceOptions::default())
+    }
+
+    /// Creates an empty workspace with custom build options.
+    pub fn with_options(options: WorkspaceOptions) -> Self {
         Self {
             files: HashMap::new(),
+            options,
         }
     }
 
@@ -29,6 +41,14 @@ impl Workspace {
     pub fn num_files(&self) -> usize {
         self.files.len()
     }
+
+    /// Finds the file which defines an entity, returning its path and parsed source.
+    pub fn find_entity_source(&self, entity_id: &EntityId) -> Option<(&PathBuf, &ParsedSource)> {
+        self.files
+            .iter()
+            .find(|(_, file)| file.parsed.find_entity(entity_id).is_some())
+            .map(|(path, file)| (path, &file.parsed))
+    }
 }
 
 /// Represents a parsed file in the workspace.
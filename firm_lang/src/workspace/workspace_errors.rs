@@ -6,6 +6,8 @@ pub enum WorkspaceError {
     IoError(io::Error),
     ParseError(PathBuf, String),
     ValidationError(PathBuf, String),
+    SchemaConflict(PathBuf, String),
+    MigrationError(PathBuf, String),
 }
 
 impl fmt::Display for WorkspaceError {
@@ -26,6 +28,18 @@ impl fmt::Display for WorkspaceError {
                 path_buf.display(),
                 error
             ),
+            WorkspaceError::SchemaConflict(path_buf, error) => write!(
+                f,
+                "Workspace file at {} has a conflicting schema: {}",
+                path_buf.display(),
+                error
+            ),
+            WorkspaceError::MigrationError(path_buf, error) => write!(
+                f,
+                "Workspace file at {} could not be migrated: {}",
+                path_buf.display(),
+                error
+            ),
         }
     }
 }
@@ -0,0 +1,47 @@
+use firm_core::schema::{BuiltinGroup, UnknownFieldPolicy};
+
+/// Options that control how a workspace is built.
+#[derive(Debug, Clone)]
+pub struct WorkspaceOptions {
+    /// The groups of built-in schemas available to the workspace.
+    pub builtin_groups: Vec<BuiltinGroup>,
+    /// How to treat entity fields not declared in their schema, unless the schema sets it.
+    pub unknown_fields: UnknownFieldPolicy,
+}
+
+impl Default for WorkspaceOptions {
+    fn default() -> Self {
+        Self {
+            builtin_groups: BuiltinGroup::defaults(),
+            unknown_fields: UnknownFieldPolicy::Warn,
+        }
+    }
+}
+
+impl WorkspaceOptions {
+    /// Builder method to opt in to a group of built-in schemas, like exchange rates.
+    pub fn with_builtin_group(mut self, group: BuiltinGroup) -> Self {
+        if !self.builtin_groups.contains(&group) {
+            self.builtin_groups.push(group);
+        }
+        self
+    }
+
+    /// Builder method to opt out of a group of built-in schemas.
+    pub fn without_builtin_group(mut self, group: BuiltinGroup) -> Self {
+        self.builtin_groups.retain(|g| *g != group);
+        self
+    }
+
+    /// Builder method to opt out of all built-in schemas.
+    pub fn without_builtin_schemas(mut self) -> Self {
+        self.builtin_groups.clear();
+        self
+    }
+
+    /// Builder method to set how to treat undeclared fields for schemas that don't set it.
+    pub fn with_unknown_fields(mut self, policy: UnknownFieldPolicy) -> Self {
+        self.unknown_fields = policy;
+        self
+    }
+}
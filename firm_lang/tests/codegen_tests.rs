@@ -0,0 +1,111 @@
+use firm_core::{
+    Entity, EntityId, EntitySchema, EntityType, FieldId, FieldType, FieldValue, ReferenceValue,
+    schema::{FieldConstraints, ValidationErrorType},
+    typed::EntityRef,
+};
+use firm_lang::codegen::generate_rust;
+
+/// The module generated from `schemas()`, checked in so the tests compile it.
+mod generated {
+    include!("fixtures/generated_entities.rs");
+}
+
+use generated::{Person, ProjectTask};
+
+fn references(target: &str) -> FieldConstraints {
+    FieldConstraints {
+        references: Some(EntityType::new(target)),
+        ..Default::default()
+    }
+}
+
+fn schemas() -> Vec<EntitySchema> {
+    vec![
+        EntitySchema::new(EntityType::new("project_task"))
+            .with_required_field(FieldId::new("name"), FieldType::String)
+            .with_optional_field(FieldId::new("assignee_ref"), FieldType::Reference)
+            .with_optional_field(FieldId::new("client_ref"), FieldType::Reference)
+            .with_optional_field(FieldId::new("source_ref"), FieldType::Reference)
+            .with_optional_field(FieldId::new("is_completed"), FieldType::Boolean)
+            .with_optional_field(FieldId::new("priority"), FieldType::Integer)
+            .with_optional_field(FieldId::new("progress"), FieldType::Float)
+            .with_optional_field(FieldId::new("budget"), FieldType::Currency)
+            .with_optional_field(FieldId::new("tags"), FieldType::List)
+            .with_optional_field(FieldId::new("created_at"), FieldType::DateTime)
+            .with_optional_field(FieldId::new("due_date"), FieldType::Date)
+            .with_optional_field(FieldId::new("estimate"), FieldType::Duration)
+            .with_optional_field(FieldId::new("check_in"), FieldType::Recurrence)
+            .with_optional_field(FieldId::new("effort"), FieldType::Quantity)
+            .with_optional_field(FieldId::new("address"), FieldType::Record)
+            .with_optional_field(FieldId::new("deliverable"), FieldType::Path)
+            .with_optional_field(FieldId::new("type"), FieldType::String)
+            .with_constraints(&FieldId::new("assignee_ref"), references("person"))
+            .with_constraints(&FieldId::new("client_ref"), references("organization")),
+        EntitySchema::new(EntityType::new("person"))
+            .with_required_field(FieldId::new("name"), FieldType::String),
+    ]
+}
+
+#[test]
+fn test_fixture_matches_generated_code() {
+    assert_eq!(
+        generate_rust(&schemas()),
+        include_str!("fixtures/generated_entities.rs"),
+        "Regenerate tests/fixtures/generated_entities.rs from the schemas in this test"
+    );
+}
+
+#[test]
+fn test_generated_references_are_typed() {
+    let task = ProjectTask::try_from(
+        &Entity::new(
+            EntityId::new("project_task.write_docs"),
+            EntityType::new("project_task"),
+        )
+        .with_field(FieldId::new("name"), "Write docs")
+        .with_field(
+            FieldId::new("assignee_ref"),
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::new("person.jane"))),
+        )
+        .with_field(
+            FieldId::new("client_ref"),
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::new("organization.acme"))),
+        )
+        .with_field(
+            FieldId::new("source_ref"),
+            FieldValue::Reference(ReferenceValue::Field(
+                EntityId::new("person.jane"),
+                FieldId::new("name"),
+            )),
+        ),
+    )
+    .unwrap();
+
+    let assignee: Option<EntityRef<Person>> = task.assignee_ref.clone();
+    assert_eq!(assignee, Some(EntityRef::new(EntityId::new("person.jane"))));
+    assert_eq!(task.client_ref, Some(EntityId::new("organization.acme")));
+    assert!(matches!(task.source_ref, Some(ReferenceValue::Field(..))));
+
+    let entity = Entity::from(&task);
+    assert_eq!(ProjectTask::try_from(&entity), Ok(task));
+}
+
+#[test]
+fn test_generated_reference_target_is_checked() {
+    let result = ProjectTask::try_from(
+        &Entity::new(
+            EntityId::new("project_task.write_docs"),
+            EntityType::new("project_task"),
+        )
+        .with_field(FieldId::new("name"), "Write docs")
+        .with_field(
+            FieldId::new("assignee_ref"),
+            FieldValue::Reference(ReferenceValue::Entity(EntityId::new("organization.acme"))),
+        ),
+    );
+
+    assert!(matches!(
+        result.unwrap_err().error_type,
+        ValidationErrorType::MismatchedReferenceType { .. }
+    ));
+}
@@ -24,20 +24,20 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        assert_eq!(entity.id, EntityId("person.john_doe".to_string()));
+        assert_eq!(entity.id, EntityId::from("person.john_doe"));
         assert_eq!(entity.entity_type, EntityType::new("person"));
         assert_eq!(entity.fields.len(), 3);
 
         assert_eq!(
-            entity.get_field(&FieldId("name".to_string())),
+            entity.get_field(&FieldId::from("name")),
             Some(&FieldValue::String("John Doe".to_string()))
         );
         assert_eq!(
-            entity.get_field(&FieldId("age".to_string())),
+            entity.get_field(&FieldId::from("age")),
             Some(&FieldValue::Integer(42))
         );
         assert_eq!(
-            entity.get_field(&FieldId("active".to_string())),
+            entity.get_field(&FieldId::from("active")),
             Some(&FieldValue::Boolean(true))
         );
     }
@@ -55,15 +55,15 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        assert_eq!(entity.id, EntityId("organization.acme_corp".to_string()));
+        assert_eq!(entity.id, EntityId::from("organization.acme_corp"));
         assert_eq!(entity.entity_type, EntityType::new("organization"));
         assert_eq!(entity.fields.len(), 2);
         assert_eq!(
-            entity.get_field(&FieldId("name".to_string())),
+            entity.get_field(&FieldId::from("name")),
             Some(&FieldValue::String("ACME Corporation".to_string()))
         );
         assert_eq!(
-            entity.get_field(&FieldId("employees".to_string())),
+            entity.get_field(&FieldId::from("employees")),
             Some(&FieldValue::Integer(500))
         );
     }
@@ -81,15 +81,15 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        assert_eq!(entity.id, EntityId("project.alpha_project".to_string()));
+        assert_eq!(entity.id, EntityId::from("project.alpha_project"));
         assert_eq!(entity.entity_type, EntityType::new("project"));
         assert_eq!(entity.fields.len(), 2);
         assert_eq!(
-            entity.get_field(&FieldId("name".to_string())),
+            entity.get_field(&FieldId::from("name")),
             Some(&FieldValue::String("Project Alpha".to_string()))
         );
         assert_eq!(
-            entity.get_field(&FieldId("status".to_string())),
+            entity.get_field(&FieldId::from("status")),
             Some(&FieldValue::String("active".to_string()))
         );
     }
@@ -112,12 +112,11 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("single_line".to_string())),
+            entity.get_field(&FieldId::from("single_line")),
             Some(&FieldValue::String("Simple string".to_string()))
         );
 
-        if let Some(FieldValue::String(multi_line)) =
-            entity.get_field(&FieldId("multi_line".to_string()))
+        if let Some(FieldValue::String(multi_line)) = entity.get_field(&FieldId::from("multi_line"))
         {
             assert!(multi_line.contains("This is a"));
             assert!(multi_line.contains("multi-line string"));
@@ -135,11 +134,11 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("integer_field".to_string())),
+            entity.get_field(&FieldId::from("integer_field")),
             Some(&FieldValue::Integer(42))
         );
         assert_eq!(
-            entity.get_field(&FieldId("float_field".to_string())),
+            entity.get_field(&FieldId::from("float_field")),
             Some(&FieldValue::Float(3.14159))
         );
     }
@@ -158,11 +157,11 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("is_active".to_string())),
+            entity.get_field(&FieldId::from("is_active")),
             Some(&FieldValue::Boolean(true))
         );
         assert_eq!(
-            entity.get_field(&FieldId("is_deleted".to_string())),
+            entity.get_field(&FieldId::from("is_deleted")),
             Some(&FieldValue::Boolean(false))
         );
     }
@@ -181,14 +180,14 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("total".to_string())),
+            entity.get_field(&FieldId::from("total")),
             Some(&FieldValue::Currency {
                 amount: Decimal::from_str_exact("1250.75").unwrap(),
                 currency: Currency::USD
             })
         );
         assert_eq!(
-            entity.get_field(&FieldId("deposit".to_string())),
+            entity.get_field(&FieldId::from("deposit")),
             Some(&FieldValue::Currency {
                 amount: Decimal::from_str_exact("500").unwrap(),
                 currency: Currency::EUR
@@ -210,16 +209,16 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("manager".to_string())),
-            Some(&FieldValue::Reference(ReferenceValue::Entity(EntityId(
-                "person.john_doe".to_string()
-            ))))
+            entity.get_field(&FieldId::from("manager")),
+            Some(&FieldValue::Reference(ReferenceValue::Entity(
+                EntityId::from("person.john_doe".to_string())
+            )))
         );
         assert_eq!(
-            entity.get_field(&FieldId("company".to_string())),
-            Some(&FieldValue::Reference(ReferenceValue::Entity(EntityId(
-                "organization.acme_corp".to_string()
-            ))))
+            entity.get_field(&FieldId::from("company")),
+            Some(&FieldValue::Reference(ReferenceValue::Entity(
+                EntityId::from("organization.acme_corp".to_string())
+            )))
         );
     }
 
@@ -237,17 +236,17 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("manager_name".to_string())),
+            entity.get_field(&FieldId::from("manager_name")),
             Some(&FieldValue::Reference(ReferenceValue::Field(
-                EntityId("person.john_doe".to_string()),
-                FieldId("name".to_string())
+                EntityId::from("person.john_doe"),
+                FieldId::from("name")
             )))
         );
         assert_eq!(
-            entity.get_field(&FieldId("company_address".to_string())),
+            entity.get_field(&FieldId::from("company_address")),
             Some(&FieldValue::Reference(ReferenceValue::Field(
-                EntityId("organization.acme_corp".to_string()),
-                FieldId("address".to_string())
+                EntityId::from("organization.acme_corp"),
+                FieldId::from("address")
             )))
         );
     }
@@ -266,7 +265,7 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        if let Some(FieldValue::List(skills)) = &entity.get_field(&FieldId("skills".to_string())) {
+        if let Some(FieldValue::List(skills)) = &entity.get_field(&FieldId::from("skills")) {
             assert_eq!(skills.len(), 3);
             assert_eq!(skills[0], FieldValue::String("Rust".to_string()));
             assert_eq!(skills[1], FieldValue::String("JavaScript".to_string()));
@@ -275,7 +274,7 @@ mod tests {
             panic!("Expected List field value for skills");
         }
 
-        if let Some(FieldValue::List(scores)) = &entity.get_field(&FieldId("scores".to_string())) {
+        if let Some(FieldValue::List(scores)) = &entity.get_field(&FieldId::from("scores")) {
             assert_eq!(scores.len(), 3);
             assert_eq!(scores[0], FieldValue::Integer(95));
             assert_eq!(scores[1], FieldValue::Integer(87));
@@ -297,8 +296,7 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        if let Some(FieldValue::List(empty_list)) =
-            &entity.get_field(&FieldId("empty_list".to_string()))
+        if let Some(FieldValue::List(empty_list)) = &entity.get_field(&FieldId::from("empty_list"))
         {
             assert_eq!(empty_list.len(), 0);
         } else {
@@ -318,9 +316,7 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        if let Some(FieldValue::List(outer_list)) =
-            &entity.get_field(&FieldId("nested".to_string()))
-        {
+        if let Some(FieldValue::List(outer_list)) = &entity.get_field(&FieldId::from("nested")) {
             assert_eq!(outer_list.len(), 2);
 
             if let FieldValue::List(first_inner) = &outer_list[0] {
@@ -345,7 +341,7 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        if let Some(start_time_field) = entity.get_field(&FieldId("start_time".to_string())) {
+        if let Some(start_time_field) = entity.get_field(&FieldId::from("start_time")) {
             match start_time_field {
                 FieldValue::DateTime(start_time) => {
                     assert_eq!(start_time.year(), 2024);
@@ -382,11 +378,11 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("relative_path".to_string())),
+            entity.get_field(&FieldId::from("relative_path")),
             Some(&FieldValue::Path(PathBuf::from("./my/path.txt")))
         );
         assert_eq!(
-            entity.get_field(&FieldId("absolute_path".to_string())),
+            entity.get_field(&FieldId::from("absolute_path")),
             Some(&FieldValue::Path(PathBuf::from("/users/me/path.txt")))
         );
     }
@@ -408,7 +404,7 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("relative_path".to_string())),
+            entity.get_field(&FieldId::from("relative_path")),
             Some(&FieldValue::Path(PathBuf::from("./subdir/my/path.txt")))
         );
     }
@@ -427,7 +423,7 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("relative_path".to_string())),
+            entity.get_field(&FieldId::from("relative_path")),
             Some(&FieldValue::Path(PathBuf::from("./my/path.txt")))
         );
     }
@@ -449,7 +445,7 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("relative_path".to_string())),
+            entity.get_field(&FieldId::from("relative_path")),
             Some(&FieldValue::Path(PathBuf::from("./sibling/path.txt")))
         );
     }
@@ -471,7 +467,7 @@ mod tests {
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
         assert_eq!(
-            entity.get_field(&FieldId("relative_path".to_string())),
+            entity.get_field(&FieldId::from("relative_path")),
             Some(&FieldValue::Path(PathBuf::from("../path.txt")))
         );
     }
@@ -495,10 +491,10 @@ mod tests {
         let entity1: Entity = (&entities[0]).try_into().unwrap();
         let entity2: Entity = (&entities[1]).try_into().unwrap();
 
-        assert_eq!(entity1.id, EntityId("person.john_doe".to_string()));
+        assert_eq!(entity1.id, EntityId::from("person.john_doe"));
         assert_eq!(entity1.entity_type, EntityType::new("person"));
 
-        assert_eq!(entity2.id, EntityId("organization.acme_corp".to_string()));
+        assert_eq!(entity2.id, EntityId::from("organization.acme_corp"));
         assert_eq!(entity2.entity_type, EntityType::new("organization"));
     }
 
@@ -516,8 +512,8 @@ mod tests {
         let entity2: Entity = (&entities[1]).try_into().unwrap();
 
         // Same local ID but different composite IDs due to different entity types
-        assert_eq!(entity1.id, EntityId("person.john_doe".to_string()));
-        assert_eq!(entity2.id, EntityId("organization.john_doe".to_string()));
+        assert_eq!(entity1.id, EntityId::from("person.john_doe"));
+        assert_eq!(entity2.id, EntityId::from("organization.john_doe"));
     }
 
     #[test]
@@ -534,8 +530,8 @@ mod tests {
         let entity2: Entity = (&entities[1]).try_into().unwrap();
 
         // Both should have lowercase entity type in composite ID
-        assert_eq!(entity1.id, EntityId("person.john_doe".to_string()));
-        assert_eq!(entity2.id, EntityId("person.jane_doe".to_string()));
+        assert_eq!(entity1.id, EntityId::from("person.john_doe"));
+        assert_eq!(entity2.id, EntityId::from("person.jane_doe"));
     }
 
     #[test]
@@ -560,42 +556,41 @@ mod tests {
         let entities = parsed.entities();
         let entity: Entity = (&entities[0]).try_into().unwrap();
 
-        assert_eq!(entity.id, EntityId("contract.main_contract".to_string()));
+        assert_eq!(entity.id, EntityId::from("contract.main_contract"));
         assert_eq!(entity.entity_type, EntityType::new("contract"));
         assert_eq!(entity.fields.len(), 7);
 
         // Verify each field type conversion
         assert_eq!(
-            entity.get_field(&FieldId("title".to_string())),
+            entity.get_field(&FieldId::from("title")),
             Some(&FieldValue::String(
                 "Software Development Agreement".to_string()
             ))
         );
         assert_eq!(
-            entity.get_field(&FieldId("value".to_string())),
+            entity.get_field(&FieldId::from("value")),
             Some(&FieldValue::Currency {
                 amount: Decimal::from_str_exact("50000.00").unwrap(),
                 currency: Currency::USD
             })
         );
         assert_eq!(
-            entity.get_field(&FieldId("active".to_string())),
+            entity.get_field(&FieldId::from("active")),
             Some(&FieldValue::Boolean(true))
         );
 
         // Verify list of entity references
-        if let Some(FieldValue::List(parties)) = &entity.get_field(&FieldId("parties".to_string()))
-        {
+        if let Some(FieldValue::List(parties)) = &entity.get_field(&FieldId::from("parties")) {
             assert_eq!(parties.len(), 2);
             assert_eq!(
                 parties[0],
-                FieldValue::Reference(ReferenceValue::Entity(EntityId(
+                FieldValue::Reference(ReferenceValue::Entity(EntityId::from(
                     "person.john_doe".to_string()
                 )))
             );
             assert_eq!(
                 parties[1],
-                FieldValue::Reference(ReferenceValue::Entity(EntityId(
+                FieldValue::Reference(ReferenceValue::Entity(EntityId::from(
                     "organization.acme_corp".to_string()
                 )))
             );
@@ -605,10 +600,10 @@ mod tests {
 
         // Verify field reference
         assert_eq!(
-            entity.get_field(&FieldId("manager_contact".to_string())),
+            entity.get_field(&FieldId::from("manager_contact")),
             Some(&FieldValue::Reference(ReferenceValue::Field(
-                EntityId("person.jane_doe".to_string()),
-                FieldId("email".to_string())
+                EntityId::from("person.jane_doe"),
+                FieldId::from("email")
             )))
         );
     }
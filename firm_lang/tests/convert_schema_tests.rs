@@ -1,10 +1,19 @@
 use firm_core::{
-    EntityType, FieldId,
+    EntityType, FieldId, FieldValue,
     field::FieldType,
-    schema::{EntitySchema, FieldMode},
+    migration::MigrationStep,
+    quantity::Dimension,
+    schema::{
+        Comparison, EntitySchema, FieldDefault, FieldMode, FieldStates, SchemaRule,
+        UnknownFieldPolicy,
+    },
 };
 
-use firm_lang::{convert::SchemaConversionError, parser::parse_source};
+use firm_lang::{
+    convert::{ComposableSchema, SchemaConversionError, SchemaResolver},
+    parser::parse_source,
+};
+use std::collections::HashMap;
 
 #[test]
 fn test_convert_simple_schema() {
@@ -34,12 +43,12 @@ fn test_convert_simple_schema() {
     assert_eq!(schema.fields.len(), 2);
 
     // Check required field
-    let title_field = &schema.fields[&FieldId("title".to_string())];
+    let title_field = &schema.fields[&FieldId::from("title")];
     assert_eq!(title_field.field_type, FieldType::String);
     assert_eq!(title_field.field_mode, FieldMode::Required);
 
     // Check optional field
-    let priority_field = &schema.fields[&FieldId("priority".to_string())];
+    let priority_field = &schema.fields[&FieldId::from("priority")];
     assert_eq!(priority_field.field_type, FieldType::Integer);
     assert_eq!(priority_field.field_mode, FieldMode::Optional);
 }
@@ -78,17 +87,17 @@ fn test_convert_schema_with_various_types() {
     assert_eq!(schema.fields.len(), 3);
 
     // Check currency field
-    let amount_field = &schema.fields[&FieldId("amount".to_string())];
+    let amount_field = &schema.fields[&FieldId::from("amount")];
     assert_eq!(amount_field.field_type, FieldType::Currency);
     assert_eq!(amount_field.field_mode, FieldMode::Required);
 
     // Check boolean field
-    let paid_field = &schema.fields[&FieldId("paid".to_string())];
+    let paid_field = &schema.fields[&FieldId::from("paid")];
     assert_eq!(paid_field.field_type, FieldType::Boolean);
     assert_eq!(paid_field.field_mode, FieldMode::Optional);
 
     // Check datetime field
-    let due_date_field = &schema.fields[&FieldId("due_date".to_string())];
+    let due_date_field = &schema.fields[&FieldId::from("due_date")];
     assert_eq!(due_date_field.field_type, FieldType::DateTime);
     assert_eq!(due_date_field.field_mode, FieldMode::Required);
 }
@@ -150,3 +159,778 @@ fn test_convert_multiple_schemas() {
     assert_eq!(project_schema.fields.len(), 1);
     assert_eq!(invoice_schema.fields.len(), 1);
 }
+
+#[test]
+fn test_resolve_extends_and_includes() {
+    let source = r#"
+        schema timestamps {
+            abstract = true
+
+            field {
+                name = "created"
+                type = "datetime"
+                required = false
+            }
+        }
+
+        schema base_project {
+            field {
+                name = "title"
+                type = "string"
+                required = true
+            }
+        }
+
+        schema client_project {
+            extends = "base_project"
+            includes = ["timestamps"]
+
+            field {
+                name = "client"
+                type = "reference"
+                required = true
+            }
+
+            field {
+                name = "created"
+                type = "datetime"
+                required = true
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+    assert!(schemas[0].is_abstract);
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let resolved = resolver
+        .resolve(&EntityType::new("client_project"))
+        .unwrap();
+
+    let ordered: Vec<&str> = resolved
+        .ordered_fields()
+        .iter()
+        .map(|(id, _)| id.as_str())
+        .collect();
+    assert_eq!(ordered, vec!["title", "client", "created"]);
+
+    // The schema's own definition decides whether an included field is required
+    assert_eq!(
+        resolved.fields[&FieldId::new("created")].field_mode,
+        FieldMode::Required
+    );
+}
+
+#[test]
+fn test_resolve_available_schema() {
+    let source = r#"
+        schema employee {
+            extends = "person"
+            includes = ["metadata"]
+
+            field {
+                name = "title"
+                type = "string"
+                required = true
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let person = EntitySchema::person();
+    let metadata = EntitySchema::metadata_group();
+    let available = HashMap::from([
+        (person.entity_type.clone(), person),
+        (metadata.entity_type.clone(), metadata),
+    ]);
+
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let resolved = resolver.resolve(&EntityType::new("employee")).unwrap();
+
+    assert!(resolved.fields.contains_key(&FieldId::new("name")));
+    assert!(resolved.fields.contains_key(&FieldId::new("title")));
+    assert!(resolved.fields.contains_key(&FieldId::new("created_at")));
+}
+
+#[test]
+fn test_resolve_cycle_error() {
+    let source = r#"
+        schema a {
+            extends = "b"
+        }
+
+        schema b {
+            includes = ["a"]
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let result = resolver.resolve(&EntityType::new("a"));
+
+    match result {
+        Err(SchemaConversionError::SchemaCycle(cycle)) => {
+            assert_eq!(cycle, vec!["a", "b", "a"]);
+        }
+        _ => panic!("Expected SchemaCycle"),
+    }
+}
+
+#[test]
+fn test_resolve_conflicting_field_types_error() {
+    let source = r#"
+        schema base {
+            field {
+                name = "amount"
+                type = "integer"
+            }
+        }
+
+        schema derived {
+            extends = "base"
+
+            field {
+                name = "amount"
+                type = "currency"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let result = resolver.resolve(&EntityType::new("derived"));
+
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::ConflictingFieldTypes(_, _))
+    ));
+}
+
+#[test]
+fn test_resolve_unknown_schema_error() {
+    let source = r#"
+        schema derived {
+            extends = "missing"
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let result = resolver.resolve(&EntityType::new("derived"));
+
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::UnknownSchema(name)) if name == "missing"
+    ));
+}
+
+#[test]
+fn test_convert_field_defaults() {
+    let source = r#"
+        schema ticket {
+            field {
+                name = "is_open"
+                type = "boolean"
+                default = true
+            }
+
+            field {
+                name = "opened_at"
+                type = "datetime"
+                auto = "now"
+                materialize = true
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    let is_open = &schema.fields[&FieldId::new("is_open")];
+    assert_eq!(
+        is_open.default,
+        Some(FieldDefault::Value(FieldValue::Boolean(true)))
+    );
+    assert!(!is_open.materialize_default);
+
+    let opened_at = &schema.fields[&FieldId::new("opened_at")];
+    assert_eq!(opened_at.default, Some(FieldDefault::Now));
+    assert!(opened_at.materialize_default);
+}
+
+#[test]
+fn test_mismatched_default_type_error() {
+    let source = r#"
+        schema ticket {
+            field {
+                name = "priority"
+                type = "integer"
+                default = "high"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidFieldDefault(field, _)) if field == "priority"
+    ));
+}
+
+#[test]
+fn test_convert_field_constraints() {
+    let source = r#"
+        schema product {
+            field {
+                name = "sku"
+                type = "string"
+                required = true
+                pattern = "^[A-Z]{3}-[0-9]+$"
+                max_length = 12
+                unique = true
+            }
+
+            field {
+                name = "rating"
+                type = "float"
+                min = 0
+                max = 5.0
+            }
+
+            field {
+                name = "tags"
+                type = "list"
+                max_items = 3
+            }
+
+            field {
+                name = "supplier_ref"
+                type = "reference"
+                references = "organization"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    let sku = &schema.fields[&FieldId::new("sku")].constraints;
+    assert_eq!(sku.pattern.as_deref(), Some("^[A-Z]{3}-[0-9]+$"));
+    assert_eq!(sku.max_length, Some(12));
+    assert!(sku.unique);
+
+    let rating = &schema.fields[&FieldId::new("rating")].constraints;
+    assert_eq!(rating.min, Some(0.0));
+    assert_eq!(rating.max, Some(5.0));
+
+    let tags = &schema.fields[&FieldId::new("tags")].constraints;
+    assert_eq!(tags.max_items, Some(3));
+
+    let supplier = &schema.fields[&FieldId::new("supplier_ref")].constraints;
+    assert_eq!(supplier.references, Some(EntityType::new("organization")));
+}
+
+#[test]
+fn test_convert_quantity_dimension() {
+    let source = r#"
+        schema plan {
+            field {
+                name = "storage"
+                type = "quantity"
+                dimension = "data"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    let storage = &schema.fields[&FieldId::new("storage")];
+    assert_eq!(storage.field_type, FieldType::Quantity);
+    assert_eq!(storage.constraints.dimension, Some(Dimension::Data));
+}
+
+#[test]
+fn test_unknown_dimension_error() {
+    let source = r#"
+        schema plan {
+            field {
+                name = "storage"
+                type = "quantity"
+                dimension = "volume"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidFieldConstraint(field, _)) if field == "storage"
+    ));
+}
+
+#[test]
+fn test_inapplicable_constraint_error() {
+    let source = r#"
+        schema product {
+            field {
+                name = "name"
+                type = "string"
+                min = 1
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidFieldConstraint(field, _)) if field == "name"
+    ));
+}
+
+#[test]
+fn test_convert_unknown_field_policy() {
+    let source = r#"
+        schema base {
+            unknown_fields = "error"
+        }
+
+        schema derived {
+            extends = "base"
+        }
+
+        schema lenient {
+            unknown_fields = "maybe"
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let parsed_schemas = parsed.schemas();
+    let schemas: Vec<ComposableSchema> = parsed_schemas[..2]
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+    assert_eq!(
+        schemas[0].schema.unknown_fields,
+        Some(UnknownFieldPolicy::Error)
+    );
+
+    // Schemas keep the policy of the schema they extend unless they set their own
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    let resolved = resolver.resolve(&EntityType::new("derived")).unwrap();
+    assert_eq!(resolved.unknown_fields, Some(UnknownFieldPolicy::Error));
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&parsed_schemas[2]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::UnknownFieldPolicy(policy)) if policy == "maybe"
+    ));
+}
+
+#[test]
+fn test_convert_schema_rules() {
+    let source = r#"
+        schema campaign {
+            field {
+                name = "status"
+                type = "string"
+            }
+
+            field {
+                name = "budget"
+                type = "integer"
+            }
+
+            field {
+                name = "start_date"
+                type = "datetime"
+            }
+
+            field {
+                name = "end_date"
+                type = "datetime"
+            }
+
+            rule {
+                require = "budget"
+                when = "status"
+                equals = "approved"
+                message = "Approved campaigns need a budget"
+            }
+
+            rule {
+                compare = "end_date >= start_date"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    assert_eq!(
+        schema.rules,
+        vec![
+            SchemaRule::required_if(
+                FieldId::new("budget"),
+                FieldId::new("status"),
+                Some(FieldValue::String(String::from("approved"))),
+            )
+            .with_message("Approved campaigns need a budget"),
+            SchemaRule::compare(
+                FieldId::new("end_date"),
+                Comparison::GreaterOrEqual,
+                FieldId::new("start_date"),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_rule_error() {
+    let source = r#"
+        schema campaign {
+            rule {
+                compare = "end_date after start_date"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(result, Err(SchemaConversionError::InvalidRule(_))));
+}
+
+#[test]
+fn test_resolve_rule_with_undeclared_field_error() {
+    let source = r#"
+        schema approvals {
+            abstract = true
+
+            rule {
+                require = "approved_by"
+                when = "approved_at"
+            }
+        }
+
+        schema expense {
+            includes = ["approvals"]
+
+            field {
+                name = "approved_at"
+                type = "datetime"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+
+    // The abstract group can refer to fields declared by the schemas that include it
+    assert!(resolver.resolve(&EntityType::new("approvals")).is_ok());
+
+    let result = resolver.resolve(&EntityType::new("expense"));
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidRule(message)) if message.contains("'approved_by'")
+    ));
+}
+
+#[test]
+fn test_convert_field_states() {
+    let source = r#"
+        schema deal {
+            field {
+                name = "status"
+                type = "string"
+                required = true
+                states = ["open", "won", "lost"]
+                transitions = ["open -> won", "open -> lost"]
+                stamp = "status_changed_at"
+            }
+
+            field {
+                name = "status_changed_at"
+                type = "datetime"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    assert_eq!(
+        schema.fields[&FieldId::new("status")].states,
+        Some(
+            FieldStates::new(["open", "won", "lost"])
+                .with_transition("open", "won")
+                .with_transition("open", "lost")
+                .with_stamp(FieldId::new("status_changed_at"))
+        )
+    );
+    assert_eq!(schema.state_field(), Some(&FieldId::new("status")));
+}
+
+#[test]
+fn test_transition_to_unknown_state_error() {
+    let source = r#"
+        schema deal {
+            field {
+                name = "status"
+                type = "string"
+                states = ["open", "won"]
+                transitions = ["open -> closed"]
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidFieldStates(field, _)) if field == "status"
+    ));
+}
+
+#[test]
+fn test_stamp_must_be_declared_datetime_error() {
+    let source = r#"
+        schema deal {
+            field {
+                name = "status"
+                type = "string"
+                states = ["open", "won"]
+                stamp = "closed_on"
+            }
+
+            field {
+                name = "closed_on"
+                type = "date"
+            }
+        }
+
+        schema lead {
+            field {
+                name = "status"
+                type = "string"
+                states = ["new", "qualified"]
+                stamp = "qualified_at"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas: Vec<ComposableSchema> = parsed
+        .schemas()
+        .iter()
+        .map(|s| s.try_into().unwrap())
+        .collect();
+
+    let available = HashMap::new();
+    let mut resolver = SchemaResolver::new(&schemas, &available);
+    for entity_type in ["deal", "lead"] {
+        let result = resolver.resolve(&EntityType::new(entity_type));
+        assert!(matches!(
+            result,
+            Err(SchemaConversionError::InvalidFieldStates(field, _)) if field == "status"
+        ));
+    }
+}
+
+#[test]
+fn test_convert_record_fields() {
+    let source = r#"
+        schema organization {
+            field {
+                name = "address"
+                type = "record"
+                required = true
+
+                field {
+                    name = "street"
+                    type = "string"
+                }
+
+                field {
+                    name = "city"
+                    type = "string"
+                    required = true
+                }
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema: EntitySchema = (&schemas[0]).try_into().unwrap();
+
+    let address = &schema.fields[&FieldId::new("address")];
+    assert_eq!(address.field_type, FieldType::Record);
+
+    let record_fields = address.record_fields.as_ref().unwrap();
+    assert_eq!(record_fields.len(), 2);
+    assert_eq!(
+        record_fields[&FieldId::new("street")].field_mode,
+        FieldMode::Optional
+    );
+    assert_eq!(
+        record_fields[&FieldId::new("city")].field_mode,
+        FieldMode::Required
+    );
+    assert_eq!(record_fields[&FieldId::new("city")].order, 1);
+}
+
+#[test]
+fn test_nested_fields_on_non_record_error() {
+    let source = r#"
+        schema organization {
+            field {
+                name = "name"
+                type = "string"
+
+                field {
+                    name = "first"
+                    type = "string"
+                }
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result: Result<EntitySchema, SchemaConversionError> = (&schemas[0]).try_into();
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidRecordFields(field, _)) if field == "name"
+    ));
+}
+
+#[test]
+fn test_convert_schema_migrations() {
+    let source = r#"
+        schema deal {
+            field {
+                name = "name"
+                type = "string"
+                required = true
+            }
+
+            migration {
+                rename = "title"
+                to = "name"
+            }
+
+            migration {
+                convert = "probability"
+                to = "float"
+            }
+
+            migration {
+                fill = "region"
+                value = "EU"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+    let schema = ComposableSchema::try_from(&schemas[0]).unwrap();
+
+    assert_eq!(schema.migration.entity_type, EntityType::new("deal"));
+    assert_eq!(
+        schema.migration.steps,
+        vec![
+            MigrationStep::Rename {
+                from: FieldId::new("title"),
+                to: FieldId::new("name"),
+            },
+            MigrationStep::Convert {
+                field: FieldId::new("probability"),
+                to: Some(FieldType::Float),
+            },
+            MigrationStep::Fill {
+                field: FieldId::new("region"),
+                value: Some(FieldValue::String(String::from("EU"))),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_migration_error() {
+    let source = r#"
+        schema deal {
+            migration {
+                rename = "title"
+            }
+        }
+    "#;
+
+    let parsed = parse_source(String::from(source), None).unwrap();
+    let schemas = parsed.schemas();
+
+    let result = ComposableSchema::try_from(&schemas[0]);
+    assert!(matches!(
+        result,
+        Err(SchemaConversionError::InvalidMigration(_))
+    ));
+}
@@ -0,0 +1,149 @@
+// Typed entities generated from Firm schemas. Do not edit by hand.
+
+/// An entity of type `person`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Person {
+    pub id: firm_core::EntityId,
+    pub name: String,
+}
+
+impl Person {
+    /// The entity type this struct holds.
+    pub const ENTITY_TYPE: &str = "person";
+}
+
+impl TryFrom<&firm_core::Entity> for Person {
+    type Error = firm_core::schema::ValidationError;
+
+    fn try_from(entity: &firm_core::Entity) -> Result<Self, Self::Error> {
+        firm_core::typed::check_entity_type(entity, &firm_core::EntityType::new(Self::ENTITY_TYPE))?;
+
+        Ok(Self {
+            id: entity.id.clone(),
+            name: firm_core::typed::required_field(entity, &firm_core::FieldId::new("name"))?,
+        })
+    }
+}
+
+impl From<&Person> for firm_core::Entity {
+    fn from(typed: &Person) -> Self {
+        let mut entity = firm_core::Entity::new(typed.id.clone(), firm_core::EntityType::new(Person::ENTITY_TYPE));
+        entity.fields.push((firm_core::FieldId::new("name"), firm_core::typed::TypedValue::to_value(&typed.name)));
+        entity
+    }
+}
+
+/// An entity of type `project_task`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectTask {
+    pub id: firm_core::EntityId,
+    pub name: String,
+    pub assignee_ref: Option<firm_core::typed::EntityRef<Person>>,
+    pub client_ref: Option<firm_core::EntityId>,
+    pub source_ref: Option<firm_core::ReferenceValue>,
+    pub is_completed: Option<bool>,
+    pub priority: Option<i64>,
+    pub progress: Option<f64>,
+    pub budget: Option<(firm_core::field::Decimal, firm_core::field::Currency)>,
+    pub tags: Option<Vec<firm_core::FieldValue>>,
+    pub created_at: Option<firm_core::field::DateTime<firm_core::field::FixedOffset>>,
+    pub due_date: Option<firm_core::field::NaiveDate>,
+    pub estimate: Option<firm_core::temporal::Duration>,
+    pub check_in: Option<firm_core::temporal::Recurrence>,
+    pub effort: Option<firm_core::quantity::Quantity>,
+    pub address: Option<Vec<(firm_core::FieldId, firm_core::FieldValue)>>,
+    pub deliverable: Option<std::path::PathBuf>,
+    pub r#type: Option<String>,
+}
+
+impl ProjectTask {
+    /// The entity type this struct holds.
+    pub const ENTITY_TYPE: &str = "project_task";
+}
+
+impl TryFrom<&firm_core::Entity> for ProjectTask {
+    type Error = firm_core::schema::ValidationError;
+
+    fn try_from(entity: &firm_core::Entity) -> Result<Self, Self::Error> {
+        firm_core::typed::check_entity_type(entity, &firm_core::EntityType::new(Self::ENTITY_TYPE))?;
+        firm_core::typed::check_reference_target(entity, &firm_core::FieldId::new("assignee_ref"), &firm_core::EntityType::new("person"))?;
+        firm_core::typed::check_reference_target(entity, &firm_core::FieldId::new("client_ref"), &firm_core::EntityType::new("organization"))?;
+
+        Ok(Self {
+            id: entity.id.clone(),
+            name: firm_core::typed::required_field(entity, &firm_core::FieldId::new("name"))?,
+            assignee_ref: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("assignee_ref"))?,
+            client_ref: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("client_ref"))?,
+            source_ref: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("source_ref"))?,
+            is_completed: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("is_completed"))?,
+            priority: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("priority"))?,
+            progress: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("progress"))?,
+            budget: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("budget"))?,
+            tags: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("tags"))?,
+            created_at: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("created_at"))?,
+            due_date: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("due_date"))?,
+            estimate: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("estimate"))?,
+            check_in: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("check_in"))?,
+            effort: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("effort"))?,
+            address: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("address"))?,
+            deliverable: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("deliverable"))?,
+            r#type: firm_core::typed::optional_field(entity, &firm_core::FieldId::new("type"))?,
+        })
+    }
+}
+
+impl From<&ProjectTask> for firm_core::Entity {
+    fn from(typed: &ProjectTask) -> Self {
+        let mut entity = firm_core::Entity::new(typed.id.clone(), firm_core::EntityType::new(ProjectTask::ENTITY_TYPE));
+        entity.fields.push((firm_core::FieldId::new("name"), firm_core::typed::TypedValue::to_value(&typed.name)));
+        if let Some(value) = &typed.assignee_ref {
+            entity.fields.push((firm_core::FieldId::new("assignee_ref"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.client_ref {
+            entity.fields.push((firm_core::FieldId::new("client_ref"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.source_ref {
+            entity.fields.push((firm_core::FieldId::new("source_ref"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.is_completed {
+            entity.fields.push((firm_core::FieldId::new("is_completed"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.priority {
+            entity.fields.push((firm_core::FieldId::new("priority"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.progress {
+            entity.fields.push((firm_core::FieldId::new("progress"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.budget {
+            entity.fields.push((firm_core::FieldId::new("budget"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.tags {
+            entity.fields.push((firm_core::FieldId::new("tags"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.created_at {
+            entity.fields.push((firm_core::FieldId::new("created_at"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.due_date {
+            entity.fields.push((firm_core::FieldId::new("due_date"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.estimate {
+            entity.fields.push((firm_core::FieldId::new("estimate"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.check_in {
+            entity.fields.push((firm_core::FieldId::new("check_in"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.effort {
+            entity.fields.push((firm_core::FieldId::new("effort"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.address {
+            entity.fields.push((firm_core::FieldId::new("address"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.deliverable {
+            entity.fields.push((firm_core::FieldId::new("deliverable"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        if let Some(value) = &typed.r#type {
+            entity.fields.push((firm_core::FieldId::new("type"), firm_core::typed::TypedValue::to_value(value)));
+        }
+        entity
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use chrono::{Datelike, Offset, Timelike};
+    use chrono::{Datelike, NaiveDate, Offset, Timelike};
     use firm_lang::parser::{ParsedValue, parse_source};
 
     #[test]
@@ -356,21 +356,18 @@ mod tests {
         let entities = parsed.entities();
         let fields = entities[0].fields();
 
-        if let Ok(ParsedValue::DateTime(dt)) = &fields[0].value() {
-            assert_eq!(dt.naive_local().date().year(), 1990);
-            assert_eq!(dt.naive_local().date().month(), 5);
-            assert_eq!(dt.naive_local().date().day(), 15);
-        } else {
-            panic!("Expected date value");
-        }
-
-        if let Ok(ParsedValue::DateTime(dt)) = &fields[1].value() {
-            assert_eq!(dt.naive_local().date().year(), 2023);
-            assert_eq!(dt.naive_local().date().month(), 1);
-            assert_eq!(dt.naive_local().date().day(), 1);
-        } else {
-            panic!("Expected date value");
-        }
+        assert_eq!(
+            fields[0].value(),
+            Ok(ParsedValue::Date(
+                NaiveDate::from_ymd_opt(1990, 5, 15).unwrap()
+            ))
+        );
+        assert_eq!(
+            fields[1].value(),
+            Ok(ParsedValue::Date(
+                NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+            ))
+        );
     }
 
     #[test]
@@ -392,7 +389,7 @@ mod tests {
 
         // All fields should be dates
         for field in fields {
-            assert!(matches!(field.value(), Ok(ParsedValue::DateTime(_))));
+            assert!(matches!(field.value(), Ok(ParsedValue::Date(_))));
         }
     }
 
@@ -399,4 +399,49 @@ mod tests {
             vec!["user_id", "created_at", "is_active", "metadata_json"]
         );
     }
+
+    #[test]
+    fn test_schema_rules_parsing() {
+        let source = r#"
+            schema task {
+                field {
+                    name = "is_completed"
+                    type = "boolean"
+                }
+
+                rule {
+                    require = "completed_at"
+                    when = "is_completed"
+                    equals = true
+                    message = "Completed tasks need a completion date"
+                }
+
+                rule {
+                    compare = "end_date > start_date"
+                }
+            }
+        "#;
+
+        let parsed = parse_source(String::from(source), None).unwrap();
+        assert!(!parsed.has_error());
+
+        let schemas = parsed.schemas();
+        let schema = &schemas[0];
+
+        // Rules are not mistaken for fields
+        assert_eq!(schema.fields().len(), 1);
+
+        let rules = schema.rules();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].require().unwrap().as_deref(), Some("completed_at"));
+        assert_eq!(rules[0].when().unwrap().as_deref(), Some("is_completed"));
+        assert_eq!(
+            rules[0].message().unwrap().as_deref(),
+            Some("Completed tasks need a completion date")
+        );
+        assert_eq!(
+            rules[1].compare().unwrap().as_deref(),
+            Some("end_date > start_date")
+        );
+    }
 }
@@ -1,5 +1,11 @@
-use firm_core::EntityType;
-use firm_lang::workspace::{Workspace, WorkspaceError};
+use firm_core::{
+    EntityId, EntityType, FieldId, FieldValue,
+    graph::{EntityGraph, SubgraphOptions},
+    migration::Migration,
+    schema::{BuiltinGroup, UnknownFieldPolicy},
+};
+use firm_lang::generate::generate_dsl;
+use firm_lang::workspace::{Workspace, WorkspaceError, WorkspaceOptions};
 
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -335,4 +341,948 @@ organization globo {
         assert_eq!(org_count, 2, "Should have 2 organization entities");
         assert!(build.schemas.len() >= 3, "Should have built-in schemas");
     }
+
+    #[test]
+    fn test_build_extends_builtin_schema() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("person.firm");
+
+        let content = r#"
+schema person {
+    mode = "extend"
+
+    field {
+        name = "linkedin"
+        type = "string"
+        required = true
+    }
+}
+
+person john {
+    name = "John Doe"
+    linkedin = "https://linkedin.com/in/john"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+        let person = build
+            .schemas
+            .iter()
+            .find(|s| s.entity_type == EntityType::new("person"))
+            .unwrap();
+
+        assert!(person.fields.contains_key(&FieldId::new("name")));
+        assert!(person.fields[&FieldId::new("linkedin")].is_required());
+    }
+
+    #[test]
+    fn test_build_reads_durations_written_as_strings() {
+        use firm_core::{FieldValue, temporal::Duration};
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tasks.firm");
+
+        let content = r#"
+task write_report {
+    name = "Write report"
+    estimate = "2h30m"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+        let task = build
+            .entities
+            .iter()
+            .find(|e| e.entity_type == EntityType::new("task"))
+            .unwrap();
+
+        assert_eq!(
+            task.get_field(&FieldId::new("estimate")),
+            Some(&FieldValue::Duration(Duration::from_minutes(150)))
+        );
+    }
+
+    #[test]
+    fn test_build_applies_extension_before_extends() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let employee_path = temp_dir.path().join("a_employee.firm");
+        let person_path = temp_dir.path().join("b_person.firm");
+
+        let employee_content = r#"
+schema employee {
+    extends = "person"
+
+    field {
+        name = "title"
+        type = "string"
+    }
+}
+"#;
+        let person_content = r#"
+schema person {
+    mode = "extend"
+
+    field {
+        name = "linkedin"
+        type = "string"
+    }
+}
+"#;
+        fs::write(&employee_path, employee_content).expect("Should write file");
+        fs::write(&person_path, person_content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_directory(&temp_dir.path().to_path_buf())
+            .expect("Should load directory");
+
+        let build = workspace.build().expect("Build should succeed");
+        let employee = build
+            .schemas
+            .iter()
+            .find(|s| s.entity_type == EntityType::new("employee"))
+            .unwrap();
+
+        assert!(employee.fields.contains_key(&FieldId::new("name")));
+        assert!(employee.fields.contains_key(&FieldId::new("linkedin")));
+        assert!(employee.fields.contains_key(&FieldId::new("title")));
+    }
+
+    #[test]
+    fn test_build_overrides_builtin_schema() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("person.firm");
+
+        let content = r#"
+schema person {
+    mode = "override"
+
+    field {
+        name = "handle"
+        type = "string"
+        required = true
+    }
+}
+
+person john {
+    handle = "john"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+        let person = build
+            .schemas
+            .iter()
+            .find(|s| s.entity_type == EntityType::new("person"))
+            .unwrap();
+
+        assert_eq!(person.fields.len(), 1);
+        assert!(person.fields.contains_key(&FieldId::new("handle")));
+    }
+
+    #[test]
+    fn test_build_fails_redefining_builtin_schema() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("person.firm");
+
+        let content = r#"
+schema person {
+    field {
+        name = "handle"
+        type = "string"
+    }
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::SchemaConflict(path, message)) => {
+                assert_eq!(path, file_path);
+                assert!(message.contains("built-in"));
+                assert!(message.contains("mode = \"extend\""));
+            }
+            _ => panic!("Expected SchemaConflict"),
+        }
+    }
+
+    #[test]
+    fn test_build_fails_duplicate_custom_schemas() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let first_path = temp_dir.path().join("a.firm");
+        let second_path = temp_dir.path().join("b.firm");
+
+        let content = r#"
+schema invoice {
+    field {
+        name = "number"
+        type = "integer"
+    }
+}
+"#;
+        fs::write(&first_path, content).expect("Should write file");
+        fs::write(&second_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_directory(&temp_dir.path().to_path_buf())
+            .expect("Should load directory");
+
+        match workspace.build() {
+            Err(WorkspaceError::SchemaConflict(path, message)) => {
+                assert_eq!(path, second_path);
+                assert!(message.contains(&first_path.display().to_string()));
+            }
+            _ => panic!("Expected SchemaConflict"),
+        }
+    }
+
+    #[test]
+    fn test_build_fails_extending_with_conflicting_field_type() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("person.firm");
+
+        let content = r#"
+schema person {
+    mode = "extend"
+
+    field {
+        name = "name"
+        type = "integer"
+    }
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::SchemaConflict(_, message)) => {
+                assert!(message.contains("field 'name'"));
+                assert!(message.contains("built-in"));
+            }
+            _ => panic!("Expected SchemaConflict"),
+        }
+    }
+
+    #[test]
+    fn test_build_extracted_subgraph() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("accounts.firm");
+
+        let content = r#"
+organization acme {
+    name = "Acme"
+}
+
+account acme {
+    name = "Acme account"
+    organization_ref = organization.acme
+}
+
+person jane {
+    name = "Jane"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+        let build = workspace.build().expect("Should build");
+
+        let mut graph = EntityGraph::new();
+        graph.add_entities(build.entities).unwrap();
+        graph.build();
+
+        // The account's required organization is left out, but its reference is kept
+        let subgraph = graph
+            .subgraph(
+                &[EntityId::new("account.acme")],
+                &SubgraphOptions::default().with_depth(0),
+            )
+            .unwrap();
+        let dangling = subgraph.dangling_references();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].target_id, EntityId::new("organization.acme"));
+
+        // The extracted DSL builds as a workspace of its own
+        let entities: Vec<_> = subgraph.entities().into_iter().cloned().collect();
+        let extracted_path = temp_dir.path().join("extracted").join("acme.firm");
+        fs::create_dir_all(extracted_path.parent().unwrap()).unwrap();
+        fs::write(&extracted_path, generate_dsl(&entities)).expect("Should write file");
+
+        let mut extracted = Workspace::new();
+        extracted
+            .load_file(&extracted_path, &PathBuf::new())
+            .expect("Should load extracted file");
+        let extracted_build = extracted.build().expect("Extracted DSL should build");
+
+        assert_eq!(extracted_build.entities, entities);
+    }
+
+    #[test]
+    fn test_build_without_builtin_group() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("person.firm");
+
+        let content = r#"
+schema person {
+    field {
+        name = "handle"
+        type = "string"
+        required = true
+    }
+}
+
+person john {
+    handle = "john"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let options = WorkspaceOptions::default().without_builtin_group(BuiltinGroup::Core);
+        let mut workspace = Workspace::with_options(options);
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+
+        assert!(
+            !build
+                .schemas
+                .iter()
+                .any(|s| s.entity_type == EntityType::new("organization"))
+        );
+        assert!(
+            build
+                .schemas
+                .iter()
+                .any(|s| s.entity_type == EntityType::new("task"))
+        );
+    }
+
+    #[test]
+    fn test_build_excludes_abstract_schemas() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("schemas.firm");
+
+        let content = r#"
+schema timestamps {
+    abstract = true
+
+    field {
+        name = "created"
+        type = "datetime"
+    }
+}
+
+schema invoice {
+    includes = ["timestamps", "metadata"]
+
+    field {
+        name = "number"
+        type = "integer"
+        required = true
+    }
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+
+        assert!(
+            !build
+                .schemas
+                .iter()
+                .any(|s| s.entity_type == EntityType::new("timestamps"))
+        );
+
+        let invoice = build
+            .schemas
+            .iter()
+            .find(|s| s.entity_type == EntityType::new("invoice"))
+            .unwrap();
+        assert!(invoice.fields.contains_key(&FieldId::new("created")));
+        assert!(invoice.fields.contains_key(&FieldId::new("notes")));
+    }
+
+    #[test]
+    fn test_build_applies_defaults() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tasks.firm");
+
+        let content = r#"
+task write_docs {
+    name = "Write docs"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Build should succeed");
+        let task = &build.entities[0];
+
+        assert_eq!(
+            task.get_field(&FieldId::new("is_completed")),
+            Some(&FieldValue::Boolean(false))
+        );
+
+        // Materialized defaults are written when entities are created, not when building
+        assert_eq!(task.get_field(&FieldId::new("created_at")), None);
+    }
+
+    #[test]
+    fn test_build_fails_constraint_violation() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("sales.firm");
+
+        let content = r#"
+schema deal {
+    field {
+        name = "probability"
+        type = "integer"
+        min = 0
+        max = 100
+    }
+}
+
+deal big_deal {
+    probability = 250
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::ValidationError(_, message)) => {
+                assert!(message.contains("ValueOutOfRange"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_build_fails_duplicate_unique_value() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("organizations.firm");
+
+        let content = r#"
+schema supplier {
+    field {
+        name = "vat_id"
+        type = "string"
+        unique = true
+    }
+}
+
+supplier acme {
+    vat_id = "US123456789"
+}
+
+supplier acme_inc {
+    vat_id = "US123456789"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::ValidationError(path, message)) => {
+                assert_eq!(path, file_path);
+                assert!(message.contains("DuplicateValue"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_build_warns_builtin_constraint_violations() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("sales.firm");
+
+        let content = r#"
+organization acme {
+    name = "Acme"
+    vat_id = "US123456789"
+}
+
+organization acme_inc {
+    name = "Acme Inc."
+    vat_id = "US123456789"
+}
+
+opportunity big_deal {
+    source_ref = organization.acme
+    name = "Big deal"
+    status = "open"
+    probability = 250
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Should build with warnings");
+        let warning_fields: Vec<Option<FieldId>> = build
+            .warnings
+            .iter()
+            .map(|warning| warning.field.clone())
+            .collect();
+
+        assert!(warning_fields.contains(&Some(FieldId::new("probability"))));
+        assert!(warning_fields.contains(&Some(FieldId::new("vat_id"))));
+    }
+
+    #[test]
+    fn test_build_warns_unusable_exchange_rates() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("rates.firm");
+
+        let content = r#"
+exchange_rate eur_dkk {
+    currency = "EUR"
+    rate = 7.45 DKK
+    valid_from = 2025-01-01
+}
+
+exchange_rate free_dkk {
+    currency = "EUR"
+    rate = 0 DKK
+    valid_from = 2025-01-01
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let options = WorkspaceOptions::default().with_builtin_group(BuiltinGroup::Finance);
+        let mut workspace = Workspace::with_options(options);
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Should build with warnings");
+
+        assert_eq!(build.warnings.len(), 1);
+        assert_eq!(
+            build.warnings[0].entity_id,
+            Some(EntityId::new("exchange_rate.free_dkk"))
+        );
+        assert_eq!(build.warnings[0].field, Some(FieldId::new("rate")));
+    }
+
+    #[test]
+    fn test_build_warns_unknown_fields() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tasks.firm");
+
+        let content = r#"
+task write_docs {
+    name = "Write docs"
+    asignee_ref = person.john
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Should build with warnings");
+        assert_eq!(build.warnings.len(), 1);
+        assert_eq!(build.warnings[0].field, Some(FieldId::new("asignee_ref")));
+        assert!(
+            build.warnings[0]
+                .message
+                .contains("Did you mean 'assignee_ref'?")
+        );
+    }
+
+    #[test]
+    fn test_build_fails_unknown_fields_when_strict() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tasks.firm");
+
+        let content = r#"
+task write_docs {
+    name = "Write docs"
+    asignee_ref = person.john
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let options = WorkspaceOptions::default().with_unknown_fields(UnknownFieldPolicy::Error);
+        let mut workspace = Workspace::with_options(options);
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::ValidationError(_, message)) => {
+                assert!(message.contains("UnknownField"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_build_schema_unknown_fields_overrides_workspace() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("notes.firm");
+
+        let content = r#"
+schema note {
+    unknown_fields = "allow"
+
+    field {
+        name = "title"
+        type = "string"
+        required = true
+    }
+}
+
+note scratch {
+    title = "Scratch"
+    colour = "yellow"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let options = WorkspaceOptions::default().with_unknown_fields(UnknownFieldPolicy::Error);
+        let mut workspace = Workspace::with_options(options);
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Should build");
+        assert!(build.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_build_fails_rule_violation() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("campaigns.firm");
+
+        let content = r#"
+schema campaign {
+    field {
+        name = "status"
+        type = "string"
+        required = true
+    }
+    field {
+        name = "budget"
+        type = "integer"
+        required = false
+    }
+    rule {
+        require = "budget"
+        when = "status"
+        equals = "approved"
+        message = "Approved campaigns need a budget"
+    }
+}
+
+campaign launch {
+    status = "approved"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::ValidationError(_, message)) => {
+                assert!(message.contains("Approved campaigns need a budget"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_build_warns_builtin_rule_violation() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tasks.firm");
+
+        let content = r#"
+task write_docs {
+    name = "Write docs"
+    is_completed = true
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let build = workspace.build().expect("Should build with warnings");
+        assert_eq!(build.warnings.len(), 1);
+        assert_eq!(
+            build.warnings[0].message,
+            "Completed tasks need a completion date"
+        );
+    }
+
+    #[test]
+    fn test_build_fails_invalid_state() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("deals.firm");
+
+        let content = r#"
+schema deal {
+    field {
+        name = "status"
+        type = "string"
+        required = true
+        states = ["open", "won", "lost"]
+    }
+}
+
+deal big_deal {
+    status = "pending"
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        match workspace.build() {
+            Err(WorkspaceError::ValidationError(_, message)) => {
+                assert!(message.contains("InvalidState"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_find_entity_source() {
+        let mut workspace = Workspace::new();
+        workspace
+            .load_directory(&PathBuf::from("../example"))
+            .expect("Should load example workspace");
+
+        let (path, _) = workspace
+            .find_entity_source(&EntityId::new("opportunity.acme_scaling_pilot"))
+            .expect("Should find entity source");
+        assert!(path.ends_with("sales/acme_corp.firm"));
+    }
+
+    #[test]
+    fn test_migrate_declared_migrations() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("deals.firm");
+
+        let content = r#"
+schema deal {
+    field {
+        name = "name"
+        type = "string"
+        required = true
+    }
+
+    field {
+        name = "probability"
+        type = "float"
+    }
+
+    field {
+        name = "region"
+        type = "string"
+        required = true
+        default = "EU"
+    }
+
+    migration {
+        rename = "title"
+        to = "name"
+    }
+
+    migration {
+        convert = "probability"
+    }
+
+    migration {
+        fill = "region"
+    }
+}
+
+// Our biggest deal
+deal big_deal {
+    title = "Big deal" // Keep this comment
+    probability = 50
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let migrations = workspace
+            .declared_migrations()
+            .expect("Should collect migrations");
+        let migrated_files = workspace.migrate(&migrations).expect("Should migrate");
+
+        assert_eq!(migrated_files.len(), 1);
+        assert_eq!(
+            migrated_files[0].entities,
+            vec![EntityId::new("deal.big_deal")]
+        );
+        assert!(
+            migrated_files[0]
+                .migrated
+                .contains("    name = \"Big deal\" // Keep this comment\n    probability = 50.0\n    region = \"EU\"\n}")
+        );
+
+        // Migrating the migrated file again changes nothing
+        fs::write(&file_path, &migrated_files[0].migrated).expect("Should write file");
+        let mut migrated_workspace = Workspace::new();
+        migrated_workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        assert!(migrated_workspace.build().is_ok());
+        assert!(
+            migrated_workspace
+                .migrate(&migrations)
+                .expect("Should migrate")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_migrate_fails_conversion() {
+        use std::fs;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("deals.firm");
+
+        let content = r#"
+schema deal {
+    field {
+        name = "probability"
+        type = "integer"
+    }
+}
+
+deal big_deal {
+    probability = 0.5
+}
+"#;
+        fs::write(&file_path, content).expect("Should write file");
+
+        let mut workspace = Workspace::new();
+        workspace
+            .load_file(&file_path, &PathBuf::new())
+            .expect("Should load file");
+
+        let migration = Migration::new(EntityType::new("deal"))
+            .with_conversion(FieldId::new("probability"), None);
+
+        match workspace.migrate(&[migration]) {
+            Err(WorkspaceError::MigrationError(_, message)) => {
+                assert!(message.contains("big_deal"));
+            }
+            _ => panic!("Expected MigrationError"),
+        }
+    }
 }